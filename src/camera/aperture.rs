@@ -0,0 +1,132 @@
+use crate::drawing::canvas::Canvas;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+//  The shape of the lens opening. Out-of-focus highlights take this shape,
+//    so samples fill it uniformly within [-1, 1] on each axis.
+#[derive(Clone)]
+pub enum Aperture {
+  Circle,
+  Polygon { sides: usize, rotation: f64 },
+  //  Opening where the mask's luminance is, weighted by it.
+  Mask { canvas: Canvas, cdf: Vec<f64> },
+}
+
+//  Instantiations
+impl Aperture {
+  pub fn polygon(sides: usize, rotation: f64) -> Self {
+    if sides < 3 {
+      panic!("A polygonal aperture needs at least three sides.");
+    }
+    Aperture::Polygon { sides, rotation }
+  }
+
+  pub fn mask(canvas: Canvas) -> Self {
+    let mut running = 0.0;
+    let mut cdf = Vec::with_capacity(canvas.width * canvas.height);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        running += canvas.pixel_at(x, y).luminance().max(0.0);
+        cdf.push(running);
+      }
+    }
+    if running <= 0.0 {
+      panic!("An aperture mask must let some light through.");
+    }
+    cdf.iter_mut().for_each(|value| *value /= running);
+    Aperture::Mask { canvas, cdf }
+  }
+}
+
+//  Methods
+impl Aperture {
+  pub fn sample(&self, u1: f64, u2: f64) -> (f64, f64) {
+    match self {
+      Aperture::Circle => {
+        let (point, _) = sampling::concentric_disk(u1, u2);
+        (point.x, point.y)
+      }
+      Aperture::Polygon { sides, rotation } => {
+        //  Each side spans an equal-area triangle with the center.
+        let scaled = u1 * *sides as f64;
+        let sector = (scaled.floor() as usize).min(sides - 1);
+        let u1 = scaled - sector as f64;
+        let step = 2.0 * PI / *sides as f64;
+        let start = rotation + sector as f64 * step;
+        let corners = [
+          Tuple::point(0.0, 0.0, 0.0),
+          Tuple::point(start.cos(), start.sin(), 0.0),
+          Tuple::point((start + step).cos(), (start + step).sin(), 0.0),
+        ];
+        let (point, _) = sampling::uniform_triangle(&corners, u1, u2);
+        (point.x, point.y)
+      }
+      Aperture::Mask { canvas, cdf } => {
+        let index = cdf.partition_point(|&bound| bound <= u1).min(cdf.len() - 1);
+        let lower = if index == 0 { 0.0 } else { cdf[index - 1] };
+        let fx = ((u1 - lower) / (cdf[index] - lower)).clamp(0.0, 1.0);
+        let (x, y) = (index % canvas.width, index / canvas.width);
+        (
+          2.0 * (x as f64 + fx) / canvas.width as f64 - 1.0,
+          1.0 - 2.0 * (y as f64 + u2) / canvas.height as f64,
+        )
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod aperture_tests {
+  use super::*;
+  use crate::drawing::color::Color;
+  use crate::math::random::Pcg32;
+
+  fn samples(aperture: &Aperture, count: usize) -> Vec<(f64, f64)> {
+    let mut rng = Pcg32::new(9, 0);
+    (0..count)
+      .map(|_| aperture.sample(rng.next_f64(), rng.next_f64()))
+      .collect()
+  }
+
+  #[test]
+  fn circular_samples_fill_the_unit_disk() {
+    let points = samples(&Aperture::Circle, 2000);
+
+    assert!(points.iter().all(|(x, y)| x * x + y * y <= 1.0));
+    let inner = points.iter().filter(|(x, y)| x * x + y * y <= 0.25).count();
+    assert!((inner as f64 / 2000.0 - 0.25).abs() < 0.03);
+  }
+
+  #[test]
+  fn polygonal_samples_stay_inside_the_polygon() {
+    let sides = 6;
+    let aperture = Aperture::polygon(sides, 0.0);
+    let apothem = (PI / sides as f64).cos();
+
+    for (x, y) in samples(&aperture, 2000) {
+      for side in 0..sides {
+        let middle = (side as f64 + 0.5) * 2.0 * PI / sides as f64;
+        assert!(x * middle.cos() + y * middle.sin() <= apothem + 1e-9);
+      }
+    }
+  }
+
+  #[test]
+  #[should_panic]
+  fn a_polygon_needs_three_sides() {
+    Aperture::polygon(2, 0.0);
+  }
+
+  #[test]
+  fn masked_samples_land_on_the_open_pixels() {
+    let mut canvas = Canvas::new(2, 2);
+    canvas.write_pixel(1, 0, Color::white());
+    let aperture = Aperture::mask(canvas);
+
+    for (x, y) in samples(&aperture, 500) {
+      assert!((0.0..=1.0).contains(&x));
+      assert!((0.0..=1.0).contains(&y));
+    }
+  }
+}
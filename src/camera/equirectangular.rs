@@ -0,0 +1,83 @@
+use super::view::{Camera, View};
+use crate::math::ray::Ray;
+use crate::texture::uv_mapping::spherical_direction;
+
+//  A full-sphere panorama laid out like an equirectangular environment map,
+//    with the view direction at the center of the image and up at the top.
+#[derive(Clone, Debug)]
+pub struct EquirectangularCamera {
+  pub hsize: usize,
+  pub vsize: usize,
+  pub view: View,
+}
+
+//  Instantiations
+impl EquirectangularCamera {
+  pub fn new(hsize: usize, vsize: usize) -> Self {
+    Self {
+      hsize,
+      vsize,
+      view: View::default(),
+    }
+  }
+}
+
+impl Camera for EquirectangularCamera {
+  fn hsize(&self) -> usize {
+    self.hsize
+  }
+
+  fn vsize(&self) -> usize {
+    self.vsize
+  }
+
+  //  The spherical mapping faces +z at its center and turns toward -x as u
+  //    grows, which is the view's forward and right.
+  fn ray(&self, x: f64, y: f64, _u1: f64, _u2: f64) -> Option<Ray> {
+    let local = spherical_direction(x / self.hsize as f64, 1.0 - y / self.vsize as f64);
+    let direction = self.view.to_world(-local.x, local.y, local.z);
+    Some(Ray::new(self.view.origin.clone(), direction))
+  }
+}
+
+#[cfg(test)]
+mod equirectangular_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::tuple::Tuple;
+
+  #[test]
+  fn the_panorama_covers_the_whole_sphere() {
+    let camera = EquirectangularCamera::new(200, 100);
+    let cases = [
+      ((100.0, 50.0), Tuple::vector(0.0, 0.0, -1.0)),
+      ((150.0, 50.0), Tuple::vector(-1.0, 0.0, 0.0)),
+      ((50.0, 50.0), Tuple::vector(1.0, 0.0, 0.0)),
+      ((0.0, 50.0), Tuple::vector(0.0, 0.0, 1.0)),
+      ((100.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+      ((100.0, 100.0), Tuple::vector(0.0, -1.0, 0.0)),
+    ];
+
+    for ((x, y), expected) in cases {
+      let ray = camera.ray(x, y, 0.5, 0.5).unwrap();
+
+      assert_feq!(ray.direction, expected);
+    }
+  }
+
+  #[test]
+  fn the_panorama_follows_the_view() {
+    let mut camera = EquirectangularCamera::new(200, 100);
+    camera.view = View::look_at(
+      Tuple::point(1.0, 2.0, 3.0),
+      Tuple::point(2.0, 2.0, 3.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    );
+
+    let ray = camera.ray(100.0, 50.0, 0.5, 0.5).unwrap();
+
+    assert_feq!(ray.origin, Tuple::point(1.0, 2.0, 3.0));
+    assert_feq!(ray.direction, Tuple::vector(1.0, 0.0, 0.0));
+  }
+}
@@ -0,0 +1,104 @@
+use super::view::{Camera, View};
+use crate::math::ray::Ray;
+
+//  An equidistant fisheye: the angle from the view direction grows evenly
+//    with distance from the image center, reaching half the field of view at
+//    the edge of the inscribed circle. Pixels outside the circle see nothing.
+#[derive(Clone, Debug)]
+pub struct FisheyeCamera {
+  pub hsize: usize,
+  pub vsize: usize,
+  pub field_of_view: f64,
+  pub view: View,
+}
+
+//  Instantiations
+impl FisheyeCamera {
+  pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+    Self {
+      hsize,
+      vsize,
+      field_of_view,
+      view: View::default(),
+    }
+  }
+}
+
+impl Camera for FisheyeCamera {
+  fn hsize(&self) -> usize {
+    self.hsize
+  }
+
+  fn vsize(&self) -> usize {
+    self.vsize
+  }
+
+  fn ray(&self, x: f64, y: f64, _u1: f64, _u2: f64) -> Option<Ray> {
+    let radius = self.hsize.min(self.vsize) as f64 / 2.0;
+    let offset_x = (x - self.hsize as f64 / 2.0) / radius;
+    let offset_y = (self.vsize as f64 / 2.0 - y) / radius;
+    let distance = offset_x.hypot(offset_y);
+    if distance > 1.0 {
+      return None;
+    }
+    let theta = distance * self.field_of_view / 2.0;
+    let (sin_theta, cos_theta) = theta.sin_cos();
+    let (cos_phi, sin_phi) = if distance > 0.0 {
+      (offset_x / distance, offset_y / distance)
+    } else {
+      (1.0, 0.0)
+    };
+    let direction = self
+      .view
+      .to_world(sin_theta * cos_phi, sin_theta * sin_phi, cos_theta);
+    Some(Ray::new(self.view.origin.clone(), direction))
+  }
+}
+
+#[cfg(test)]
+mod fisheye_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::tuple::Tuple;
+  use std::f64::consts::PI;
+
+  #[test]
+  fn the_center_looks_along_the_view() {
+    let camera = FisheyeCamera::new(100, 100, PI);
+
+    let ray = camera.ray(50.0, 50.0, 0.5, 0.5).unwrap();
+
+    assert_feq!(ray.direction, Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn the_edge_of_the_circle_is_at_half_the_field_of_view() {
+    let camera = FisheyeCamera::new(100, 100, PI);
+
+    let right = camera.ray(100.0, 50.0, 0.5, 0.5).unwrap();
+    let top = camera.ray(50.0, 0.0, 0.5, 0.5).unwrap();
+
+    assert_feq!(right.direction, Tuple::vector(-1.0, 0.0, 0.0));
+    assert_feq!(top.direction, Tuple::vector(0.0, 1.0, 0.0));
+  }
+
+  #[test]
+  fn angles_grow_evenly_from_the_center() {
+    let camera = FisheyeCamera::new(100, 100, PI);
+
+    let ray = camera.ray(75.0, 50.0, 0.5, 0.5).unwrap();
+
+    assert_feq!(
+      ray.direction.dot(Tuple::vector(0.0, 0.0, -1.0)),
+      (PI / 4.0).cos()
+    );
+  }
+
+  #[test]
+  fn corners_lie_outside_the_image_circle() {
+    let camera = FisheyeCamera::new(100, 100, PI);
+
+    assert!(camera.ray(0.5, 0.5, 0.5, 0.5).is_none());
+  }
+}
@@ -0,0 +1,8 @@
+pub mod aperture;
+pub mod equirectangular;
+pub mod fisheye;
+pub mod moving;
+pub mod orthographic;
+pub mod perspective;
+pub mod stereo;
+pub mod view;
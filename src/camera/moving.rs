@@ -0,0 +1,135 @@
+use super::view::Camera;
+use crate::math::motion::AnimatedTransform;
+use crate::math::ray::Ray;
+
+//  A camera carried along a keyframed path while the shutter is open. The
+//    motion is applied on top of the wrapped camera's own view, and the
+//    renderer spreads each pixel's samples over the shutter interval.
+#[derive(Clone, Debug)]
+pub struct MovingCamera<C: Camera> {
+  pub camera: C,
+  pub motion: AnimatedTransform,
+  pub shutter_open: f64,
+  pub shutter_close: f64,
+}
+
+//  Instantiations
+impl<C: Camera> MovingCamera<C> {
+  pub fn new(camera: C, motion: AnimatedTransform, shutter_open: f64, shutter_close: f64) -> Self {
+    if shutter_close < shutter_open {
+      panic!("The shutter cannot close before it opens.");
+    }
+    Self {
+      camera,
+      motion,
+      shutter_open,
+      shutter_close,
+    }
+  }
+}
+
+impl<C: Camera> Camera for MovingCamera<C> {
+  fn hsize(&self) -> usize {
+    self.camera.hsize()
+  }
+
+  fn vsize(&self) -> usize {
+    self.camera.vsize()
+  }
+
+  fn ray(&self, x: f64, y: f64, u1: f64, u2: f64) -> Option<Ray> {
+    self.ray_at(x, y, u1, u2, self.shutter_open)
+  }
+
+  fn shutter(&self) -> (f64, f64) {
+    (self.shutter_open, self.shutter_close)
+  }
+
+  fn ray_at(&self, x: f64, y: f64, u1: f64, u2: f64, time: f64) -> Option<Ray> {
+    let ray = self.camera.ray_at(x, y, u1, u2, time)?;
+    Some(self.motion.at(time).apply_ray(&ray))
+  }
+}
+
+#[cfg(test)]
+mod moving_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::camera::orthographic::OrthographicCamera;
+  use crate::camera::view::View;
+  use crate::drawing::color::Color;
+  use crate::integrator::path_tracer::PathTracer;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::motion::RigidTransform;
+  use crate::math::sampler::RandomSampler;
+  use crate::math::tuple::Tuple;
+
+  fn looking_down() -> OrthographicCamera {
+    let mut camera = OrthographicCamera::new(4, 4, 1.0);
+    camera.view = View::look_at(
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::origin(),
+      Tuple::vector(0.0, 0.0, 1.0),
+    );
+    camera
+  }
+
+  fn sinking() -> MovingCamera<OrthographicCamera> {
+    MovingCamera::new(
+      looking_down(),
+      AnimatedTransform::between(
+        RigidTransform::identity(),
+        RigidTransform::translation(0.0, -2.0, 0.0),
+      ),
+      0.0,
+      1.0,
+    )
+  }
+
+  #[test]
+  fn rays_follow_the_camera_through_the_shutter_interval() {
+    let camera = sinking();
+
+    let early = camera.ray_at(2.0, 2.0, 0.5, 0.5, 0.0).unwrap();
+    let late = camera.ray_at(2.0, 2.0, 0.5, 0.5, 0.75).unwrap();
+
+    assert_feq!(early.origin, Tuple::point(0.0, 1.0, 0.0));
+    assert_feq!(late.origin, Tuple::point(0.0, -0.5, 0.0));
+    assert_feq!(late.time, 0.75);
+  }
+
+  #[test]
+  fn a_still_camera_exposes_an_instant() {
+    assert_feq!(looking_down().shutter().0, 0.0);
+    assert_feq!(looking_down().shutter().1, 0.0);
+    assert_feq!(sinking().shutter().1, 1.0);
+  }
+
+  #[test]
+  #[should_panic]
+  fn a_shutter_that_closes_before_it_opens_should_panic() {
+    let _camera = MovingCamera::new(
+      looking_down(),
+      AnimatedTransform::between(RigidTransform::identity(), RigidTransform::identity()),
+      1.0,
+      0.0,
+    );
+  }
+
+  #[test]
+  fn renders_average_over_the_shutter_interval() {
+    //  The camera passes through a glowing floor halfway through the
+    //    exposure and sees only darkness beneath it.
+    let mut scene = Planes::default();
+    scene.add(0.0, 1.0, PbrMaterial::emissive(Color::white()));
+    let mut tracer = PathTracer::new();
+    tracer.max_depth = 1;
+
+    let image = tracer.render_camera(&scene, &sinking(), &RandomSampler::new(256));
+
+    let pixel = image.pixel_at(1, 2);
+    assert!((pixel.red - 0.5).abs() < 0.1, "{:?}", pixel);
+  }
+}
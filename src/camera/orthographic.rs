@@ -0,0 +1,86 @@
+use super::view::{Camera, View};
+use crate::math::ray::Ray;
+
+//  Parallel rays along the view direction, for measured drawings. Width is
+//    the extent of the image in world units.
+#[derive(Clone, Debug)]
+pub struct OrthographicCamera {
+  pub hsize: usize,
+  pub vsize: usize,
+  pub width: f64,
+  pub view: View,
+}
+
+//  Instantiations
+impl OrthographicCamera {
+  pub fn new(hsize: usize, vsize: usize, width: f64) -> Self {
+    Self {
+      hsize,
+      vsize,
+      width,
+      view: View::default(),
+    }
+  }
+}
+
+//  Methods
+impl OrthographicCamera {
+  pub fn pixel_size(&self) -> f64 {
+    self.width / self.hsize as f64
+  }
+}
+
+impl Camera for OrthographicCamera {
+  fn hsize(&self) -> usize {
+    self.hsize
+  }
+
+  fn vsize(&self) -> usize {
+    self.vsize
+  }
+
+  fn ray(&self, x: f64, y: f64, _u1: f64, _u2: f64) -> Option<Ray> {
+    let pixel_size = self.pixel_size();
+    let offset_x = (x - self.hsize as f64 / 2.0) * pixel_size;
+    let offset_y = (self.vsize as f64 / 2.0 - y) * pixel_size;
+    let origin = self.view.origin.clone() + self.view.to_world(offset_x, offset_y, 0.0);
+    Some(Ray::new(origin, self.view.forward.clone()))
+  }
+}
+
+#[cfg(test)]
+mod orthographic_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::tuple::Tuple;
+
+  #[test]
+  fn every_ray_travels_in_the_view_direction() {
+    let mut camera = OrthographicCamera::new(40, 20, 8.0);
+    camera.view = View::look_at(
+      Tuple::point(0.0, 10.0, 0.0),
+      Tuple::origin(),
+      Tuple::vector(0.0, 0.0, 1.0),
+    );
+
+    for (x, y) in [(0.5, 0.5), (20.0, 10.0), (39.5, 3.5)] {
+      let ray = camera.ray(x, y, 0.5, 0.5).unwrap();
+
+      assert_feq!(ray.direction, Tuple::vector(0.0, -1.0, 0.0));
+      assert_feq!(ray.origin.y, 10.0);
+    }
+  }
+
+  #[test]
+  fn pixels_map_evenly_across_the_width() {
+    let camera = OrthographicCamera::new(40, 20, 8.0);
+
+    let center = camera.ray(20.0, 10.0, 0.5, 0.5).unwrap();
+    let corner = camera.ray(0.0, 0.0, 0.5, 0.5).unwrap();
+
+    assert_feq!(camera.pixel_size(), 0.2);
+    assert_feq!(center.origin, Tuple::point(0.0, 0.0, 0.0));
+    assert_feq!(corner.origin, Tuple::point(4.0, 2.0, 0.0));
+  }
+}
@@ -0,0 +1,277 @@
+use super::aperture::Aperture;
+use super::view::{Camera, View};
+use crate::integrator::scene::Scene;
+use crate::math::ray::Ray;
+use crate::math::tuple::Tuple;
+
+//  Pixel coordinates are continuous, with pixel (x, y) covering [x, x + 1)
+//    and [y, y + 1) and rows counting down from the top. With no aperture
+//    this is a pinhole camera; otherwise a thin lens that keeps the plane
+//    at focal_distance sharp.
+#[derive(Clone)]
+pub struct PerspectiveCamera {
+  pub hsize: usize,
+  pub vsize: usize,
+  pub field_of_view: f64,
+  pub aperture_radius: f64,
+  pub focal_distance: f64,
+  pub aperture: Aperture,
+  pub view: View,
+  half_width: f64,
+  half_height: f64,
+}
+
+//  Instantiations
+impl PerspectiveCamera {
+  //  Starts at the origin looking toward -z.
+  pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+    let half_view = (field_of_view / 2.0).tan();
+    let aspect = hsize as f64 / vsize as f64;
+    let (half_width, half_height) = if aspect >= 1.0 {
+      (half_view, half_view / aspect)
+    } else {
+      (half_view * aspect, half_view)
+    };
+    Self {
+      hsize,
+      vsize,
+      field_of_view,
+      aperture_radius: 0.0,
+      focal_distance: 1.0,
+      aperture: Aperture::Circle,
+      view: View::default(),
+      half_width,
+      half_height,
+    }
+  }
+}
+
+//  Methods
+impl PerspectiveCamera {
+  pub fn look_at(&mut self, from: Tuple, to: Tuple, up: Tuple) {
+    self.view = View::look_at(from, to, up);
+  }
+
+  pub fn pixel_size(&self) -> f64 {
+    self.half_width * 2.0 / self.hsize as f64
+  }
+
+  //  The ray through the center of the lens.
+  pub fn ray_for_pixel(&self, x: f64, y: f64) -> Ray {
+    Ray::new(self.view.origin.clone(), self.pixel_direction(x, y))
+  }
+
+  //  A ray from a point on the lens, chosen by the aperture from u1 and u2,
+  //    toward where the pinhole ray meets the plane of focus.
+  pub fn lens_ray(&self, x: f64, y: f64, u1: f64, u2: f64) -> Ray {
+    let direction = self.pixel_direction(x, y);
+    if self.aperture_radius <= 0.0 {
+      return Ray::new(self.view.origin.clone(), direction);
+    }
+    let along_axis = direction.clone().dot(self.view.forward.clone());
+    let focus = self.view.origin.clone() + direction * (self.focal_distance / along_axis);
+    let (lens_x, lens_y) = self.aperture.sample(u1, u2);
+    let lens = self.view.origin.clone()
+      + self.view.to_world(
+        lens_x * self.aperture_radius,
+        lens_y * self.aperture_radius,
+        0.0,
+      );
+    Ray::new(lens.clone(), (focus - lens).normalize())
+  }
+
+  //  Focuses on whatever the center of the image sees, returning the new
+  //    focal distance, or None when the center ray misses.
+  pub fn auto_focus(&mut self, scene: &dyn Scene) -> Option<f64> {
+    let ray = self.ray_for_pixel(self.hsize as f64 / 2.0, self.vsize as f64 / 2.0);
+    let hit = scene.intersect(&ray)?;
+    self.focal_distance = hit.distance * ray.direction.dot(self.view.forward.clone());
+    Some(self.focal_distance)
+  }
+
+  fn pixel_direction(&self, x: f64, y: f64) -> Tuple {
+    let pixel_size = self.pixel_size();
+    let offset_x = x * pixel_size - self.half_width;
+    let offset_y = self.half_height - y * pixel_size;
+    self.view.to_world(offset_x, offset_y, 1.0).normalize()
+  }
+}
+
+impl Camera for PerspectiveCamera {
+  fn hsize(&self) -> usize {
+    self.hsize
+  }
+
+  fn vsize(&self) -> usize {
+    self.vsize
+  }
+
+  fn ray(&self, x: f64, y: f64, u1: f64, u2: f64) -> Option<Ray> {
+    Some(self.lens_ray(x, y, u1, u2))
+  }
+}
+
+#[cfg(test)]
+mod perspective_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::drawing::color::Color;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::random::Pcg32;
+  use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+  mod pinhole_tests {
+    use super::*;
+
+    #[test]
+    fn the_pixel_size_for_a_horizontal_canvas() {
+      let camera = PerspectiveCamera::new(200, 125, PI / 2.0);
+
+      assert_feq!(camera.pixel_size(), 0.01);
+    }
+
+    #[test]
+    fn the_pixel_size_for_a_vertical_canvas() {
+      let camera = PerspectiveCamera::new(125, 200, PI / 2.0);
+
+      assert_feq!(camera.pixel_size(), 0.01);
+    }
+
+    #[test]
+    fn constructing_a_ray_through_the_center_of_the_canvas() {
+      let camera = PerspectiveCamera::new(201, 101, PI / 2.0);
+
+      let ray = camera.ray_for_pixel(100.5, 50.5);
+
+      assert_feq!(ray.origin, Tuple::point(0.0, 0.0, 0.0));
+      assert_feq!(ray.direction, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn constructing_a_ray_through_a_corner_of_the_canvas() {
+      let camera = PerspectiveCamera::new(201, 101, PI / 2.0);
+
+      let ray = camera.ray_for_pixel(0.5, 0.5);
+
+      assert_feq!(ray.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
+    }
+
+    #[test]
+    fn constructing_a_ray_when_the_camera_is_moved() {
+      let mut camera = PerspectiveCamera::new(201, 101, PI / 2.0);
+      camera.look_at(
+        Tuple::point(0.0, 2.0, -5.0),
+        Tuple::point(FRAC_1_SQRT_2, 2.0, -5.0 - FRAC_1_SQRT_2),
+        Tuple::vector(0.0, 1.0, 0.0),
+      );
+
+      let ray = camera.ray_for_pixel(100.5, 50.5);
+
+      assert_feq!(ray.origin, Tuple::point(0.0, 2.0, -5.0));
+      assert_feq!(
+        ray.direction,
+        Tuple::vector(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2)
+      );
+    }
+
+    #[test]
+    fn differential_rays_pass_through_the_next_pixels() {
+      let camera = PerspectiveCamera::new(201, 101, PI / 2.0);
+
+      let ray = camera.differential_ray(0.5, 0.5, 0.5, 0.5, 0.0).unwrap();
+      let differentials = ray.differentials.unwrap();
+
+      assert_feq!(ray.direction, camera.ray_for_pixel(0.5, 0.5).direction);
+      assert_feq!(
+        differentials.x_direction,
+        camera.ray_for_pixel(1.5, 0.5).direction
+      );
+      assert_feq!(
+        differentials.y_direction,
+        camera.ray_for_pixel(0.5, 1.5).direction
+      );
+      assert_feq!(differentials.y_origin, Tuple::origin());
+    }
+  }
+
+  mod lens_tests {
+    use super::*;
+
+    fn lens_camera(aperture: Aperture) -> PerspectiveCamera {
+      let mut camera = PerspectiveCamera::new(101, 101, PI / 3.0);
+      camera.aperture_radius = 0.5;
+      camera.focal_distance = 4.0;
+      camera.aperture = aperture;
+      camera
+    }
+
+    #[test]
+    fn without_an_aperture_the_lens_is_a_pinhole() {
+      let camera = PerspectiveCamera::new(101, 101, PI / 3.0);
+
+      let ray = camera.lens_ray(20.5, 70.5, 0.9, 0.1);
+
+      assert_feq!(ray.origin, Tuple::origin());
+      assert_feq!(ray.direction, camera.ray_for_pixel(20.5, 70.5).direction);
+    }
+
+    #[test]
+    fn lens_rays_for_a_pixel_meet_on_the_plane_of_focus() {
+      let camera = lens_camera(Aperture::Circle);
+      let pinhole = camera.ray_for_pixel(20.5, 70.5);
+      let focus =
+        pinhole.position(4.0 / pinhole.direction.clone().dot(camera.view.forward.clone()));
+      let mut rng = Pcg32::new(2, 0);
+
+      for _ in 0..20 {
+        let ray = camera.lens_ray(20.5, 70.5, rng.next_f64(), rng.next_f64());
+        let t = (focus.z - ray.origin.z) / ray.direction.z;
+
+        assert_feq!(ray.position(t), focus.clone());
+        assert!((ray.origin.clone() - Tuple::origin()).magnitude() <= 0.5 + 1e-9);
+      }
+    }
+
+    #[test]
+    fn lens_positions_take_the_aperture_shape() {
+      let camera = lens_camera(Aperture::polygon(5, 0.0));
+      let mut rng = Pcg32::new(3, 0);
+
+      for _ in 0..100 {
+        let (u1, u2) = (rng.next_f64(), rng.next_f64());
+        let (x, y) = camera.aperture.sample(u1, u2);
+
+        let ray = camera.lens_ray(50.5, 50.5, u1, u2);
+
+        assert_feq!(ray.origin, Tuple::point(-x * 0.5, y * 0.5, 0.0));
+      }
+    }
+
+    #[test]
+    fn auto_focus_uses_the_surface_under_the_center_ray() {
+      let mut scene = Planes::default();
+      scene.add(-3.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 1.0));
+      let mut camera = lens_camera(Aperture::Circle);
+      camera.look_at(
+        Tuple::origin(),
+        Tuple::point(0.0, -1.0, 0.0),
+        Tuple::vector(0.0, 0.0, -1.0),
+      );
+
+      let focus = camera.auto_focus(&scene);
+
+      assert_feq!(focus.unwrap(), 3.0);
+      assert_feq!(camera.focal_distance, 3.0);
+    }
+
+    #[test]
+    fn auto_focus_keeps_the_focus_when_nothing_is_hit() {
+      let mut camera = lens_camera(Aperture::Circle);
+
+      assert!(camera.auto_focus(&Planes::default()).is_none());
+      assert_feq!(camera.focal_distance, 4.0);
+    }
+  }
+}
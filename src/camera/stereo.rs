@@ -0,0 +1,213 @@
+use super::perspective::PerspectiveCamera;
+use super::view::{Camera, View};
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Eye {
+  Left,
+  Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StereoLayout {
+  SideBySide,
+  OverUnder,
+  //  Red from the left eye, green and blue from the right.
+  Anaglyph,
+}
+
+//  Two copies of a camera, interocular apart along its right axis and toed
+//    in so their views cross at the convergence distance. Objects there
+//    appear at screen depth.
+#[derive(Clone)]
+pub struct StereoRig {
+  pub camera: PerspectiveCamera,
+  pub interocular: f64,
+  pub convergence: f64,
+}
+
+//  Instantiations
+impl StereoRig {
+  pub fn new(camera: PerspectiveCamera, interocular: f64, convergence: f64) -> Self {
+    Self {
+      camera,
+      interocular,
+      convergence,
+    }
+  }
+}
+
+//  Methods
+impl StereoRig {
+  pub fn eye(&self, eye: Eye) -> PerspectiveCamera {
+    let view = &self.camera.view;
+    let side = match eye {
+      Eye::Left => -0.5,
+      Eye::Right => 0.5,
+    };
+    let origin = view.origin.clone() + view.right.clone() * (side * self.interocular);
+    let target = view.origin.clone() + view.forward.clone() * self.convergence;
+    let mut camera = self.camera.clone();
+    camera.view = View::look_at(origin, target, view.up.clone());
+    camera
+  }
+
+  //  Renders each eye with the given renderer and composes the pair.
+  pub fn render<F>(&self, layout: StereoLayout, render: F) -> Canvas
+  where
+    F: Fn(&dyn Camera) -> Canvas,
+  {
+    let left = render(&self.eye(Eye::Left));
+    let right = render(&self.eye(Eye::Right));
+    layout.compose(&left, &right)
+  }
+}
+
+impl StereoLayout {
+  pub fn compose(&self, left: &Canvas, right: &Canvas) -> Canvas {
+    if left.width != right.width || left.height != right.height {
+      panic!("Both eyes of a stereo pair must be the same size.");
+    }
+    let (width, height) = (left.width, left.height);
+    let mut canvas = match self {
+      StereoLayout::SideBySide => Canvas::new(width * 2, height),
+      StereoLayout::OverUnder => Canvas::new(width, height * 2),
+      StereoLayout::Anaglyph => Canvas::new(width, height),
+    };
+    for y in 0..height {
+      for x in 0..width {
+        let (l, r) = (left.pixel_at(x, y), right.pixel_at(x, y));
+        match self {
+          StereoLayout::SideBySide => {
+            canvas.write_pixel(x, y, l);
+            canvas.write_pixel(x + width, y, r);
+          }
+          StereoLayout::OverUnder => {
+            canvas.write_pixel(x, y, l);
+            canvas.write_pixel(x, y + height, r);
+          }
+          StereoLayout::Anaglyph => {
+            canvas.write_pixel(x, y, Color::new(l.red, r.green, r.blue));
+          }
+        }
+      }
+    }
+    canvas
+  }
+}
+
+#[cfg(test)]
+mod stereo_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::tuple::Tuple;
+  use std::f64::consts::PI;
+
+  fn rig() -> StereoRig {
+    StereoRig::new(PerspectiveCamera::new(20, 10, PI / 3.0), 0.064, 2.0)
+  }
+
+  fn filled(width: usize, height: usize, color: Color) -> Canvas {
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+      for x in 0..width {
+        canvas.write_pixel(x, y, color.clone());
+      }
+    }
+    canvas
+  }
+
+  mod rig_tests {
+    use super::*;
+
+    #[test]
+    fn the_eyes_sit_either_side_of_the_camera() {
+      let rig = rig();
+
+      let left = rig.eye(Eye::Left);
+      let right = rig.eye(Eye::Right);
+
+      assert_feq!(left.view.origin, Tuple::point(0.032, 0.0, 0.0));
+      assert_feq!(right.view.origin, Tuple::point(-0.032, 0.0, 0.0));
+    }
+
+    #[test]
+    fn the_eyes_converge_at_the_convergence_distance() {
+      let rig = rig();
+      let target = Tuple::point(0.0, 0.0, -2.0);
+
+      for eye in [Eye::Left, Eye::Right] {
+        let camera = rig.eye(eye);
+        let ray = camera.ray_for_pixel(10.0, 5.0);
+
+        assert_feq!(ray.position(2.0_f64.hypot(0.032)), target.clone());
+      }
+    }
+
+    #[test]
+    fn rendering_renders_each_eye() {
+      let rig = rig();
+
+      let canvas = rig.render(StereoLayout::SideBySide, |camera| {
+        let ray = camera.ray(10.0, 5.0, 0.5, 0.5).unwrap();
+        let shade = if ray.origin.x > 0.0 { 1.0 } else { 0.0 };
+        filled(
+          camera.hsize(),
+          camera.vsize(),
+          Color::new(shade, shade, shade),
+        )
+      });
+
+      assert_eq!(canvas.width, 40);
+      assert_feq!(canvas.pixel_at(0, 0), Color::white());
+      assert_feq!(canvas.pixel_at(39, 9), Color::black());
+    }
+  }
+
+  mod layout_tests {
+    use super::*;
+
+    #[test]
+    fn side_by_side_puts_the_left_eye_on_the_left() {
+      let left = filled(3, 2, Color::red());
+      let right = filled(3, 2, Color::blue());
+
+      let canvas = StereoLayout::SideBySide.compose(&left, &right);
+
+      assert_eq!((canvas.width, canvas.height), (6, 2));
+      assert_feq!(canvas.pixel_at(2, 1), Color::red());
+      assert_feq!(canvas.pixel_at(3, 0), Color::blue());
+    }
+
+    #[test]
+    fn over_under_puts_the_left_eye_on_top() {
+      let left = filled(3, 2, Color::red());
+      let right = filled(3, 2, Color::blue());
+
+      let canvas = StereoLayout::OverUnder.compose(&left, &right);
+
+      assert_eq!((canvas.width, canvas.height), (3, 4));
+      assert_feq!(canvas.pixel_at(2, 1), Color::red());
+      assert_feq!(canvas.pixel_at(0, 2), Color::blue());
+    }
+
+    #[test]
+    fn an_anaglyph_takes_red_from_the_left_eye_and_cyan_from_the_right() {
+      let left = filled(3, 2, Color::new(0.2, 0.4, 0.6));
+      let right = filled(3, 2, Color::new(0.7, 0.8, 0.9));
+
+      let canvas = StereoLayout::Anaglyph.compose(&left, &right);
+
+      assert_eq!((canvas.width, canvas.height), (3, 2));
+      assert_feq!(canvas.pixel_at(1, 1), Color::new(0.2, 0.8, 0.9));
+    }
+
+    #[test]
+    #[should_panic]
+    fn eyes_of_different_sizes_should_panic() {
+      StereoLayout::Anaglyph.compose(&Canvas::new(3, 2), &Canvas::new(2, 3));
+    }
+  }
+}
@@ -0,0 +1,116 @@
+use crate::math::ray::{Ray, RayDifferentials};
+use crate::math::tuple::Tuple;
+
+//  Generates the rays that form an image. Pixel coordinates are continuous,
+//    with pixel (x, y) covering [x, x + 1) and [y, y + 1) and rows counting
+//    down from the top. u1 and u2 pick a point on the lens for cameras that
+//    have one. Pixels outside a projection's image have no ray. Cameras are
+//    shared between render threads, so they must be Sync.
+pub trait Camera: Sync {
+  fn hsize(&self) -> usize;
+
+  fn vsize(&self) -> usize;
+
+  fn ray(&self, x: f64, y: f64, u1: f64, u2: f64) -> Option<Ray>;
+
+  //  When the shutter opens and closes. A still camera exposes an instant.
+  fn shutter(&self) -> (f64, f64) {
+    (0.0, 0.0)
+  }
+
+  //  The ray for the pixel at a moment within the shutter interval.
+  fn ray_at(&self, x: f64, y: f64, u1: f64, u2: f64, time: f64) -> Option<Ray> {
+    self.ray(x, y, u1, u2).map(|ray| ray.with_time(time))
+  }
+
+  //  As ray_at, with differentials from the rays through the same lens point
+  //    one pixel across and one pixel down. Where the projection has no
+  //    such ray, the ray goes without.
+  fn differential_ray(&self, x: f64, y: f64, u1: f64, u2: f64, time: f64) -> Option<Ray> {
+    let ray = self.ray_at(x, y, u1, u2, time)?;
+    let differentials = match (
+      self.ray_at(x + 1.0, y, u1, u2, time),
+      self.ray_at(x, y + 1.0, u1, u2, time),
+    ) {
+      (Some(across), Some(down)) => Some(RayDifferentials {
+        x_origin: across.origin,
+        x_direction: across.direction,
+        y_origin: down.origin,
+        y_direction: down.direction,
+      }),
+      _ => None,
+    };
+    Some(ray.with_differentials(differentials))
+  }
+}
+
+//  Where a camera stands and which way it faces. Like the view transform,
+//    the image's right is up x forward.
+#[derive(Clone, Debug)]
+pub struct View {
+  pub origin: Tuple,
+  pub forward: Tuple,
+  pub right: Tuple,
+  pub up: Tuple,
+}
+
+//  Instantiations
+impl View {
+  pub fn look_at(from: Tuple, to: Tuple, up: Tuple) -> Self {
+    let forward = (to - from.clone()).normalize();
+    let right = up.normalize().cross(forward.clone()).normalize();
+    Self {
+      origin: from,
+      up: forward.clone().cross(right.clone()),
+      forward,
+      right,
+    }
+  }
+}
+
+impl Default for View {
+  //  At the origin looking toward -z.
+  fn default() -> Self {
+    Self::look_at(
+      Tuple::origin(),
+      Tuple::point(0.0, 0.0, -1.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    )
+  }
+}
+
+//  Methods
+impl View {
+  pub fn to_world(&self, right: f64, up: f64, forward: f64) -> Tuple {
+    self.right.clone() * right + self.up.clone() * up + self.forward.clone() * forward
+  }
+}
+
+#[cfg(test)]
+mod view_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn the_default_view_looks_down_negative_z() {
+    let view = View::default();
+
+    assert_feq!(view.forward, Tuple::vector(0.0, 0.0, -1.0));
+    assert_feq!(view.right, Tuple::vector(-1.0, 0.0, 0.0));
+    assert_feq!(view.up, Tuple::vector(0.0, 1.0, 0.0));
+  }
+
+  #[test]
+  fn the_up_vector_need_not_be_perpendicular() {
+    let view = View::look_at(
+      Tuple::point(1.0, 3.0, 2.0),
+      Tuple::point(4.0, -2.0, 8.0),
+      Tuple::vector(1.0, 1.0, 0.0),
+    );
+
+    assert_feq!(view.up.clone().dot(view.forward.clone()), 0.0);
+    assert_feq!(view.up.magnitude(), 1.0);
+    assert_feq!(view.right.clone().dot(view.forward.clone()), 0.0);
+  }
+}
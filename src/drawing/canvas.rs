@@ -1,5 +1,5 @@
 use super::color::Color;
-use std::fs::write;
+use std::fs::{read, read_to_string, write};
 
 #[derive(Clone)]
 pub struct Canvas {
@@ -17,6 +17,186 @@ impl Canvas {
       pixels: vec![Color::black(); width * height],
     }
   }
+
+  pub fn from_png(filename: &str) -> Self {
+    let data = read(filename).expect("Could not read png file from disk.");
+    Self::parse_png(&data)
+  }
+
+  pub fn parse_png(data: &[u8]) -> Self {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().expect("Could not read png header.");
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+      .next_frame(&mut buffer)
+      .expect("Could not decode png data.");
+    let samples = info.color_type.samples();
+    let mut canvas = Self::new(info.width as usize, info.height as usize);
+    for (index, pixel) in buffer[..info.buffer_size()].chunks(samples).enumerate() {
+      let (red, green, blue) = match info.color_type {
+        png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => {
+          (pixel[0], pixel[0], pixel[0])
+        }
+        _ => (pixel[0], pixel[1], pixel[2]),
+      };
+      canvas.pixels[index] = Color::new(
+        red as f64 / 255.0,
+        green as f64 / 255.0,
+        blue as f64 / 255.0,
+      );
+    }
+    canvas
+  }
+
+  pub fn from_ppm(filename: &str) -> Self {
+    let data = read_to_string(filename).expect("Could not read ppm file from disk.");
+    Self::parse_ppm(&data)
+  }
+
+  pub fn parse_ppm(data: &str) -> Self {
+    let mut values = data
+      .lines()
+      .map(|line| line.split('#').next().unwrap())
+      .flat_map(|line| line.split_whitespace());
+    if values.next() != Some("P3") {
+      panic!("Can only parse plain (P3) ppm data.");
+    }
+    let mut next_value = || -> usize {
+      values
+        .next()
+        .expect("Ppm data ended unexpectedly.")
+        .parse()
+        .expect("Ppm data contained an invalid number.")
+    };
+    let width = next_value();
+    let height = next_value();
+    let scale = next_value() as f64;
+    let mut canvas = Self::new(width, height);
+    for pixel in canvas.pixels.iter_mut() {
+      let red = next_value() as f64 / scale;
+      let green = next_value() as f64 / scale;
+      let blue = next_value() as f64 / scale;
+      *pixel = Color::new(red, green, blue);
+    }
+    canvas
+  }
+
+  pub fn from_hdr(filename: &str) -> Self {
+    let data = read(filename).expect("Could not read hdr file from disk.");
+    Self::parse_hdr(&data)
+  }
+
+  //  Radiance RGBE files, with either flat or run-length encoded scanlines.
+  pub fn parse_hdr(data: &[u8]) -> Self {
+    let mut position = 0;
+    let mut next_line = || -> String {
+      let start = position;
+      while position < data.len() && data[position] != b'\n' {
+        position += 1;
+      }
+      let line = String::from_utf8_lossy(&data[start..position]).to_string();
+      position += 1;
+      line
+    };
+    if !next_line().starts_with("#?") {
+      panic!("Can only parse Radiance hdr data.");
+    }
+    loop {
+      let line = next_line();
+      if line.starts_with("FORMAT=") && line != "FORMAT=32-bit_rle_rgbe" {
+        panic!("Can only parse rgbe hdr data.");
+      }
+      if line.is_empty() {
+        break;
+      }
+    }
+    let resolution = next_line();
+    let fields: Vec<&str> = resolution.split_whitespace().collect();
+    if fields.len() != 4 || fields[0] != "-Y" || fields[2] != "+X" {
+      panic!("Can only parse hdr data stored top to bottom, left to right.");
+    }
+    let height: usize = fields[1].parse().expect("Hdr data has an invalid height.");
+    let width: usize = fields[3].parse().expect("Hdr data has an invalid width.");
+
+    let mut canvas = Self::new(width, height);
+    for y in 0..height {
+      let scanline = read_hdr_scanline(data, &mut position, width);
+      for (x, rgbe) in scanline.iter().enumerate() {
+        canvas.write_pixel(x, y, rgbe_to_color(rgbe));
+      }
+    }
+    canvas
+  }
+}
+
+fn read_hdr_scanline(data: &[u8], position: &mut usize, width: usize) -> Vec<[u8; 4]> {
+  let mut scanline = vec![[0; 4]; width];
+  let is_run_length_encoded = (8..32768).contains(&width)
+    && data.len() >= *position + 4
+    && data[*position] == 2
+    && data[*position + 1] == 2
+    && data[*position + 2] < 128;
+  let mut next_byte = || -> u8 {
+    let byte = *data.get(*position).expect("Hdr data ended unexpectedly.");
+    *position += 1;
+    byte
+  };
+  if !is_run_length_encoded {
+    for pixel in scanline.iter_mut() {
+      for channel in pixel.iter_mut() {
+        *channel = next_byte();
+      }
+    }
+    return scanline;
+  }
+  let header = [next_byte(), next_byte(), next_byte(), next_byte()];
+  if ((header[2] as usize) << 8 | header[3] as usize) != width {
+    panic!("Hdr scanline width does not match the image width.");
+  }
+  let mut planes = vec![vec![0; width]; 4];
+  for plane in planes.iter_mut() {
+    let mut x = 0;
+    while x < width {
+      let count = next_byte() as usize;
+      let (is_run, length) = if count > 128 {
+        (true, count - 128)
+      } else {
+        (false, count)
+      };
+      if length == 0 || length > width - x {
+        panic!(
+          "Hdr scanline has a run of {} at x = {} in a scanline {} wide.",
+          length, x, width
+        );
+      }
+      if is_run {
+        let value = next_byte();
+        plane[x..x + length].fill(value);
+      } else {
+        for byte in plane[x..x + length].iter_mut() {
+          *byte = next_byte();
+        }
+      }
+      x += length;
+    }
+  }
+  for (x, pixel) in scanline.iter_mut().enumerate() {
+    *pixel = [planes[0][x], planes[1][x], planes[2][x], planes[3][x]];
+  }
+  scanline
+}
+
+fn rgbe_to_color(rgbe: &[u8; 4]) -> Color {
+  if rgbe[3] == 0 {
+    return Color::black();
+  }
+  let scale = 2.0_f64.powi(rgbe[3] as i32 - 136);
+  Color::new(
+    rgbe[0] as f64 * scale,
+    rgbe[1] as f64 * scale,
+    rgbe[2] as f64 * scale,
+  )
 }
 
 //  Operations
@@ -69,9 +249,9 @@ impl Canvas {
         (pixel.blue / max_value) * 255.0
       };
       pixel_data.push(Color {
-        red: red.min(255.0).max(0.0),
-        green: green.min(255.0).max(0.0),
-        blue: blue.min(255.0).max(0.0),
+        red: red.clamp(0.0, 255.0),
+        green: green.clamp(0.0, 255.0),
+        blue: blue.clamp(0.0, 255.0),
       });
     }
     self.pixels = pixel_data;
@@ -140,4 +320,141 @@ mod canvas_tests {
       assert_feq!(c.pixel_at(2, 3), red);
     }
   }
+
+  mod loading_tests {
+    use super::*;
+    use crate::assert_feq;
+
+    #[test]
+    #[should_panic]
+    fn reading_a_file_with_the_wrong_magic_number_should_panic() {
+      let ppm = "P32\n1 1\n255\n0 0 0\n";
+
+      let _canvas = Canvas::parse_ppm(ppm);
+    }
+
+    #[test]
+    fn reading_a_ppm_returns_a_canvas_of_the_right_size() {
+      let ppm = "P3\n10 2\n255\n0 0 0  0 0 0  0 0 0  0 0 0  0 0 0\n0 0 0  0 0 0  0 0 0  0 0 0  0 0 0\n0 0 0  0 0 0  0 0 0  0 0 0  0 0 0\n0 0 0  0 0 0  0 0 0  0 0 0  0 0 0\n";
+
+      let canvas = Canvas::parse_ppm(ppm);
+
+      assert_eq!(10, canvas.width);
+      assert_eq!(2, canvas.height);
+    }
+
+    #[test]
+    fn reading_pixel_data_from_a_ppm_file() {
+      let ppm = "P3\n4 3\n255\n255 127 0  0 127 255  127 255 0  255 255 255\n0 0 0  255 0 0  0 255 0  0 0 255\n255 255 0  0 255 255  255 0 255  127 127 127\n";
+
+      let canvas = Canvas::parse_ppm(ppm);
+
+      assert_feq!(canvas.pixel_at(0, 0), Color::new(1.0, 127.0 / 255.0, 0.0));
+      assert_feq!(canvas.pixel_at(3, 0), Color::white());
+      assert_feq!(canvas.pixel_at(2, 1), Color::green());
+      assert_feq!(
+        canvas.pixel_at(3, 2),
+        Color::new(127.0 / 255.0, 127.0 / 255.0, 127.0 / 255.0)
+      );
+    }
+
+    #[test]
+    fn ppm_parsing_ignores_comment_lines() {
+      let ppm = "P3\n# this is a comment\n2 1\n255\n# this, too\n255 255 255\n# another comment\n255 0 255\n";
+
+      let canvas = Canvas::parse_ppm(ppm);
+
+      assert_feq!(canvas.pixel_at(0, 0), Color::white());
+      assert_feq!(canvas.pixel_at(1, 0), Color::new(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn ppm_parsing_allows_an_rgb_triple_to_span_lines() {
+      let ppm = "P3\n1 1\n255\n51\n153\n\n204\n";
+
+      let canvas = Canvas::parse_ppm(ppm);
+
+      assert_feq!(canvas.pixel_at(0, 0), Color::new(0.2, 0.6, 0.8));
+    }
+
+    #[test]
+    fn ppm_parsing_respects_the_scale_setting() {
+      let ppm = "P3\n2 2\n100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+
+      let canvas = Canvas::parse_ppm(ppm);
+
+      assert_feq!(canvas.pixel_at(0, 1), Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn reading_flat_hdr_data() {
+      let mut hdr = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+      hdr.extend_from_slice(&[128, 64, 0, 129, 128, 128, 128, 130]);
+
+      let canvas = Canvas::parse_hdr(&hdr);
+
+      assert_eq!(2, canvas.width);
+      assert_eq!(1, canvas.height);
+      assert_feq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.5, 0.0));
+      assert_feq!(canvas.pixel_at(1, 0), Color::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn reading_run_length_encoded_hdr_data() {
+      let mut hdr = b"#?RGBE\n# made by hand\n\n-Y 1 +X 8\n".to_vec();
+      hdr.extend_from_slice(&[2, 2, 0, 8]);
+      hdr.extend_from_slice(&[136, 128]);
+      hdr.extend_from_slice(&[4, 0, 32, 64, 128, 132, 0]);
+      hdr.extend_from_slice(&[136, 0]);
+      hdr.extend_from_slice(&[136, 129]);
+
+      let canvas = Canvas::parse_hdr(&hdr);
+
+      assert_eq!(8, canvas.width);
+      assert_feq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+      assert_feq!(canvas.pixel_at(2, 0), Color::new(1.0, 0.5, 0.0));
+      assert_feq!(canvas.pixel_at(3, 0), Color::new(1.0, 1.0, 0.0));
+      assert_feq!(canvas.pixel_at(7, 0), Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "run of 0")]
+    fn a_zero_length_hdr_run_should_panic() {
+      let mut hdr = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+      hdr.extend_from_slice(&[2, 2, 0, 8, 0, 0, 0, 0]);
+      let _canvas = Canvas::parse_hdr(&hdr);
+    }
+
+    #[test]
+    #[should_panic(expected = "run of 9")]
+    fn an_hdr_run_past_the_end_of_the_scanline_should_panic() {
+      let mut hdr = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+      hdr.extend_from_slice(&[2, 2, 0, 8, 137, 0]);
+      let _canvas = Canvas::parse_hdr(&hdr);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reading_hdr_data_without_the_radiance_signature_should_panic() {
+      let _canvas = Canvas::parse_hdr(b"P3\n1 1\n255\n0 0 0\n");
+    }
+
+    #[test]
+    fn png_data_round_trips_through_a_canvas() {
+      let mut canvas = Canvas::new(3, 2);
+      canvas.write_pixel(0, 0, Color::red());
+      canvas.write_pixel(1, 0, Color::white());
+      canvas.write_pixel(2, 1, Color::blue());
+      let png_data = canvas.write_png_data(canvas.clone().clamp_pixels().as_rgba32());
+
+      let actual = Canvas::parse_png(&png_data);
+
+      assert_eq!(3, actual.width);
+      assert_eq!(2, actual.height);
+      assert_feq!(actual.pixel_at(0, 0), Color::red());
+      assert_feq!(actual.pixel_at(1, 0), Color::white());
+      assert_feq!(actual.pixel_at(2, 1), Color::blue());
+      assert_feq!(actual.pixel_at(0, 1), Color::black());
+    }
+  }
 }
@@ -57,6 +57,13 @@ impl Color {
   }
 }
 
+//  Methods
+impl Color {
+  pub fn luminance(&self) -> f64 {
+    0.2126 * self.red + 0.7152 * self.green + 0.0722 * self.blue
+  }
+}
+
 impl Add<Self> for Color {
   type Output = Self;
 
@@ -259,4 +266,20 @@ mod color_tests {
       assert_feq!(actual, expected);
     }
   }
+
+  mod method_tests {
+    use super::*;
+    use crate::assert_feq;
+
+    #[test]
+    fn the_luminance_of_white_is_one() {
+      assert_feq!(Color::white().luminance(), 1.0);
+    }
+
+    #[test]
+    fn green_contributes_most_to_luminance() {
+      assert!(Color::green().luminance() > Color::red().luminance());
+      assert!(Color::red().luminance() > Color::blue().luminance());
+    }
+  }
 }
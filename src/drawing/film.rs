@@ -0,0 +1,282 @@
+use super::canvas::Canvas;
+use super::color::Color;
+use crate::math::special::sinc;
+
+//  Reconstruction filters, applied separably in x and y. Each is zero
+//    beyond its radius, measured in pixels from the pixel center.
+#[derive(Clone, Debug)]
+pub enum Filter {
+  Box { radius: f64 },
+  Tent { radius: f64 },
+  Gaussian { radius: f64, alpha: f64 },
+  Mitchell { radius: f64, b: f64, c: f64 },
+  Lanczos { radius: f64 },
+}
+
+//  Accumulates weighted samples for every pixel a sample's filter reaches,
+//    then resolves them into a canvas by dividing out the weights.
+#[derive(Clone, Debug)]
+pub struct Film {
+  pub width: usize,
+  pub height: usize,
+  pub filter: Filter,
+  sums: Vec<Color>,
+  weights: Vec<f64>,
+}
+
+//  Instantiations
+impl Filter {
+  pub fn gaussian(radius: f64) -> Self {
+    Filter::Gaussian { radius, alpha: 2.0 }
+  }
+
+  //  The B = C = 1/3 filter Mitchell and Netravali recommend.
+  pub fn mitchell(radius: f64) -> Self {
+    Filter::Mitchell {
+      radius,
+      b: 1.0 / 3.0,
+      c: 1.0 / 3.0,
+    }
+  }
+}
+
+impl Film {
+  pub fn new(width: usize, height: usize, filter: Filter) -> Self {
+    if filter.radius() <= 0.0 {
+      panic!("A reconstruction filter needs a positive radius.");
+    }
+    Self {
+      width,
+      height,
+      filter,
+      sums: vec![Color::black(); width * height],
+      weights: vec![0.0; width * height],
+    }
+  }
+}
+
+//  Methods
+impl Filter {
+  pub fn radius(&self) -> f64 {
+    match self {
+      Filter::Box { radius }
+      | Filter::Tent { radius }
+      | Filter::Gaussian { radius, .. }
+      | Filter::Mitchell { radius, .. }
+      | Filter::Lanczos { radius } => *radius,
+    }
+  }
+
+  pub fn evaluate(&self, x: f64, y: f64) -> f64 {
+    self.evaluate_1d(x) * self.evaluate_1d(y)
+  }
+
+  fn evaluate_1d(&self, x: f64) -> f64 {
+    let x = x.abs();
+    let radius = self.radius();
+    if x > radius {
+      return 0.0;
+    }
+    match self {
+      Filter::Box { .. } => 1.0,
+      Filter::Tent { .. } => radius - x,
+      Filter::Gaussian { alpha, .. } => {
+        ((-alpha * x * x).exp() - (-alpha * radius * radius).exp()).max(0.0)
+      }
+      Filter::Mitchell { b, c, .. } => {
+        //  The cubic is defined over [0, 2]; stretch it to the radius.
+        let x = 2.0 * x / radius;
+        if x < 1.0 {
+          ((12.0 - 9.0 * b - 6.0 * c) * x * x * x
+            + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+            + (6.0 - 2.0 * b))
+            / 6.0
+        } else {
+          ((-b - 6.0 * c) * x * x * x
+            + (6.0 * b + 30.0 * c) * x * x
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c))
+            / 6.0
+        }
+      }
+      Filter::Lanczos { .. } => sinc(x) * sinc(x / radius),
+    }
+  }
+}
+
+impl Film {
+  //  Adds a sample taken at continuous position (x, y), where pixel (i, j)
+  //    covers [i, i + 1) x [j, j + 1) and is centered at (i + 0.5, j + 0.5).
+  pub fn add_sample(&mut self, x: f64, y: f64, color: &Color) {
+    let radius = self.filter.radius();
+    let x0 = (x - 0.5 - radius).ceil().max(0.0) as usize;
+    let y0 = (y - 0.5 - radius).ceil().max(0.0) as usize;
+    let x1 = (x - 0.5 + radius).floor().min(self.width as f64 - 1.0);
+    let y1 = (y - 0.5 + radius).floor().min(self.height as f64 - 1.0);
+    if x1 < 0.0 || y1 < 0.0 {
+      return;
+    }
+    for j in y0..=y1 as usize {
+      for i in x0..=x1 as usize {
+        let weight = self.filter.evaluate(i as f64 + 0.5 - x, j as f64 + 0.5 - y);
+        if weight == 0.0 {
+          continue;
+        }
+        let index = j * self.width + i;
+        self.sums[index] += color.clone() * weight;
+        self.weights[index] += weight;
+      }
+    }
+  }
+
+  //  Pixels no sample reached stay black.
+  pub fn resolve(&self) -> Canvas {
+    let mut canvas = Canvas::new(self.width, self.height);
+    for y in 0..self.height {
+      for x in 0..self.width {
+        let index = y * self.width + x;
+        if self.weights[index] != 0.0 {
+          canvas.write_pixel(x, y, self.sums[index].clone() / self.weights[index]);
+        }
+      }
+    }
+    canvas
+  }
+}
+
+#[cfg(test)]
+mod film_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  mod filter_tests {
+    use super::*;
+
+    #[test]
+    fn every_filter_vanishes_beyond_its_radius() {
+      for filter in [
+        Filter::Box { radius: 0.5 },
+        Filter::Tent { radius: 1.0 },
+        Filter::gaussian(1.5),
+        Filter::mitchell(2.0),
+        Filter::Lanczos { radius: 3.0 },
+      ] {
+        let beyond = filter.radius() + 0.01;
+
+        assert_feq!(filter.evaluate(beyond, 0.0), 0.0);
+        assert_feq!(filter.evaluate(0.0, -beyond), 0.0);
+        assert!(filter.evaluate(0.0, 0.0) > 0.0);
+      }
+    }
+
+    #[test]
+    fn a_tent_falls_off_linearly() {
+      let filter = Filter::Tent { radius: 2.0 };
+
+      assert_feq!(filter.evaluate(0.0, 0.0), 4.0);
+      assert_feq!(filter.evaluate(1.0, 0.0), 2.0);
+      assert_feq!(filter.evaluate(1.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn a_gaussian_reaches_zero_at_its_radius() {
+      let filter = Filter::gaussian(1.5);
+
+      assert_feq!(filter.evaluate(1.5, 0.0), 0.0);
+      assert!(filter.evaluate(0.5, 0.0) < filter.evaluate(0.25, 0.0));
+    }
+
+    #[test]
+    fn the_mitchell_filter_has_negative_lobes() {
+      let filter = Filter::mitchell(2.0);
+
+      assert_feq!(filter.evaluate(0.0, 0.0), (8.0 / 9.0) * (8.0 / 9.0));
+      assert!(filter.evaluate(1.5, 0.0) < 0.0);
+      assert_feq!(filter.evaluate(2.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn lanczos_crosses_zero_at_whole_pixels() {
+      let filter = Filter::Lanczos { radius: 3.0 };
+
+      assert_feq!(filter.evaluate(0.0, 0.0), 1.0);
+      assert_feq!(filter.evaluate(1.0, 0.0), 0.0);
+      assert_feq!(filter.evaluate(2.0, 0.0), 0.0);
+      assert!(filter.evaluate(1.5, 0.0) < 0.0);
+    }
+  }
+
+  mod splat_tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn a_filter_without_a_radius_should_panic() {
+      let _film = Film::new(4, 4, Filter::Box { radius: 0.0 });
+    }
+
+    #[test]
+    fn a_half_pixel_box_averages_the_samples_inside_each_pixel() {
+      let mut film = Film::new(2, 1, Filter::Box { radius: 0.5 });
+
+      film.add_sample(0.2, 0.5, &Color::white());
+      film.add_sample(0.7, 0.5, &Color::black());
+      film.add_sample(1.5, 0.5, &Color::red());
+
+      let image = film.resolve();
+      assert_feq!(image.pixel_at(0, 0), Color::new(0.5, 0.5, 0.5));
+      assert_feq!(image.pixel_at(1, 0), Color::red());
+    }
+
+    #[test]
+    fn a_wide_filter_spreads_a_sample_to_its_neighbors() {
+      let mut film = Film::new(3, 3, Filter::Tent { radius: 1.5 });
+
+      film.add_sample(1.5, 1.5, &Color::white());
+
+      let image = film.resolve();
+      assert_feq!(image.pixel_at(0, 0), Color::white());
+      assert_feq!(image.pixel_at(2, 1), Color::white());
+    }
+
+    #[test]
+    fn unreached_pixels_stay_black() {
+      let mut film = Film::new(3, 1, Filter::Box { radius: 0.5 });
+
+      film.add_sample(0.5, 0.5, &Color::white());
+
+      assert_feq!(film.resolve().pixel_at(2, 0), Color::black());
+    }
+
+    #[test]
+    fn samples_off_the_film_still_reach_the_edge_pixels() {
+      let mut film = Film::new(2, 2, Filter::gaussian(1.5));
+
+      film.add_sample(-0.5, -0.5, &Color::blue());
+
+      assert_feq!(film.resolve().pixel_at(0, 0), Color::blue());
+    }
+
+    #[test]
+    fn a_flat_image_resolves_to_its_color_under_every_filter() {
+      for filter in [
+        Filter::Box { radius: 0.5 },
+        Filter::Tent { radius: 1.0 },
+        Filter::gaussian(1.5),
+        Filter::mitchell(2.0),
+        Filter::Lanczos { radius: 2.0 },
+      ] {
+        let mut film = Film::new(4, 4, filter);
+        for j in 0..16 {
+          for i in 0..16 {
+            let (x, y) = ((i as f64 + 0.5) / 4.0, (j as f64 + 0.5) / 4.0);
+            film.add_sample(x, y, &Color::new(0.2, 0.4, 0.6));
+          }
+        }
+
+        assert_feq!(film.resolve().pixel_at(1, 2), Color::new(0.2, 0.4, 0.6));
+      }
+    }
+  }
+}
@@ -1,2 +1,3 @@
 pub mod canvas;
 pub mod color;
+pub mod film;
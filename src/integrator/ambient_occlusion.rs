@@ -0,0 +1,226 @@
+use super::scene::{Scene, SurfaceInteraction};
+use super::tiles::TileRenderer;
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampler::Sampler;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use crate::EPSILON;
+
+//  The fraction of the hemisphere above a point that is open within
+//    max_distance, weighted by cosine as diffuse light would be.
+#[derive(Clone, Debug)]
+pub struct AmbientOcclusion {
+  pub samples: usize,
+  pub max_distance: f64,
+  //  One per core unless set otherwise.
+  pub threads: usize,
+}
+
+//  Instantiations
+impl AmbientOcclusion {
+  pub fn new(samples: usize, max_distance: f64) -> Self {
+    if samples == 0 {
+      panic!("Ambient occlusion needs at least one sample.");
+    }
+    Self {
+      samples,
+      max_distance,
+      threads: TileRenderer::available().threads(),
+    }
+  }
+}
+
+//  Methods
+impl AmbientOcclusion {
+  //  One when nothing is nearby, falling to zero when fully enclosed. The
+  //    normal is taken on the eye's side of the surface. Each direction
+  //    starts its own pixel sample at (x, y).
+  pub fn visibility(
+    &self,
+    scene: &dyn Scene,
+    hit: &SurfaceInteraction,
+    eyev: &Tuple,
+    (x, y): (usize, usize),
+    sampler: &mut dyn Sampler,
+  ) -> f64 {
+    let normalv = if hit.normalv.clone().dot(eyev.clone()) < 0.0 {
+      -hit.normalv.clone()
+    } else {
+      hit.normalv.clone()
+    };
+    let origin = hit.point.clone() + normalv.clone() * EPSILON;
+    let mut open = 0;
+    for index in 0..self.samples {
+      sampler.start_pixel_sample(x, y, index);
+      let (u1, u2) = sampler.next_2d();
+      let (direction, _) = sampling::cosine_hemisphere(&normalv, u1, u2);
+      let blocked = scene
+        .intersect(&Ray::new(origin.clone(), direction).with_time(hit.time))
+        .is_some_and(|occluder| occluder.distance < self.max_distance);
+      if !blocked {
+        open += 1;
+      }
+    }
+    open as f64 / self.samples as f64
+  }
+
+  //  A grayscale pass, white where camera rays see nothing.
+  pub fn render<F>(
+    &self,
+    scene: &dyn Scene,
+    width: usize,
+    height: usize,
+    sampler: &dyn Sampler,
+    camera: F,
+  ) -> Canvas
+  where
+    F: Fn(f64, f64) -> Ray + Sync,
+  {
+    TileRenderer::new(self.threads).render(width, height, |x, y| {
+      let mut sampler = sampler.clone_sampler();
+      let ray = camera(x as f64 + 0.5, y as f64 + 0.5);
+      let eyev = -ray.direction.clone();
+      let visibility = match scene.intersect(&ray) {
+        Some(hit) => self.visibility(scene, &hit, &eyev, (x, y), sampler.as_mut()),
+        None => 1.0,
+      };
+      Color::new(visibility, visibility, visibility)
+    })
+  }
+}
+
+#[cfg(test)]
+mod ambient_occlusion_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::sampler::RandomSampler;
+
+  fn floor_hit() -> SurfaceInteraction {
+    SurfaceInteraction {
+      distance: 1.0,
+      point: Tuple::origin(),
+      normalv: Tuple::vector(0.0, 1.0, 0.0),
+      material: PbrMaterial::new(Color::white(), 0.0, 1.0),
+      time: 0.0,
+    }
+  }
+
+  fn floor_and_ceiling() -> Planes {
+    let mut scene = Planes::default();
+    scene.add(0.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 1.0));
+    scene.add(1.0, -1.0, PbrMaterial::new(Color::white(), 0.0, 1.0));
+    scene
+  }
+
+  mod visibility_tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn ambient_occlusion_without_samples_should_panic() {
+      let _occlusion = AmbientOcclusion::new(0, 1.0);
+    }
+
+    #[test]
+    fn an_open_surface_is_fully_visible() {
+      let mut scene = Planes::default();
+      scene.add(0.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 1.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let visibility = AmbientOcclusion::new(64, 10.0).visibility(
+        &scene,
+        &floor_hit(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        (0, 0),
+        &mut sampler,
+      );
+
+      assert_feq!(visibility, 1.0);
+    }
+
+    #[test]
+    fn occluders_beyond_the_maximum_distance_are_ignored() {
+      let mut sampler = RandomSampler::new(1);
+
+      let visibility = AmbientOcclusion::new(64, 0.9).visibility(
+        &floor_and_ceiling(),
+        &floor_hit(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        (0, 0),
+        &mut sampler,
+      );
+
+      assert_feq!(visibility, 1.0);
+    }
+
+    //  A ceiling at height one is within two units for directions up to 60
+    //    degrees from the normal, which hold three quarters of the cosine
+    //    weighted hemisphere.
+    #[test]
+    fn a_nearby_ceiling_occludes_part_of_the_hemisphere() {
+      let mut sampler = RandomSampler::new(1);
+
+      let visibility = AmbientOcclusion::new(4000, 2.0).visibility(
+        &floor_and_ceiling(),
+        &floor_hit(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        (0, 0),
+        &mut sampler,
+      );
+
+      assert!((visibility - 0.25).abs() < 0.02);
+    }
+  }
+
+  mod sampler_tests {
+    use super::*;
+    use crate::math::sampler::SobolSampler;
+
+    //  Well spread directions find the occluded fraction with far fewer
+    //    samples than independent ones.
+    #[test]
+    fn a_sobol_sampler_converges_with_few_samples() {
+      let mut sampler = SobolSampler::new(256);
+
+      let visibility = AmbientOcclusion::new(256, 2.0).visibility(
+        &floor_and_ceiling(),
+        &floor_hit(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        (0, 0),
+        &mut sampler,
+      );
+
+      assert!((visibility - 0.25).abs() < 0.01, "{}", visibility);
+    }
+  }
+
+  mod render_tests {
+    use super::*;
+
+    #[test]
+    fn the_pass_is_grayscale_and_white_where_nothing_is_hit() {
+      let scene = floor_and_ceiling();
+      let occlusion = AmbientOcclusion::new(16, 2.0);
+
+      let canvas = occlusion.render(&scene, 2, 1, &RandomSampler::new(16), |x, _| {
+        let origin = Tuple::point(0.0, 0.5, 0.0);
+        if x < 1.0 {
+          Ray::new(origin, Tuple::vector(0.0, -1.0, 0.0))
+        } else {
+          Ray::new(Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0))
+        }
+      });
+      let hit = canvas.pixel_at(0, 0);
+
+      assert_feq!(hit.red, hit.green);
+      assert_feq!(hit.red, hit.blue);
+      assert!(hit.red < 1.0);
+      assert_feq!(canvas.pixel_at(1, 0), Color::white());
+    }
+  }
+}
@@ -0,0 +1,328 @@
+use super::scene::{Scene, SurfaceInteraction};
+use crate::drawing::color::Color;
+use crate::lighting::light::Light;
+use crate::math::sampler::Sampler;
+use crate::math::tuple::Tuple;
+
+#[derive(Clone, Debug)]
+pub struct LightDistribution {
+  cdf: Vec<f64>,
+}
+
+//  A scene's lights with the distribution that picks among them by power.
+//    Measuring power can be costly, so this is built once per render.
+pub struct SceneLights<'a> {
+  pub lights: Vec<&'a dyn Light>,
+  pub distribution: LightDistribution,
+}
+
+//  Instantiations
+impl LightDistribution {
+  pub fn new(lights: &[&dyn Light]) -> Self {
+    let powers: Vec<f64> = lights.iter().map(|light| light.power().max(0.0)).collect();
+    let total: f64 = powers.iter().sum();
+    let mut cdf = Vec::with_capacity(powers.len());
+    let mut running = 0.0;
+    for power in powers.iter() {
+      running += if total > 0.0 {
+        power / total
+      } else {
+        1.0 / powers.len() as f64
+      };
+      cdf.push(running);
+    }
+    Self { cdf }
+  }
+}
+
+impl<'a> SceneLights<'a> {
+  pub fn new(scene: &'a dyn Scene) -> Self {
+    let lights = scene.lights();
+    let distribution = LightDistribution::new(&lights);
+    Self {
+      lights,
+      distribution,
+    }
+  }
+}
+
+//  Methods
+impl LightDistribution {
+  pub fn probability(&self, index: usize) -> f64 {
+    let previous = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+    self.cdf[index] - previous
+  }
+
+  pub fn sample(&self, u: f64) -> Option<(usize, f64)> {
+    if self.cdf.is_empty() {
+      return None;
+    }
+    let index = self
+      .cdf
+      .iter()
+      .position(|&bound| u < bound)
+      .unwrap_or(self.cdf.len() - 1);
+    Some((index, self.probability(index)))
+  }
+}
+
+pub fn power_heuristic(f_count: f64, f_pdf: f64, g_count: f64, g_pdf: f64) -> f64 {
+  let f = f_count * f_pdf;
+  let g = g_count * g_pdf;
+  if f == 0.0 && g == 0.0 {
+    return 0.0;
+  }
+  (f * f) / (f * f + g * g)
+}
+
+//  Combines one light sample and one BSDF sample, each weighted by the power
+//    heuristic. Delta lights can only be reached by the light sample.
+pub fn estimate_direct(
+  scene: &dyn Scene,
+  hit: &SurfaceInteraction,
+  eyev: &Tuple,
+  light: &dyn Light,
+  sampler: &mut dyn Sampler,
+) -> Color {
+  let over_point = hit.over_point();
+  let material = &hit.material;
+  let mut direct = Color::black();
+  //  Drawn up front, so every estimate takes the same dimensions.
+  let (l1, l2) = sampler.next_2d();
+  let u1 = sampler.next_1d();
+  let (u2, u3) = sampler.next_2d();
+
+  if let Some((sample, light_pdf)) = light.sample_incident(&hit.point, l1, l2) {
+    let n_dot_l = hit.normalv.clone().dot(sample.direction.clone());
+    if light_pdf > 0.0 && n_dot_l > 0.0 && !scene.is_occluded(&over_point, &sample, hit.time) {
+      let brdf = material.brdf(&sample.direction, eyev, &hit.normalv);
+      let weight = if light.is_delta() {
+        1.0
+      } else {
+        let bsdf_pdf = material.pdf(&sample.direction, eyev, &hit.normalv);
+        power_heuristic(1.0, light_pdf, 1.0, bsdf_pdf)
+      };
+      direct += brdf * sample.intensity * (n_dot_l * weight / light_pdf);
+    }
+  }
+
+  if light.is_delta() {
+    return direct;
+  }
+  if let Some(bsdf_sample) = material.sample(eyev, &hit.normalv, u1, u2, u3) {
+    if let Some((sample, light_pdf)) = light.emitted_toward(&hit.point, &bsdf_sample.direction) {
+      if !scene.is_occluded(&over_point, &sample, hit.time) {
+        let n_dot_l = hit.normalv.clone().dot(sample.direction.clone());
+        let weight = power_heuristic(1.0, bsdf_sample.pdf, 1.0, light_pdf);
+        direct += bsdf_sample.value * sample.intensity * (n_dot_l * weight / bsdf_sample.pdf);
+      }
+    }
+  }
+  direct
+}
+
+pub fn sample_one_light(
+  scene: &dyn Scene,
+  lights: &SceneLights,
+  hit: &SurfaceInteraction,
+  eyev: &Tuple,
+  sampler: &mut dyn Sampler,
+) -> Color {
+  match lights.distribution.sample(sampler.next_1d()) {
+    Some((index, probability)) if probability > 0.0 => {
+      estimate_direct(scene, hit, eyev, lights.lights[index], sampler) / probability
+    }
+    _ => Color::black(),
+  }
+}
+
+#[cfg(test)]
+mod direct_lighting_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::area_light::AreaLight;
+  use crate::lighting::mesh_light::MeshLight;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::lighting::point_light::PointLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::sampler::RandomSampler;
+
+  mod heuristic_tests {
+    use super::*;
+
+    #[test]
+    fn equal_densities_share_the_weight() {
+      assert_feq!(power_heuristic(1.0, 0.5, 1.0, 0.5), 0.5);
+    }
+
+    #[test]
+    fn the_power_heuristic_favours_the_denser_strategy() {
+      assert_feq!(power_heuristic(1.0, 3.0, 1.0, 1.0), 0.9);
+      assert_feq!(power_heuristic(1.0, 2.0, 1.0, 0.0), 1.0);
+      assert_feq!(power_heuristic(1.0, 0.0, 1.0, 0.0), 0.0);
+    }
+  }
+
+  mod distribution_tests {
+    use super::*;
+
+    #[test]
+    fn lights_are_chosen_in_proportion_to_their_power() {
+      let dim = PointLight::new(Tuple::origin(), Color::new(1.0, 1.0, 1.0));
+      let bright = PointLight::new(Tuple::origin(), Color::new(3.0, 3.0, 3.0));
+      let lights: Vec<&dyn Light> = vec![&dim, &bright];
+
+      let distribution = LightDistribution::new(&lights);
+
+      assert_feq!(distribution.probability(0), 0.25);
+      assert_feq!(distribution.probability(1), 0.75);
+      assert_eq!(distribution.sample(0.1).unwrap().0, 0);
+      assert_eq!(distribution.sample(0.3).unwrap().0, 1);
+      assert_eq!(distribution.sample(0.999).unwrap().0, 1);
+    }
+
+    #[test]
+    fn an_empty_scene_has_nothing_to_sample() {
+      let distribution = LightDistribution::new(&[]);
+
+      assert!(distribution.sample(0.5).is_none());
+    }
+  }
+
+  mod estimate_tests {
+    use super::*;
+
+    fn floor_hit(material: PbrMaterial) -> SurfaceInteraction {
+      SurfaceInteraction {
+        distance: 1.0,
+        point: Tuple::origin(),
+        normalv: Tuple::vector(0.0, 1.0, 0.0),
+        material,
+        time: 0.0,
+      }
+    }
+
+    fn panel() -> AreaLight {
+      AreaLight::rectangle(
+        Tuple::point(-1.0, 2.0, -1.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        1,
+        Tuple::vector(0.0, 0.0, 2.0),
+        1,
+        Color::white(),
+      )
+    }
+
+    fn reference(light: &AreaLight, hit: &SurfaceInteraction, eyev: &Tuple) -> f64 {
+      let steps = 200;
+      let mut sum = 0.0;
+      for i in 0..steps {
+        for j in 0..steps {
+          let s = (i as f64 + 0.5) / steps as f64;
+          let t = (j as f64 + 0.5) / steps as f64;
+          let light_point = Tuple::point(-1.0 + 2.0 * s, 2.0, -1.0 + 2.0 * t);
+          let offset = light_point - hit.point.clone();
+          let distance2 = offset.magnitude_squared();
+          let lightv = offset.normalize();
+          let cos_surface = lightv.clone().dot(hit.normalv.clone());
+          let cos_light = lightv.y;
+          let brdf = hit.material.brdf(&lightv, eyev, &hit.normalv).red;
+          sum += brdf * cos_surface * cos_light / distance2;
+        }
+      }
+      sum * light.area() / (steps * steps) as f64
+    }
+
+    #[test]
+    fn a_point_light_estimate_falls_off_with_the_squared_distance() {
+      let scene = Planes::default();
+      let hit = floor_hit(PbrMaterial::new(Color::white(), 0.0, 0.5));
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let light = PointLight::new(Tuple::point(0.0, 4.0, 0.0), Color::white());
+      let mut sampler = RandomSampler::new(1);
+
+      let actual = estimate_direct(&scene, &hit, &eyev, &light, &mut sampler);
+      //  Four units away, the light arrives with a sixteenth of its intensity.
+      let expected = hit.material.brdf(&eyev, &eyev, &hit.normalv) / 16.0;
+
+      assert_feq!(actual, expected);
+    }
+
+    #[test]
+    fn multiple_importance_sampling_converges_for_a_glossy_surface() {
+      let scene = Planes::default();
+      let hit = floor_hit(PbrMaterial::new(Color::white(), 1.0, 0.3));
+      let eyev = Tuple::vector(0.3, 1.0, 0.0).normalize();
+      let light = panel();
+      let mut sampler = RandomSampler::new(1);
+      let count = 20000;
+
+      let mut sum = 0.0;
+      for _ in 0..count {
+        sum += estimate_direct(&scene, &hit, &eyev, &light, &mut sampler).red;
+      }
+      let estimate = sum / count as f64;
+      let expected = reference(&light, &hit, &eyev);
+
+      assert!((estimate - expected).abs() < 0.03 * expected);
+    }
+
+    #[test]
+    fn a_mesh_light_lights_like_the_equivalent_area_light() {
+      let scene = Planes::default();
+      let hit = floor_hit(PbrMaterial::new(Color::white(), 0.0, 0.6));
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let corners = [
+        Tuple::point(-1.0, 2.0, -1.0),
+        Tuple::point(1.0, 2.0, -1.0),
+        Tuple::point(1.0, 2.0, 1.0),
+        Tuple::point(-1.0, 2.0, 1.0),
+      ];
+      let mesh = MeshLight::new(
+        vec![
+          [corners[0].clone(), corners[1].clone(), corners[2].clone()],
+          [corners[0].clone(), corners[2].clone(), corners[3].clone()],
+        ],
+        &PbrMaterial::emissive(Color::white()),
+      );
+      let mut sampler = RandomSampler::new(1);
+      let count = 20000;
+
+      let mut sum = 0.0;
+      for _ in 0..count {
+        sum += estimate_direct(&scene, &hit, &eyev, &mesh, &mut sampler).red;
+      }
+      let estimate = sum / count as f64;
+      let expected = reference(&panel(), &hit, &eyev);
+
+      assert!((estimate - expected).abs() < 0.03 * expected);
+    }
+
+    #[test]
+    fn sampling_one_light_converges_to_the_sum_over_lights() {
+      let mut scene = Planes::default();
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 4.0, 0.0), Color::white()));
+      scene.lights.push(PointLight::new(
+        Tuple::point(0.0, 2.0, 0.0),
+        Color::new(0.5, 0.5, 0.5),
+      ));
+      let hit = floor_hit(PbrMaterial::new(Color::white(), 0.0, 1.0));
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let mut sampler = RandomSampler::new(1);
+      let count = 20000;
+      let lights = SceneLights::new(&scene);
+
+      let mut sum = Color::black();
+      for _ in 0..count {
+        sum += sample_one_light(&scene, &lights, &hit, &eyev, &mut sampler);
+      }
+      let estimate = sum / count as f64;
+      let expected = hit.material.brdf(&eyev, &eyev, &hit.normalv) * (1.0 / 16.0 + 0.5 / 4.0);
+
+      assert!((estimate.red - expected.red).abs() < 0.02 * expected.red);
+    }
+  }
+}
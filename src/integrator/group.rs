@@ -0,0 +1,165 @@
+use super::scene::{Scene, SurfaceInteraction};
+use crate::drawing::color::Color;
+use crate::lighting::light::Light;
+use crate::math::ray::Ray;
+use crate::math::tuple::Tuple;
+
+//  Several scenes seen as one, so a moving scene can sit among static
+//    ones. A ray meets the nearest of the members' hits, the members'
+//    lights are pooled, and the background is the first member's.
+#[derive(Default)]
+pub struct SceneGroup {
+  pub scenes: Vec<Box<dyn Scene>>,
+}
+
+//  Instantiations
+impl SceneGroup {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+//  Methods
+impl SceneGroup {
+  pub fn add<S: Scene + 'static>(&mut self, scene: S) {
+    self.scenes.push(Box::new(scene));
+  }
+}
+
+impl Scene for SceneGroup {
+  fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+    let mut nearest: Option<SurfaceInteraction> = None;
+    for scene in self.scenes.iter() {
+      if let Some(hit) = scene.intersect(ray) {
+        if nearest
+          .as_ref()
+          .is_none_or(|near| hit.distance < near.distance)
+        {
+          nearest = Some(hit);
+        }
+      }
+    }
+    nearest
+  }
+
+  fn lights(&self) -> Vec<&dyn Light> {
+    self
+      .scenes
+      .iter()
+      .flat_map(|scene| scene.lights())
+      .collect()
+  }
+
+  fn background(&self, direction: &Tuple) -> Color {
+    match self.scenes.first() {
+      Some(scene) => scene.background(direction),
+      None => Color::black(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod group_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::integrator::moving::MovingScene;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::lighting::point_light::PointLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::motion::{AnimatedTransform, RigidTransform};
+
+  fn plane(height: f64, color: Color) -> Planes {
+    let mut scene = Planes::default();
+    scene.add(height, 1.0, PbrMaterial::new(color, 0.0, 0.5));
+    scene
+  }
+
+  mod intersect_tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_group_is_never_hit() {
+      let group = SceneGroup::new();
+      let ray = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+      assert!(group.intersect(&ray).is_none());
+      assert!(group.lights().is_empty());
+      assert_feq!(group.background(&ray.direction), Color::black());
+    }
+
+    #[test]
+    fn a_ray_meets_the_nearest_member() {
+      let mut group = SceneGroup::new();
+      group.add(plane(0.0, Color::white()));
+      group.add(plane(2.0, Color::new(1.0, 0.0, 0.0)));
+      let ray = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+      let hit = group.intersect(&ray).unwrap();
+
+      assert_feq!(hit.distance, 3.0);
+      assert_feq!(hit.material.base_color, Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn the_lights_of_every_member_are_pooled() {
+      let mut first = plane(0.0, Color::white());
+      first
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 4.0, 0.0), Color::white()));
+      let mut second = plane(2.0, Color::white());
+      second
+        .lights
+        .push(PointLight::new(Tuple::point(1.0, 4.0, 0.0), Color::white()));
+      let mut group = SceneGroup::new();
+      group.add(first);
+      group.add(second);
+
+      assert_eq!(group.lights().len(), 2);
+    }
+
+    #[test]
+    fn the_background_is_the_first_members() {
+      let sky = Planes {
+        background: Color::new(0.2, 0.3, 0.4),
+        ..Planes::default()
+      };
+      let mut group = SceneGroup::new();
+      group.add(sky);
+      group.add(plane(0.0, Color::white()));
+
+      assert_feq!(
+        group.background(&Tuple::vector(0.0, 1.0, 0.0)),
+        Color::new(0.2, 0.3, 0.4)
+      );
+    }
+  }
+
+  mod motion_tests {
+    use super::*;
+
+    //  A red plane sinks from above a static white floor to below it while
+    //    the shutter is open, so only early rays see it.
+    #[test]
+    fn one_member_moves_while_the_floor_stays_put() {
+      let mut group = SceneGroup::new();
+      group.add(plane(0.0, Color::white()));
+      group.add(MovingScene::new(
+        plane(1.0, Color::new(1.0, 0.0, 0.0)),
+        AnimatedTransform::between(
+          RigidTransform::identity(),
+          RigidTransform::translation(0.0, -2.0, 0.0),
+        ),
+      ));
+      let ray = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+      let early = group.intersect(&ray.clone().with_time(0.0)).unwrap();
+      let late = group.intersect(&ray.with_time(1.0)).unwrap();
+
+      assert_feq!(early.distance, 4.0);
+      assert_feq!(early.material.base_color, Color::new(1.0, 0.0, 0.0));
+      assert_feq!(late.distance, 5.0);
+      assert_feq!(late.material.base_color, Color::white());
+    }
+  }
+}
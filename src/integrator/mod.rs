@@ -0,0 +1,10 @@
+pub mod ambient_occlusion;
+pub mod direct_lighting;
+pub mod group;
+pub mod moving;
+pub mod path_tracer;
+pub mod photon_map;
+pub mod scene;
+pub mod supersampling;
+pub mod tiles;
+pub mod volume;
@@ -0,0 +1,121 @@
+use super::scene::{Scene, SurfaceInteraction};
+use crate::drawing::color::Color;
+use crate::lighting::light::Light;
+use crate::math::motion::AnimatedTransform;
+use crate::math::ray::Ray;
+use crate::math::tuple::Tuple;
+
+//  Geometry carried along a keyframed path. Each ray is taken into the
+//    wrapped scene's space as it stood at the ray's time, and the hit is
+//    brought back out. The wrapped scene's lights stay where they are.
+pub struct MovingScene<S: Scene> {
+  pub scene: S,
+  pub motion: AnimatedTransform,
+}
+
+//  Instantiations
+impl<S: Scene> MovingScene<S> {
+  pub fn new(scene: S, motion: AnimatedTransform) -> Self {
+    Self { scene, motion }
+  }
+}
+
+impl<S: Scene> Scene for MovingScene<S> {
+  fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+    let transform = self.motion.at(ray.time);
+    let local = transform.inverse().apply_ray(ray);
+    let hit = self.scene.intersect(&local)?;
+    Some(SurfaceInteraction {
+      point: transform.apply_point(&hit.point),
+      normalv: transform.apply_vector(&hit.normalv),
+      ..hit
+    })
+  }
+
+  fn lights(&self) -> Vec<&dyn Light> {
+    self.scene.lights()
+  }
+
+  fn background(&self, direction: &Tuple) -> Color {
+    self.scene.background(direction)
+  }
+}
+
+#[cfg(test)]
+mod moving_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::lighting::point_light::PointLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::motion::RigidTransform;
+  use crate::math::quaternion::Quaternion;
+  use std::f64::consts::PI;
+
+  fn rising_floor() -> MovingScene<Planes> {
+    let mut floor = Planes::default();
+    floor.add(0.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 0.5));
+    MovingScene::new(
+      floor,
+      AnimatedTransform::between(
+        RigidTransform::identity(),
+        RigidTransform::translation(0.0, 2.0, 0.0),
+      ),
+    )
+  }
+
+  #[test]
+  fn a_ray_meets_moving_geometry_where_it_stands_at_the_ray_time() {
+    let scene = rising_floor();
+    let ray = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+    let early = scene.intersect(&ray.clone().with_time(0.0)).unwrap();
+    let late = scene.intersect(&ray.with_time(0.5)).unwrap();
+
+    assert_feq!(early.distance, 5.0);
+    assert_feq!(late.distance, 4.0);
+    assert_feq!(late.point, Tuple::point(0.0, 1.0, 0.0));
+    assert_feq!(late.time, 0.5);
+  }
+
+  #[test]
+  fn normals_turn_with_the_geometry() {
+    let mut floor = Planes::default();
+    floor.add(0.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 0.5));
+    let scene = MovingScene::new(
+      floor,
+      AnimatedTransform::between(
+        RigidTransform::identity(),
+        RigidTransform::new(
+          Tuple::zero(),
+          Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), PI / 2.0),
+        ),
+      ),
+    );
+    let ray = Ray::new(Tuple::point(5.0, 0.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)).with_time(1.0);
+
+    let hit = scene.intersect(&ray).unwrap();
+
+    assert_feq!(hit.normalv, Tuple::vector(-1.0, 0.0, 0.0));
+    assert_feq!(hit.distance, 5.0);
+  }
+
+  #[test]
+  fn shadows_are_cast_from_where_the_geometry_stands() {
+    let mut ceiling = Planes::default();
+    ceiling.add(1.0, -1.0, PbrMaterial::new(Color::white(), 0.0, 0.5));
+    let scene = MovingScene::new(
+      ceiling,
+      AnimatedTransform::between(
+        RigidTransform::identity(),
+        RigidTransform::translation(0.0, 4.0, 0.0),
+      ),
+    );
+    let light = PointLight::new(Tuple::point(0.0, 3.0, 0.0), Color::white());
+    let sample = light.incident(&Tuple::origin());
+
+    assert!(scene.is_occluded(&Tuple::origin(), &sample, 0.0));
+    assert!(!scene.is_occluded(&Tuple::origin(), &sample, 1.0));
+  }
+}
@@ -0,0 +1,716 @@
+use super::direct_lighting::{sample_one_light, SceneLights};
+use super::photon_map::PhotonMap;
+use super::scene::Scene;
+use super::tiles::TileRenderer;
+use super::volume::FogVolume;
+use crate::camera::view::Camera;
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampler::Sampler;
+
+#[derive(Clone, Debug)]
+pub struct PathTracer {
+  pub max_depth: usize,
+  pub roulette_depth: usize,
+  pub caustics: Option<PhotonMap>,
+  //  Dims and lights every segment of every path that crosses it.
+  pub fog: Option<FogVolume>,
+  //  One per core unless set otherwise.
+  pub threads: usize,
+}
+
+//  Instantiations
+impl PathTracer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+impl Default for PathTracer {
+  fn default() -> Self {
+    Self {
+      max_depth: 8,
+      roulette_depth: 3,
+      caustics: None,
+      fog: None,
+      threads: TileRenderer::available().threads(),
+    }
+  }
+}
+
+//  Methods
+impl PathTracer {
+  pub fn radiance(&self, scene: &dyn Scene, ray: Ray, sampler: &mut dyn Sampler) -> Color {
+    self.trace(scene, &SceneLights::new(scene), ray, sampler)
+  }
+
+  fn trace(
+    &self,
+    scene: &dyn Scene,
+    lights: &SceneLights,
+    ray: Ray,
+    sampler: &mut dyn Sampler,
+  ) -> Color {
+    let mut radiance = Color::black();
+    let mut throughput = Color::white();
+    let mut ray = ray;
+    //  Light from emitters reached straight from a rough bounce is already
+    //    counted when the scene's lights are sampled, so only emission seen
+    //    directly or through mirrors and glass is added. Without a caustics
+    //    map, that includes emission reached through mirrors and glass after
+    //    a rough bounce, which light sampling cannot find. The same goes for
+    //    the background, which lights the scene through an EnvironmentMap in
+    //    the scene's lights.
+    let mut seen_directly = true;
+    for depth in 0..self.max_depth {
+      let hit = scene.intersect(&ray);
+      if let Some(volume) = &self.fog {
+        let distance = hit.as_ref().map_or(f64::INFINITY, |hit| hit.distance);
+        let (transmittance, scattered) = volume.segment(scene, &ray, distance, sampler);
+        radiance += throughput.clone() * scattered;
+        throughput *= transmittance;
+      }
+      let hit = match hit {
+        Some(hit) => hit,
+        None => {
+          if seen_directly {
+            radiance += throughput * scene.background(&ray.direction);
+          }
+          break;
+        }
+      };
+      let eyev = -ray.direction.clone().normalize();
+      let mut hit = hit;
+      let entering = hit.normalv.clone().dot(eyev.clone()) >= 0.0;
+      if !entering {
+        hit.normalv = -hit.normalv;
+      }
+
+      if seen_directly {
+        radiance += throughput.clone() * hit.material.emission.clone();
+      }
+      if hit.material.is_specular() {
+        let (direction, weight) =
+          hit
+            .material
+            .sample_specular(&eyev, &hit.normalv, entering, sampler.next_1d());
+        throughput *= weight;
+        let reflected = direction.clone().dot(hit.normalv.clone()) > 0.0;
+        let origin = if reflected {
+          hit.over_point()
+        } else {
+          hit.under_point()
+        };
+        //  Differentials follow mirror and glass bounces so textures seen
+        //    through them are filtered over the right footprint. Rough
+        //    bounces drop them.
+        let differentials = ray.differentials.as_ref().and_then(|differentials| {
+          if reflected {
+            differentials.reflect(&hit.point, &hit.normalv)
+          } else {
+            let (n1, n2) = hit.material.refractive_indices(entering);
+            differentials.refract(&hit.point, &hit.normalv, n1 / n2)
+          }
+        });
+        ray = Ray::new(origin, direction)
+          .with_time(hit.time)
+          .with_differentials(differentials);
+        if self.caustics.is_none() {
+          seen_directly = true;
+        }
+        continue;
+      }
+      seen_directly = false;
+
+      let over_point = hit.over_point();
+      radiance += throughput.clone() * sample_one_light(scene, lights, &hit, &eyev, sampler);
+      if let Some(caustics) = &self.caustics {
+        radiance += throughput.clone() * caustics.estimate(&hit, &eyev);
+      }
+
+      let lobe = sampler.next_1d();
+      let (u1, u2) = sampler.next_2d();
+      let sample = match hit.material.sample(&eyev, &hit.normalv, lobe, u1, u2) {
+        Some(sample) => sample,
+        None => break,
+      };
+      let cos = sample.direction.clone().dot(hit.normalv.clone());
+      throughput *= sample.value * (cos / sample.pdf);
+
+      if depth + 1 >= self.roulette_depth {
+        let survival = throughput
+          .red
+          .max(throughput.green)
+          .max(throughput.blue)
+          .min(0.95);
+        if sampler.next_1d() >= survival {
+          break;
+        }
+        throughput /= survival;
+      }
+      ray = Ray::new(over_point, sample.direction).with_time(hit.time);
+    }
+    radiance
+  }
+
+  //  The camera closure maps a continuous pixel position to a primary ray.
+  //    Each pixel draws from its own copy of the sampler, starting pixel
+  //    sample i for its ith sample, so the result does not depend on the
+  //    order pixels are rendered in or on the thread count. The sampler
+  //    decides how many samples each pixel takes.
+  pub fn render<F>(
+    &self,
+    scene: &dyn Scene,
+    width: usize,
+    height: usize,
+    sampler: &dyn Sampler,
+    camera: F,
+  ) -> Canvas
+  where
+    F: Fn(f64, f64) -> Ray + Sync,
+  {
+    self.render_rays(scene, width, height, sampler, |x, y, _| Some(camera(x, y)))
+  }
+
+  //  Pixels the camera has no ray for are left black. Camera rays carry
+  //    differentials for scenes that filter textures.
+  pub fn render_camera(
+    &self,
+    scene: &dyn Scene,
+    camera: &dyn Camera,
+    sampler: &dyn Sampler,
+  ) -> Canvas {
+    self.render_rays(
+      scene,
+      camera.hsize(),
+      camera.vsize(),
+      sampler,
+      |x, y, sampler| {
+        let (open, close) = camera.shutter();
+        let time = open + (close - open) * sampler.next_1d();
+        let (u1, u2) = sampler.next_2d();
+        camera.differential_ray(x, y, u1, u2, time)
+      },
+    )
+  }
+
+  fn render_rays<F>(
+    &self,
+    scene: &dyn Scene,
+    width: usize,
+    height: usize,
+    sampler: &dyn Sampler,
+    camera: F,
+  ) -> Canvas
+  where
+    F: Fn(f64, f64, &mut dyn Sampler) -> Option<Ray> + Sync,
+  {
+    let lights = SceneLights::new(scene);
+    TileRenderer::new(self.threads).render(width, height, |x, y| {
+      let mut sampler = sampler.clone_sampler();
+      let count = sampler.samples_per_pixel();
+      let mut sum = Color::black();
+      for index in 0..count {
+        sampler.start_pixel_sample(x, y, index);
+        let (dx, dy) = sampler.next_2d();
+        if let Some(ray) = camera(x as f64 + dx, y as f64 + dy, sampler.as_mut()) {
+          sum += self.trace(scene, &lights, ray, sampler.as_mut());
+        }
+      }
+      sum / count as f64
+    })
+  }
+}
+
+#[cfg(test)]
+mod path_tracer_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::pbr::{pbr_lighting, PbrMaterial};
+  use crate::lighting::point_light::PointLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::random::Pcg32;
+  use crate::math::sampler::RandomSampler;
+  use crate::math::tuple::Tuple;
+
+  fn white_floor() -> Planes {
+    let mut scene = Planes::default();
+    scene.add(0.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 0.8));
+    scene
+  }
+
+  fn average_radiance(tracer: &PathTracer, scene: &dyn Scene, ray: &Ray, count: usize) -> Color {
+    let mut sampler = RandomSampler::new(1);
+    let mut sum = Color::black();
+    for _ in 0..count {
+      sum += tracer.radiance(scene, ray.clone(), &mut sampler);
+    }
+    sum / count as f64
+  }
+
+  mod radiance_tests {
+    use super::*;
+
+    #[test]
+    fn a_ray_that_misses_everything_sees_the_background() {
+      let mut scene = white_floor();
+      scene.background = Color::new(0.2, 0.3, 0.4);
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let actual = PathTracer::new().radiance(&scene, ray, &mut sampler);
+
+      assert_feq!(actual, Color::new(0.2, 0.3, 0.4));
+    }
+
+    #[test]
+    fn emissive_surfaces_are_visible_to_camera_rays() {
+      let mut scene = Planes::default();
+      scene.add(0.0, 1.0, PbrMaterial::emissive(Color::new(0.5, 1.0, 2.0)));
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let actual = PathTracer::new().radiance(&scene, ray, &mut sampler);
+
+      assert_feq!(actual, Color::new(0.5, 1.0, 2.0));
+    }
+
+    #[test]
+    fn a_single_bounce_gives_the_direct_lighting() {
+      let mut scene = white_floor();
+      let light = PointLight::new(Tuple::point(2.0, 5.0, 0.0), Color::white());
+      scene.lights.push(light.clone());
+      let mut tracer = PathTracer::new();
+      tracer.max_depth = 1;
+      let ray = Ray::new(
+        Tuple::point(0.0, 1.0, -1.0),
+        Tuple::vector(0.0, -1.0, 1.0).normalize(),
+      );
+      let mut sampler = RandomSampler::new(1);
+
+      let actual = tracer.radiance(&scene, ray, &mut sampler);
+      let expected = pbr_lighting(
+        &PbrMaterial::new(Color::white(), 0.0, 0.8),
+        &light,
+        &Tuple::origin(),
+        &Tuple::vector(0.0, 1.0, -1.0).normalize(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        |_| false,
+      );
+
+      //  pbr_lighting leaves out the inverse square falloff.
+      assert_feq!(actual, expected / 29.0);
+    }
+
+    #[test]
+    fn occluded_lights_do_not_contribute() {
+      let mut scene = white_floor();
+      scene.add(2.0, -1.0, PbrMaterial::new(Color::white(), 0.0, 0.8));
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 5.0, 0.0), Color::white()));
+      let mut tracer = PathTracer::new();
+      tracer.max_depth = 1;
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let actual = tracer.radiance(&scene, ray, &mut sampler);
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn indirect_light_bleeds_the_color_of_nearby_surfaces() {
+      let mut scene = white_floor();
+      scene.add(2.0, -1.0, PbrMaterial::new(Color::red(), 0.0, 1.0));
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 1.0, 0.0), Color::white()));
+      let ray = Ray::new(
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(1.0, -1.0, 0.0).normalize(),
+      );
+      let mut direct_only = PathTracer::new();
+      direct_only.max_depth = 1;
+      let full = PathTracer::new();
+
+      let direct = average_radiance(&direct_only, &scene, &ray, 200);
+      let global = average_radiance(&full, &scene, &ray, 200);
+
+      assert!(global.red > direct.red);
+      assert!(global.red - direct.red > global.green - direct.green);
+    }
+
+    #[test]
+    fn russian_roulette_does_not_bias_the_estimate() {
+      let mut scene = white_floor();
+      scene.add(3.0, -1.0, PbrMaterial::new(Color::white(), 0.0, 0.8));
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 1.5, 0.0), Color::white()));
+      let ray = Ray::new(
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.5, -1.0, 0.0).normalize(),
+      );
+      let mut without_roulette = PathTracer::new();
+      without_roulette.roulette_depth = without_roulette.max_depth;
+      let mut with_roulette = PathTracer::new();
+      with_roulette.roulette_depth = 1;
+
+      let expected = average_radiance(&without_roulette, &scene, &ray, 4000);
+      let actual = average_radiance(&with_roulette, &scene, &ray, 4000);
+
+      assert!((actual.red - expected.red).abs() < 0.05 * expected.red);
+    }
+  }
+
+  mod specular_tests {
+    use super::*;
+    use crate::camera::perspective::PerspectiveCamera;
+    use crate::integrator::scene::SurfaceInteraction;
+    use crate::lighting::light::Light;
+    use crate::texture::mipmap::UvDifferentials;
+    use crate::texture::uv_mapping::planar_map;
+    use crate::EPSILON;
+    use std::f64::consts::PI;
+
+    fn floor_under_glass() -> Planes {
+      let mut scene = white_floor();
+      scene.add(2.0, 1.0, PbrMaterial::glass(1.5));
+      scene.add(1.5, -1.0, PbrMaterial::glass(1.5));
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 4.0, 0.0), Color::white()));
+      scene
+    }
+
+    #[test]
+    fn the_background_is_seen_through_glass() {
+      let mut scene = Planes::default();
+      scene.add(0.5, 1.0, PbrMaterial::glass(1.5));
+      scene.add(0.0, -1.0, PbrMaterial::glass(1.5));
+      scene.background = Color::new(0.2, 0.3, 0.4);
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let mut sampler = RandomSampler::new(1);
+
+      for _ in 0..20 {
+        let actual = PathTracer::new().radiance(&scene, ray.clone(), &mut sampler);
+
+        assert_feq!(actual, Color::new(0.2, 0.3, 0.4));
+      }
+    }
+
+    #[test]
+    fn glass_shadows_are_filled_in_by_caustics() {
+      let scene = floor_under_glass();
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let without_caustics = PathTracer::new();
+      let mut with_caustics = PathTracer::new();
+      let mut rng = Pcg32::new(0, 0);
+      with_caustics.caustics = Some(PhotonMap::build(&scene, 20000, 8, &mut rng));
+
+      let dark = average_radiance(&without_caustics, &scene, &ray, 20);
+      let lit = average_radiance(&with_caustics, &scene, &ray, 20);
+
+      assert_feq!(dark, Color::black());
+      assert!(lit.luminance() > 0.0);
+    }
+
+    //  A floor beside a mirror wall at x = 1, under a sky that only shines
+    //    toward -x. Sky the floor sees directly is left to the scene's
+    //    lights, of which there are none, so all its light comes off the
+    //    mirror.
+    struct MirrorWall(Planes);
+
+    impl Scene for MirrorWall {
+      fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+        let floor = self.0.intersect(ray);
+        let t = (1.0 - ray.origin.x) / ray.direction.x;
+        if t < EPSILON || floor.as_ref().is_some_and(|hit| hit.distance <= t) {
+          return floor;
+        }
+        Some(SurfaceInteraction {
+          distance: t,
+          point: ray.position(t),
+          normalv: Tuple::vector(-1.0, 0.0, 0.0),
+          material: PbrMaterial::new(Color::white(), 1.0, 0.0),
+          time: ray.time,
+        })
+      }
+
+      fn lights(&self) -> Vec<&dyn Light> {
+        Vec::new()
+      }
+
+      fn background(&self, direction: &Tuple) -> Color {
+        if direction.x < 0.0 {
+          Color::white()
+        } else {
+          Color::black()
+        }
+      }
+    }
+
+    #[test]
+    fn a_floor_is_lit_through_a_mirror_without_caustics() {
+      let scene = MirrorWall(white_floor());
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let mut with_caustics = PathTracer::new();
+      let mut rng = Pcg32::new(0, 0);
+      with_caustics.caustics = Some(PhotonMap::build(&scene, 100, 8, &mut rng));
+
+      let lit = average_radiance(&PathTracer::new(), &scene, &ray, 200);
+      let left_to_caustics = average_radiance(&with_caustics, &scene, &ray, 200);
+
+      assert!(lit.luminance() > 0.05);
+      assert_feq!(left_to_caustics, Color::black());
+    }
+
+    //  Wraps a scene so its floor at y = 0 glows with the size of the pixel
+    //    footprint a ray's differentials measure there.
+    struct Footprints(Planes);
+
+    impl Scene for Footprints {
+      fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+        let mut hit = self.0.intersect(ray)?;
+        if hit.point.y.abs() < EPSILON {
+          let glow = UvDifferentials::from_ray(ray, &hit.point, &hit.normalv, planar_map).map_or(
+            Color::black(),
+            |footprint| {
+              Color::new(
+                footprint.dudx.hypot(footprint.dvdx),
+                footprint.dudy.hypot(footprint.dvdy),
+                0.0,
+              )
+            },
+          );
+          hit.material = PbrMaterial::emissive(glow);
+        }
+        Some(hit)
+      }
+
+      fn lights(&self) -> Vec<&dyn Light> {
+        self.0.lights()
+      }
+    }
+
+    fn pinhole(from: Tuple, to: Tuple) -> PerspectiveCamera {
+      let mut camera = PerspectiveCamera::new(11, 11, PI / 6.0);
+      camera.look_at(from, to, Tuple::vector(0.0, 0.0, 1.0));
+      camera
+    }
+
+    #[test]
+    fn ray_differentials_follow_mirror_bounces() {
+      let mut mirrored = white_floor();
+      mirrored.add(2.0, -1.0, PbrMaterial::new(Color::white(), 1.0, 0.0));
+      let under_mirror = pinhole(Tuple::point(0.0, 1.0, 0.0), Tuple::point(0.0, 2.0, 0.0));
+      //  Where the floor's reflection puts the camera.
+      let above_floor = pinhole(Tuple::point(0.0, 3.0, 0.0), Tuple::origin());
+      let mut sampler = RandomSampler::new(1);
+
+      let through_mirror = PathTracer::new().radiance(
+        &Footprints(mirrored),
+        under_mirror
+          .differential_ray(5.5, 5.5, 0.5, 0.5, 0.0)
+          .unwrap(),
+        &mut sampler,
+      );
+      let direct = PathTracer::new().radiance(
+        &Footprints(white_floor()),
+        above_floor
+          .differential_ray(5.5, 5.5, 0.5, 0.5, 0.0)
+          .unwrap(),
+        &mut sampler,
+      );
+
+      assert!(direct.red > 0.0 && direct.green > 0.0);
+      assert_feq!(through_mirror, direct);
+    }
+  }
+
+  mod fog_tests {
+    use super::*;
+    use crate::integrator::volume::FogVolume;
+    use crate::lighting::fog::{Everywhere, Fog};
+
+    fn grey(value: f64) -> Color {
+      Color::new(value, value, value)
+    }
+
+    #[test]
+    fn fog_dims_every_segment_of_a_path() {
+      let mut scene = Planes::default();
+      scene.add(0.0, 1.0, PbrMaterial::new(Color::white(), 1.0, 0.0));
+      scene.add(2.0, -1.0, PbrMaterial::emissive(Color::white()));
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let mut tracer = PathTracer::new();
+      //  Stops at the ceiling, before its faint reflection comes back
+      //    through the mirror.
+      tracer.max_depth = 2;
+      tracer.fog = Some(FogVolume::new(
+        Fog::homogeneous(grey(0.2), Color::black(), 0.0),
+        Everywhere,
+      ));
+      let mut sampler = RandomSampler::new(1);
+
+      let actual = tracer.radiance(&scene, ray, &mut sampler);
+
+      //  One unit down to the mirror, then two up to the ceiling.
+      assert_feq!(actual, grey((-0.6_f64).exp()));
+    }
+
+    #[test]
+    fn fog_outside_its_boundary_changes_nothing() {
+      let mut scene = Planes::default();
+      scene.add(0.0, 1.0, PbrMaterial::emissive(Color::white()));
+      let ray = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+      let mut tracer = PathTracer::new();
+      tracer.fog = Some(FogVolume::new(
+        Fog::homogeneous(grey(0.2), grey(0.2), 0.0),
+        |_: &Ray| None,
+      ));
+      let mut sampler = RandomSampler::new(1);
+
+      assert_feq!(tracer.radiance(&scene, ray, &mut sampler), Color::white());
+    }
+
+    #[test]
+    fn lit_fog_glows_where_nothing_is_hit() {
+      let mut scene = Planes::default();
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 3.0, 0.0), Color::white()));
+      let ray = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0));
+      let mut tracer = PathTracer::new();
+      let mut sampler = RandomSampler::new(1);
+
+      let clear = tracer.radiance(&scene, ray.clone(), &mut sampler);
+      tracer.fog = Some(FogVolume::new(
+        Fog::homogeneous(Color::black(), grey(0.05), 0.0),
+        Everywhere,
+      ));
+      let foggy = tracer.radiance(&scene, ray, &mut sampler);
+
+      assert_feq!(clear, Color::black());
+      assert!(foggy.luminance() > 0.0);
+    }
+  }
+
+  mod render_tests {
+    use super::*;
+    use crate::camera::fisheye::FisheyeCamera;
+    use crate::math::sampler::BlueNoiseSampler;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn rendering_is_reproducible_from_the_sampler() {
+      let mut scene = white_floor();
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 3.0, 0.0), Color::white()));
+      let tracer = PathTracer::new();
+      let camera = |x: f64, y: f64| {
+        Ray::new(
+          Tuple::point(0.0, 1.0, 0.0),
+          Tuple::vector(x - 2.0, -2.0, y - 2.0).normalize(),
+        )
+      };
+
+      let first = tracer.render(&scene, 4, 4, &RandomSampler::new(4), camera);
+      let second = tracer.render(&scene, 4, 4, &RandomSampler::new(4), camera);
+
+      assert_eq!(first.width, 4);
+      assert_eq!(first.height, 4);
+      for y in 0..4 {
+        for x in 0..4 {
+          assert_feq!(first.pixel_at(x, y), second.pixel_at(x, y));
+        }
+      }
+    }
+
+    #[test]
+    fn a_new_path_tracer_renders_on_every_core() {
+      let cores = std::thread::available_parallelism().map_or(1, |count| count.get());
+
+      assert_eq!(PathTracer::new().threads, cores);
+    }
+
+    #[test]
+    fn rendering_on_several_threads_matches_one_thread() {
+      let mut scene = white_floor();
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 3.0, 0.0), Color::white()));
+      let mut tracer = PathTracer::new();
+      tracer.threads = 1;
+      let camera = |x: f64, y: f64| {
+        Ray::new(
+          Tuple::point(0.0, 1.0, 0.0),
+          Tuple::vector(x - 20.0, -20.0, y - 20.0).normalize(),
+        )
+      };
+
+      let single = tracer.render(&scene, 40, 40, &RandomSampler::new(2), camera);
+      tracer.threads = 3;
+      let several = tracer.render(&scene, 40, 40, &RandomSampler::new(2), camera);
+
+      for y in 0..40 {
+        for x in 0..40 {
+          assert_feq!(several.pixel_at(x, y), single.pixel_at(x, y));
+        }
+      }
+    }
+
+    #[test]
+    fn a_blue_noise_sampler_converges_to_the_same_image() {
+      let mut scene = white_floor();
+      scene
+        .lights
+        .push(PointLight::new(Tuple::point(0.0, 3.0, 0.0), Color::white()));
+      let tracer = PathTracer::new();
+      let camera = |x: f64, y: f64| {
+        Ray::new(
+          Tuple::point(0.0, 1.0, 0.0),
+          Tuple::vector(x - 4.0, -4.0, y - 4.0).normalize(),
+        )
+      };
+      let mean = |sampler: &dyn Sampler| {
+        let canvas = tracer.render(&scene, 8, 8, sampler, camera);
+        let mut sum = 0.0;
+        for y in 0..8 {
+          for x in 0..8 {
+            sum += canvas.pixel_at(x, y).luminance();
+          }
+        }
+        sum / 64.0
+      };
+
+      let random = mean(&RandomSampler::new(16));
+      let blue_noise = mean(&BlueNoiseSampler::new(16));
+
+      assert!(
+        (blue_noise - random).abs() < 0.05 * random,
+        "{} {}",
+        blue_noise,
+        random
+      );
+    }
+
+    #[test]
+    fn pixels_outside_a_cameras_image_are_black() {
+      let scene = Planes {
+        background: Color::white(),
+        ..Planes::default()
+      };
+      let camera = FisheyeCamera::new(10, 10, PI);
+
+      let canvas = PathTracer::new().render_camera(&scene, &camera, &RandomSampler::new(4));
+
+      assert_feq!(canvas.pixel_at(0, 0), Color::black());
+      assert_feq!(canvas.pixel_at(5, 5), Color::white());
+    }
+  }
+}
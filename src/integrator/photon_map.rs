@@ -0,0 +1,329 @@
+use super::direct_lighting::LightDistribution;
+use super::scene::{Scene, SurfaceInteraction};
+use crate::drawing::color::Color;
+use crate::lighting::light::Light;
+use crate::math::kd_tree::KdTree;
+use crate::math::random::Pcg32;
+use crate::math::ray::Ray;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+//  The incoming direction points back the way the photon came.
+#[derive(Clone, Debug)]
+pub struct Photon {
+  pub incoming: Tuple,
+  pub power: Color,
+}
+
+//  Caustics: photons that reached a diffuse surface through at least one
+//    mirror or glass surface. Light along those paths cannot be found by
+//    sampling lights, so the path tracer reads it from here instead.
+#[derive(Clone, Debug)]
+pub struct PhotonMap {
+  tree: KdTree<Photon>,
+  pub nearest: usize,
+  pub max_radius: f64,
+}
+
+//  Instantiations
+impl PhotonMap {
+  pub fn build(scene: &dyn Scene, photon_count: usize, max_depth: usize, rng: &mut Pcg32) -> Self {
+    //  Lights that cannot launch photons would take a share of the budget
+    //    and skew the probabilities the others are chosen with.
+    let lights: Vec<&dyn Light> = scene
+      .lights()
+      .into_iter()
+      .filter(|light| light.emits_photons())
+      .collect();
+    let distribution = LightDistribution::new(&lights);
+    let mut stored = Vec::new();
+    for _ in 0..photon_count {
+      let (index, probability) = match distribution.sample(rng.next_f64()) {
+        Some(choice) => choice,
+        None => break,
+      };
+      let (ray, flux) = match lights[index].emit(
+        rng.next_f64(),
+        rng.next_f64(),
+        rng.next_f64(),
+        rng.next_f64(),
+      ) {
+        Some(emitted) => emitted,
+        None => continue,
+      };
+      let power = flux / (probability * photon_count as f64);
+      trace_photon(scene, ray, power, max_depth, rng, &mut stored);
+    }
+    Self {
+      tree: KdTree::new(stored),
+      nearest: 50,
+      max_radius: 1.0,
+    }
+  }
+}
+
+//  Methods
+impl PhotonMap {
+  pub fn len(&self) -> usize {
+    self.tree.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.tree.is_empty()
+  }
+
+  //  Density estimation over the nearest photons. When fewer are found, the
+  //    full search radius is taken as the area they were gathered from.
+  pub fn estimate(&self, hit: &SurfaceInteraction, eyev: &Tuple) -> Color {
+    let found = self.tree.nearest(&hit.point, self.nearest, self.max_radius);
+    if found.is_empty() {
+      return Color::black();
+    }
+    let radius = if found.len() < self.nearest {
+      self.max_radius
+    } else {
+      found[found.len() - 1].1
+    };
+    let mut sum = Color::black();
+    for (photon, _) in found {
+      sum += hit.material.brdf(&photon.incoming, eyev, &hit.normalv) * photon.power.clone();
+    }
+    sum / (PI * radius * radius)
+  }
+}
+
+fn trace_photon(
+  scene: &dyn Scene,
+  ray: Ray,
+  power: Color,
+  max_depth: usize,
+  rng: &mut Pcg32,
+  stored: &mut Vec<(Tuple, Photon)>,
+) {
+  let mut ray = ray;
+  let mut power = power;
+  for depth in 0..max_depth {
+    let mut hit = match scene.intersect(&ray) {
+      Some(hit) => hit,
+      None => return,
+    };
+    let incoming = -ray.direction.clone().normalize();
+    let entering = hit.normalv.clone().dot(incoming.clone()) >= 0.0;
+    if !entering {
+      hit.normalv = -hit.normalv;
+    }
+    if !hit.material.is_specular() {
+      if depth > 0 {
+        stored.push((hit.point, Photon { incoming, power }));
+      }
+      return;
+    }
+    let (direction, weight) =
+      hit
+        .material
+        .sample_specular(&incoming, &hit.normalv, entering, rng.next_f64());
+    power *= weight;
+    let origin = if direction.clone().dot(hit.normalv.clone()) > 0.0 {
+      hit.over_point()
+    } else {
+      hit.under_point()
+    };
+    ray = Ray::new(origin, direction).with_time(hit.time);
+  }
+}
+
+#[cfg(test)]
+mod photon_map_tests {
+  use super::*;
+  use crate::integrator::direct_lighting::estimate_direct;
+  use crate::integrator::scene::scene_tests::Planes;
+  use crate::lighting::area_light::AreaLight;
+  use crate::lighting::directional_light::DirectionalLight;
+  use crate::lighting::light::Light;
+  use crate::lighting::mesh_light::MeshLight;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::lighting::point_light::PointLight;
+  use crate::math::sampler::RandomSampler;
+
+  //  A white floor at y = 0 under a glass slab between y = 1.5 and y = 2.
+  fn floor_under_glass(slab: bool) -> Planes {
+    let mut scene = Planes::default();
+    scene.add(0.0, 1.0, PbrMaterial::new(Color::white(), 0.0, 1.0));
+    if slab {
+      scene.add(2.0, 1.0, PbrMaterial::glass(1.5));
+      scene.add(1.5, -1.0, PbrMaterial::glass(1.5));
+    }
+    scene
+  }
+
+  //  Planes only holds point lights, so other lights are added alongside,
+  //    with an optional sun that cannot launch photons.
+  struct WithLight<L: Light> {
+    planes: Planes,
+    light: L,
+    sun: Option<DirectionalLight>,
+  }
+
+  impl<L: Light> WithLight<L> {
+    fn new(planes: Planes, light: L) -> Self {
+      Self {
+        planes,
+        light,
+        sun: None,
+      }
+    }
+  }
+
+  impl<L: Light> Scene for WithLight<L> {
+    fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+      self.planes.intersect(ray)
+    }
+
+    fn lights(&self) -> Vec<&dyn Light> {
+      let mut lights: Vec<&dyn Light> = vec![&self.light];
+      if let Some(sun) = &self.sun {
+        lights.push(sun);
+      }
+      lights
+    }
+  }
+
+  fn small_light() -> AreaLight {
+    AreaLight::rectangle(
+      Tuple::point(-0.25, 4.0, -0.25),
+      Tuple::vector(0.5, 0.0, 0.0),
+      1,
+      Tuple::vector(0.0, 0.0, 0.5),
+      1,
+      Color::new(10.0, 10.0, 10.0),
+    )
+  }
+
+  //  The same square as small_light, as two triangles.
+  fn small_mesh() -> MeshLight {
+    let a = Tuple::point(-0.25, 4.0, -0.25);
+    let b = Tuple::point(0.25, 4.0, -0.25);
+    let c = Tuple::point(0.25, 4.0, 0.25);
+    let d = Tuple::point(-0.25, 4.0, 0.25);
+    MeshLight::new(
+      vec![[a.clone(), b, c.clone()], [a, c, d]],
+      &PbrMaterial::emissive(Color::new(10.0, 10.0, 10.0)),
+    )
+  }
+
+  fn floor_hit() -> SurfaceInteraction {
+    SurfaceInteraction {
+      distance: 1.0,
+      point: Tuple::point(0.0, 0.0, 0.0),
+      normalv: Tuple::vector(0.0, 1.0, 0.0),
+      material: PbrMaterial::new(Color::white(), 0.0, 1.0),
+      time: 0.0,
+    }
+  }
+
+  mod build_tests {
+    use super::*;
+
+    #[test]
+    fn light_that_reaches_a_surface_directly_is_not_stored() {
+      let scene = WithLight::new(floor_under_glass(false), small_light());
+      let mut rng = Pcg32::new(0, 0);
+
+      let map = PhotonMap::build(&scene, 1000, 8, &mut rng);
+
+      assert!(map.is_empty());
+    }
+
+    #[test]
+    fn glass_focuses_photons_onto_the_floor() {
+      let scene = WithLight::new(floor_under_glass(true), small_light());
+      let mut rng = Pcg32::new(0, 0);
+
+      let map = PhotonMap::build(&scene, 1000, 8, &mut rng);
+
+      assert!(!map.is_empty());
+    }
+
+    #[test]
+    fn lights_that_cannot_emit_take_no_photons() {
+      let alone = WithLight::new(floor_under_glass(true), small_light());
+      let mut with_sun = WithLight::new(floor_under_glass(true), small_light());
+      with_sun.sun = Some(DirectionalLight::new(
+        Tuple::vector(0.0, -1.0, 0.0),
+        Color::new(100.0, 100.0, 100.0),
+      ));
+
+      let expected = PhotonMap::build(&alone, 1000, 8, &mut Pcg32::new(0, 0));
+      let map = PhotonMap::build(&with_sun, 1000, 8, &mut Pcg32::new(0, 0));
+
+      assert_eq!(map.len(), expected.len());
+    }
+  }
+
+  mod estimate_tests {
+    use super::*;
+
+    //  A small light of area A at height h delivers L A / h^2. Near normal
+    //    incidence the slab passes (1 - 0.04)^2 of it, and a slab of thickness
+    //    t makes the light appear t (1 - 1 / n) closer.
+    #[test]
+    fn the_caustic_estimate_matches_the_light_through_the_slab() {
+      let light = small_light();
+      let scene = WithLight::new(floor_under_glass(true), light.clone());
+      let mut rng = Pcg32::new(1, 0);
+      let mut map = PhotonMap::build(&scene, 100000, 8, &mut rng);
+      map.nearest = 200;
+      let hit = floor_hit();
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      let estimate = map.estimate(&hit, &up);
+
+      let height: f64 = 4.0 - 0.5 * (1.0 - 1.0 / 1.5);
+      let irradiance = light.intensity.red * light.area() / height.powi(2) * 0.96 * 0.96;
+      let expected = hit.material.brdf(&up, &up, &up).red * irradiance;
+      assert!((estimate.red - expected).abs() < 0.1 * expected);
+    }
+
+    //  The same comparison for a point light, whose photons and direct
+    //    samples must agree on how it falls off with distance.
+    #[test]
+    fn a_point_light_caustic_matches_its_direct_term() {
+      let light = PointLight::new(Tuple::point(0.0, 4.0, 0.0), Color::new(10.0, 10.0, 10.0));
+      let mut slab = floor_under_glass(true);
+      slab.lights.push(light.clone());
+      let mut open = floor_under_glass(false);
+      open.lights.push(light.clone());
+      let mut rng = Pcg32::new(1, 0);
+      let mut map = PhotonMap::build(&slab, 100000, 8, &mut rng);
+      map.nearest = 200;
+      let hit = floor_hit();
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      let estimate = map.estimate(&hit, &up);
+
+      let direct = estimate_direct(&open, &hit, &up, &light, &mut RandomSampler::new(1));
+      let height: f64 = 4.0 - 0.5 * (1.0 - 1.0 / 1.5);
+      let expected = direct.red * (4.0 / height).powi(2) * 0.96 * 0.96;
+      assert!((estimate.red - expected).abs() < 0.1 * expected);
+    }
+
+    //  A mesh light shaped like small_light must throw the same caustic.
+    #[test]
+    fn a_mesh_light_behind_glass_matches_the_area_light() {
+      let light = small_mesh();
+      let scene = WithLight::new(floor_under_glass(true), light.clone());
+      let mut rng = Pcg32::new(1, 0);
+      let mut map = PhotonMap::build(&scene, 100000, 8, &mut rng);
+      map.nearest = 200;
+      let hit = floor_hit();
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      let estimate = map.estimate(&hit, &up);
+
+      let height: f64 = 4.0 - 0.5 * (1.0 - 1.0 / 1.5);
+      let irradiance = light.emission.red * light.area() / height.powi(2) * 0.96 * 0.96;
+      let expected = hit.material.brdf(&up, &up, &up).red * irradiance;
+      assert!((estimate.red - expected).abs() < 0.1 * expected);
+    }
+  }
+}
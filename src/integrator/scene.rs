@@ -0,0 +1,138 @@
+use crate::drawing::color::Color;
+use crate::lighting::light::{Light, LightSample};
+use crate::lighting::pbr::PbrMaterial;
+use crate::math::ray::Ray;
+use crate::math::tuple::Tuple;
+use crate::EPSILON;
+
+#[derive(Clone, Debug)]
+pub struct SurfaceInteraction {
+  pub distance: f64,
+  pub point: Tuple,
+  pub normalv: Tuple,
+  pub material: PbrMaterial,
+  pub time: f64,
+}
+
+//  The queries an integrator needs from whatever holds the geometry. Scenes
+//    are shared between render threads, so they must be Sync.
+pub trait Scene: Sync {
+  fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction>;
+
+  fn lights(&self) -> Vec<&dyn Light>;
+
+  fn background(&self, _direction: &Tuple) -> Color {
+    Color::black()
+  }
+
+  //  The time is the moment the shadow ray is cast, so moving geometry
+  //    shadows from where it is at that moment.
+  fn is_occluded(&self, point: &Tuple, sample: &LightSample, time: f64) -> bool {
+    let ray = Ray::new(point.clone(), sample.direction.clone()).with_time(time);
+    match self.intersect(&ray) {
+      Some(hit) => hit.distance < sample.distance - EPSILON,
+      None => false,
+    }
+  }
+}
+
+//  Methods
+impl SurfaceInteraction {
+  pub fn over_point(&self) -> Tuple {
+    self.point.clone() + self.normalv.clone() * EPSILON
+  }
+
+  pub fn under_point(&self) -> Tuple {
+    self.point.clone() - self.normalv.clone() * EPSILON
+  }
+}
+
+#[cfg(test)]
+pub mod scene_tests {
+  use super::*;
+  use crate::lighting::point_light::PointLight;
+
+  //  Infinite horizontal planes, enough geometry to exercise the integrators.
+  pub struct Planes {
+    pub planes: Vec<(f64, f64, PbrMaterial)>,
+    pub lights: Vec<PointLight>,
+    pub background: Color,
+  }
+
+  impl Default for Planes {
+    fn default() -> Self {
+      Self {
+        planes: Vec::new(),
+        lights: Vec::new(),
+        background: Color::black(),
+      }
+    }
+  }
+
+  impl Planes {
+    pub fn add(&mut self, height: f64, facing: f64, material: PbrMaterial) {
+      self.planes.push((height, facing, material));
+    }
+  }
+
+  impl Scene for Planes {
+    fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+      let mut nearest: Option<SurfaceInteraction> = None;
+      for (height, facing, material) in self.planes.iter() {
+        if ray.direction.y.abs() < EPSILON {
+          continue;
+        }
+        let t = (height - ray.origin.y) / ray.direction.y;
+        if t < EPSILON || nearest.as_ref().is_some_and(|hit| hit.distance <= t) {
+          continue;
+        }
+        nearest = Some(SurfaceInteraction {
+          distance: t,
+          point: ray.position(t),
+          normalv: Tuple::vector(0.0, *facing, 0.0),
+          material: material.clone(),
+          time: ray.time,
+        });
+      }
+      nearest
+    }
+
+    fn lights(&self) -> Vec<&dyn Light> {
+      self
+        .lights
+        .iter()
+        .map(|light| light as &dyn Light)
+        .collect()
+    }
+
+    fn background(&self, _direction: &Tuple) -> Color {
+      self.background.clone()
+    }
+  }
+
+  mod occlusion_tests {
+    use super::*;
+
+    #[test]
+    fn a_light_blocked_by_geometry_is_occluded() {
+      let mut scene = Planes::default();
+      scene.add(1.0, -1.0, PbrMaterial::new(Color::white(), 0.0, 0.5));
+      let light = PointLight::new(Tuple::point(0.0, 2.0, 0.0), Color::white());
+
+      let sample = light.incident(&Tuple::origin());
+
+      assert!(scene.is_occluded(&Tuple::origin(), &sample, 0.0));
+    }
+
+    #[test]
+    fn geometry_beyond_the_light_does_not_occlude() {
+      let mut scene = Planes::default();
+      scene.add(3.0, -1.0, PbrMaterial::new(Color::white(), 0.0, 0.5));
+      let light = PointLight::new(Tuple::point(0.0, 2.0, 0.0), Color::white());
+
+      let sample = light.incident(&Tuple::origin());
+
+      assert!(!scene.is_occluded(&Tuple::origin(), &sample, 0.0));
+    }
+  }
+}
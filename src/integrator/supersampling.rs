@@ -0,0 +1,394 @@
+use super::tiles::TileRenderer;
+use crate::camera::view::Camera;
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::drawing::film::Film;
+use crate::math::ray::Ray;
+use crate::math::sampler::Sampler;
+
+//  Where within a pixel the samples land. Grid and Jittered split the pixel
+//    into n x n cells and take their centers or a random point in each.
+//    Adaptive starts with min_samples random points and keeps adding more
+//    while the variance of the pixel's mean luminance exceeds the threshold.
+#[derive(Clone, Debug)]
+pub enum Supersampling {
+  Grid(usize),
+  Jittered(usize),
+  Adaptive {
+    min_samples: usize,
+    max_samples: usize,
+    threshold: f64,
+  },
+}
+
+//  Renders by averaging several shaded positions per pixel. The nth
+//    position starts pixel sample n of the sampler, so results do not
+//    depend on render order or on the thread count.
+#[derive(Clone, Debug)]
+pub struct Supersampler {
+  pub pattern: Supersampling,
+  //  One per core unless set otherwise.
+  pub threads: usize,
+}
+
+//  Running sums for a pixel's mean color and the spread of its luminance.
+struct Moments {
+  sum: Color,
+  luminance: f64,
+  luminance_squared: f64,
+  count: usize,
+}
+
+//  Instantiations
+impl Supersampler {
+  pub fn new(pattern: Supersampling) -> Self {
+    match pattern {
+      Supersampling::Grid(0) | Supersampling::Jittered(0) => {
+        panic!("Grid and jittered sampling need at least one cell per side.");
+      }
+      Supersampling::Adaptive {
+        min_samples,
+        max_samples,
+        ..
+      } if min_samples < 2 || max_samples < min_samples => {
+        panic!("Adaptive sampling needs at least two samples and max_samples >= min_samples.");
+      }
+      _ => {}
+    }
+    Self {
+      pattern,
+      threads: TileRenderer::available().threads(),
+    }
+  }
+}
+
+impl Default for Moments {
+  fn default() -> Self {
+    Self {
+      sum: Color::black(),
+      luminance: 0.0,
+      luminance_squared: 0.0,
+      count: 0,
+    }
+  }
+}
+
+//  Methods
+impl Supersampling {
+  //  How many samples every pixel takes before any adaptive refinement.
+  pub fn initial_samples(&self) -> usize {
+    match self {
+      Supersampling::Grid(n) | Supersampling::Jittered(n) => n * n,
+      Supersampling::Adaptive { min_samples, .. } => *min_samples,
+    }
+  }
+
+  //  Where within the unit pixel the sample with the given index lands.
+  //    Grid cells are taken row by row.
+  pub fn offset(&self, index: usize, sampler: &mut dyn Sampler) -> (f64, f64) {
+    match self {
+      Supersampling::Grid(n) | Supersampling::Jittered(n) => {
+        let (du, dv) = if matches!(self, Supersampling::Jittered(_)) {
+          sampler.next_2d()
+        } else {
+          (0.5, 0.5)
+        };
+        let cell = 1.0 / *n as f64;
+        (
+          ((index % n) as f64 + du) * cell,
+          ((index / n) as f64 + dv) * cell,
+        )
+      }
+      Supersampling::Adaptive { .. } => sampler.next_2d(),
+    }
+  }
+}
+
+impl Moments {
+  fn add(&mut self, color: Color) {
+    let luminance = color.luminance();
+    self.luminance += luminance;
+    self.luminance_squared += luminance * luminance;
+    self.sum += color;
+    self.count += 1;
+  }
+
+  //  The unbiased sample variance divided by the count.
+  fn variance_of_mean(&self) -> f64 {
+    let n = self.count as f64;
+    let mean = self.luminance / n;
+    (self.luminance_squared / n - mean * mean).max(0.0) / (n - 1.0)
+  }
+}
+
+impl Supersampler {
+  //  The averaged color of pixel (x, y), with the number of samples it took.
+  pub fn pixel<F>(&self, x: usize, y: usize, sampler: &mut dyn Sampler, shade: &F) -> (Color, usize)
+  where
+    F: Fn(f64, f64, &mut dyn Sampler) -> Color,
+  {
+    self.sample_pixel(x, y, sampler, shade, &mut |_, _, _| {})
+  }
+
+  //  Like pixel, also handing every sample and its position to record.
+  fn sample_pixel<F, R>(
+    &self,
+    x: usize,
+    y: usize,
+    sampler: &mut dyn Sampler,
+    shade: &F,
+    record: &mut R,
+  ) -> (Color, usize)
+  where
+    F: Fn(f64, f64, &mut dyn Sampler) -> Color,
+    R: FnMut(f64, f64, &Color),
+  {
+    let mut moments = Moments::default();
+    let mut take = |sampler: &mut dyn Sampler, moments: &mut Moments| {
+      let index = moments.count;
+      sampler.start_pixel_sample(x, y, index);
+      let (du, dv) = self.pattern.offset(index, sampler);
+      let (px, py) = (x as f64 + du, y as f64 + dv);
+      let color = shade(px, py, sampler);
+      record(px, py, &color);
+      moments.add(color);
+    };
+    for _ in 0..self.pattern.initial_samples() {
+      take(sampler, &mut moments);
+    }
+    if let Supersampling::Adaptive {
+      max_samples,
+      threshold,
+      ..
+    } = self.pattern
+    {
+      while moments.count < max_samples && moments.variance_of_mean() > threshold {
+        take(sampler, &mut moments);
+      }
+    }
+    (moments.sum / moments.count as f64, moments.count)
+  }
+
+  //  The shading closure maps a continuous pixel position to a color. It
+  //    draws from a copy of the sampler started for the sample it shades.
+  pub fn render<F>(&self, width: usize, height: usize, sampler: &dyn Sampler, shade: F) -> Canvas
+  where
+    F: Fn(f64, f64, &mut dyn Sampler) -> Color + Sync,
+  {
+    TileRenderer::new(self.threads).render(width, height, |x, y| {
+      let mut sampler = sampler.clone_sampler();
+      self.pixel(x, y, sampler.as_mut(), &shade).0
+    })
+  }
+
+  //  Splats every sample into the film through its reconstruction filter
+  //    instead of averaging within each pixel. Pixels are shaded on the
+  //    tile renderer's threads and splatted in row order afterwards, so the
+  //    film sums its samples in the same order on any thread count.
+  pub fn render_film<F>(&self, film: &mut Film, sampler: &dyn Sampler, shade: F)
+  where
+    F: Fn(f64, f64, &mut dyn Sampler) -> Color + Sync,
+  {
+    let pixels = TileRenderer::new(self.threads).shade_pixels(film.width, film.height, |x, y| {
+      let mut sampler = sampler.clone_sampler();
+      let mut samples = Vec::new();
+      self.sample_pixel(x, y, sampler.as_mut(), &shade, &mut |px, py, color| {
+        samples.push((px, py, color.clone()))
+      });
+      samples
+    });
+    for (px, py, color) in pixels.into_iter().flatten() {
+      film.add_sample(px, py, &color);
+    }
+  }
+
+  //  Shades the camera's rays, sampling the lens and shutter for each one.
+  //    Rays carry differentials for texture filtering. Positions the camera
+  //    has no ray for count as black.
+  pub fn render_camera<F>(&self, camera: &dyn Camera, sampler: &dyn Sampler, shade: F) -> Canvas
+  where
+    F: Fn(Ray, &mut dyn Sampler) -> Color + Sync,
+  {
+    let (open, close) = camera.shutter();
+    self.render(camera.hsize(), camera.vsize(), sampler, |x, y, sampler| {
+      let time = open + (close - open) * sampler.next_1d();
+      let (u1, u2) = sampler.next_2d();
+      match camera.differential_ray(x, y, u1, u2, time) {
+        Some(ray) => shade(ray, sampler),
+        None => Color::black(),
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod supersampling_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::drawing::film::Filter;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::sampler::RandomSampler;
+  use std::cell::Cell;
+
+  //  White left of x = 2.5, black to the right.
+  fn edge(x: f64, _y: f64, _sampler: &mut dyn Sampler) -> Color {
+    if x < 2.5 {
+      Color::white()
+    } else {
+      Color::black()
+    }
+  }
+
+  mod pattern_tests {
+    use super::*;
+
+    #[test]
+    fn a_grid_samples_the_centers_of_its_cells() {
+      let mut random = RandomSampler::new(4);
+      let grid = Supersampling::Grid(2);
+
+      let offsets: Vec<(f64, f64)> = (0..4).map(|i| grid.offset(i, &mut random)).collect();
+
+      assert_eq!(
+        offsets,
+        vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+      );
+    }
+
+    #[test]
+    fn jittered_samples_fall_one_in_each_cell() {
+      let mut random = RandomSampler::new(16);
+      let jittered = Supersampling::Jittered(4);
+
+      let offsets: Vec<(f64, f64)> = (0..16).map(|i| jittered.offset(i, &mut random)).collect();
+
+      assert_eq!(jittered.initial_samples(), 16);
+      for (index, (u, v)) in offsets.iter().enumerate() {
+        assert_eq!((u * 4.0) as usize, index % 4);
+        assert_eq!((v * 4.0) as usize, index / 4);
+      }
+    }
+
+    #[test]
+    #[should_panic]
+    fn an_empty_grid_should_panic() {
+      let _sampler = Supersampler::new(Supersampling::Grid(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn an_empty_jittered_grid_should_panic() {
+      let _sampler = Supersampler::new(Supersampling::Jittered(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn adaptive_sampling_with_fewer_than_two_samples_should_panic() {
+      let _sampler = Supersampler::new(Supersampling::Adaptive {
+        min_samples: 1,
+        max_samples: 8,
+        threshold: 0.01,
+      });
+    }
+  }
+
+  mod render_tests {
+    use super::*;
+
+    #[test]
+    fn a_grid_softens_an_edge_through_a_pixel() {
+      let image =
+        Supersampler::new(Supersampling::Grid(4)).render(5, 1, &RandomSampler::new(16), edge);
+
+      assert_feq!(image.pixel_at(1, 0), Color::white());
+      assert_feq!(image.pixel_at(2, 0), Color::new(0.5, 0.5, 0.5));
+      assert_feq!(image.pixel_at(3, 0), Color::black());
+    }
+
+    #[test]
+    fn jittered_sampling_estimates_the_covered_fraction() {
+      let random = RandomSampler::new(64);
+      let image =
+        Supersampler::new(Supersampling::Jittered(8)).render(5, 1, &random, |x, y, random| {
+          //  An edge at x = 2.3 leaves 30% of pixel 2 white.
+          edge(x + 0.2, y, random)
+        });
+
+      assert!((image.pixel_at(2, 0).red - 0.3).abs() < 0.07);
+    }
+
+    #[test]
+    fn adaptive_sampling_spends_more_samples_on_edges() {
+      let sampler = Supersampler::new(Supersampling::Adaptive {
+        min_samples: 4,
+        max_samples: 64,
+        threshold: 0.001,
+      });
+      let count = |x: usize| sampler.pixel(x, 0, &mut RandomSampler::new(64), &edge).1;
+
+      assert_eq!(count(0), 4);
+      assert!(count(2) > 16);
+      assert_eq!(count(4), 4);
+    }
+
+    #[test]
+    fn a_film_filter_blurs_across_pixel_boundaries() {
+      let sampler = Supersampler::new(Supersampling::Grid(4));
+      let mut boxed = Film::new(5, 1, Filter::Box { radius: 0.5 });
+      let mut tent = Film::new(5, 1, Filter::Tent { radius: 1.5 });
+
+      sampler.render_film(&mut boxed, &RandomSampler::new(16), edge);
+      sampler.render_film(&mut tent, &RandomSampler::new(16), edge);
+
+      assert_feq!(boxed.resolve().pixel_at(1, 0), Color::white());
+      assert!(tent.resolve().pixel_at(1, 0).red < 1.0);
+      assert!(tent.resolve().pixel_at(3, 0).red > 0.0);
+    }
+
+    #[test]
+    fn rendering_on_several_threads_matches_one_thread() {
+      let mut sampler = Supersampler::new(Supersampling::Jittered(2));
+      sampler.threads = 1;
+      let mut single = Film::new(20, 3, Filter::Tent { radius: 1.5 });
+      let mut several = Film::new(20, 3, Filter::Tent { radius: 1.5 });
+      let random = RandomSampler::new(4);
+      let noise =
+        |x: f64, _: f64, random: &mut dyn Sampler| Color::new(x / 20.0, random.next_1d(), 0.0);
+
+      let expected = sampler.render(20, 3, &random, noise);
+      sampler.render_film(&mut single, &random, noise);
+      sampler.threads = 3;
+      let actual = sampler.render(20, 3, &random, noise);
+      sampler.render_film(&mut several, &random, noise);
+
+      let (single, several) = (single.resolve(), several.resolve());
+      for y in 0..3 {
+        for x in 0..20 {
+          assert_feq!(actual.pixel_at(x, y), expected.pixel_at(x, y));
+          assert_feq!(several.pixel_at(x, y), single.pixel_at(x, y));
+        }
+      }
+    }
+
+    #[test]
+    fn adaptive_sampling_stops_at_the_sample_limit() {
+      let sampler = Supersampler::new(Supersampling::Adaptive {
+        min_samples: 4,
+        max_samples: 10,
+        threshold: 0.0,
+      });
+      let calls = Cell::new(0);
+      sampler.pixel(
+        2,
+        0,
+        &mut RandomSampler::new(10),
+        &|x, y, random: &mut dyn Sampler| {
+          calls.set(calls.get() + 1);
+          edge(x, y, random)
+        },
+      );
+
+      assert_eq!(calls.get(), 10);
+    }
+  }
+}
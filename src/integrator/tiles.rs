@@ -0,0 +1,259 @@
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+//  A rectangle of pixels rendered as one unit of work.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tile {
+  pub x: usize,
+  pub y: usize,
+  pub width: usize,
+  pub height: usize,
+}
+
+//  Splits the image into square tiles and shades them on scoped worker
+//    threads, each taking the next unclaimed tile. Every pixel is shaded
+//    independently, so the image does not depend on the thread count as
+//    long as the shading closure seeds its randomness from the pixel.
+#[derive(Clone, Debug)]
+pub struct TileRenderer {
+  threads: usize,
+  pub tile_size: usize,
+}
+
+//  Instantiations
+impl TileRenderer {
+  pub fn new(threads: usize) -> Self {
+    let mut renderer = Self {
+      threads: 1,
+      tile_size: 16,
+    };
+    renderer.set_threads(threads);
+    renderer
+  }
+
+  //  One worker per core the system reports.
+  pub fn available() -> Self {
+    Self::new(thread::available_parallelism().map_or(1, |count| count.get()))
+  }
+}
+
+//  Methods
+impl TileRenderer {
+  pub fn threads(&self) -> usize {
+    self.threads
+  }
+
+  pub fn set_threads(&mut self, threads: usize) {
+    if threads == 0 {
+      panic!("A tile renderer needs at least one thread.");
+    }
+    self.threads = threads;
+  }
+
+  //  Row by row from the top left. Tiles on the right and bottom edges are
+  //    cut short to fit the image.
+  pub fn tiles(&self, width: usize, height: usize) -> Vec<Tile> {
+    let size = self.tile_size.max(1);
+    let mut tiles = Vec::new();
+    for y in (0..height).step_by(size) {
+      for x in (0..width).step_by(size) {
+        tiles.push(Tile {
+          x,
+          y,
+          width: size.min(width - x),
+          height: size.min(height - y),
+        });
+      }
+    }
+    tiles
+  }
+
+  pub fn render<F>(&self, width: usize, height: usize, shade: F) -> Canvas
+  where
+    F: Fn(usize, usize) -> Color + Sync,
+  {
+    let pixels = self.shade_pixels(width, height, shade);
+    let mut canvas = Canvas::new(width, height);
+    for (index, color) in pixels.into_iter().enumerate() {
+      canvas.write_pixel(index % width, index / width, color);
+    }
+    canvas
+  }
+
+  //  Like render, for shading that yields something other than a color.
+  //    The results come back row by row from the top left, whatever order
+  //    the tiles finished in.
+  pub fn shade_pixels<T, F>(&self, width: usize, height: usize, shade: F) -> Vec<T>
+  where
+    T: Send,
+    F: Fn(usize, usize) -> T + Sync,
+  {
+    let tiles = self.tiles(width, height);
+    let next = AtomicUsize::new(0);
+    let work = || {
+      let mut done = Vec::new();
+      loop {
+        let index = next.fetch_add(1, Ordering::Relaxed);
+        let Some(tile) = tiles.get(index) else {
+          break;
+        };
+        let mut values = Vec::with_capacity(tile.width * tile.height);
+        for y in tile.y..tile.y + tile.height {
+          for x in tile.x..tile.x + tile.width {
+            values.push(shade(x, y));
+          }
+        }
+        done.push((tile, values));
+      }
+      done
+    };
+    let finished: Vec<(&Tile, Vec<T>)> = thread::scope(|scope| {
+      let workers: Vec<_> = (0..self.threads.min(tiles.len().max(1)))
+        .map(|_| scope.spawn(work))
+        .collect();
+      workers
+        .into_iter()
+        .flat_map(|worker| worker.join().expect("A render thread panicked."))
+        .collect()
+    });
+
+    let mut pixels: Vec<Option<T>> = (0..width * height).map(|_| None).collect();
+    for (tile, values) in finished {
+      for (index, value) in values.into_iter().enumerate() {
+        let (x, y) = (tile.x + index % tile.width, tile.y + index / tile.width);
+        pixels[y * width + x] = Some(value);
+      }
+    }
+    pixels.into_iter().flatten().collect()
+  }
+}
+
+#[cfg(test)]
+mod tiles_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::random::Pcg32;
+
+  //  Noise seeded from the pixel alone, as the integrators do.
+  fn noise(x: usize, y: usize) -> Color {
+    let mut rng = Pcg32::new(11, (y * 1000 + x) as u64);
+    Color::new(rng.next_f64(), rng.next_f64(), rng.next_f64())
+  }
+
+  mod tile_tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_every_pixel_exactly_once() {
+      let mut renderer = TileRenderer::new(1);
+      renderer.tile_size = 4;
+      let mut covered = vec![0; 10 * 7];
+
+      for tile in renderer.tiles(10, 7) {
+        for y in tile.y..tile.y + tile.height {
+          for x in tile.x..tile.x + tile.width {
+            covered[y * 10 + x] += 1;
+          }
+        }
+      }
+
+      assert!(covered.iter().all(|&count| count == 1));
+    }
+
+    #[test]
+    fn edge_tiles_are_cut_to_fit() {
+      let mut renderer = TileRenderer::new(1);
+      renderer.tile_size = 4;
+
+      let tiles = renderer.tiles(10, 7);
+
+      assert_eq!(tiles.len(), 6);
+      assert_eq!(
+        tiles[5],
+        Tile {
+          x: 8,
+          y: 4,
+          width: 2,
+          height: 3
+        }
+      );
+    }
+  }
+
+  mod thread_tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn a_renderer_without_threads_should_panic() {
+      let _renderer = TileRenderer::new(0);
+    }
+
+    #[test]
+    fn the_thread_count_can_be_changed() {
+      let mut renderer = TileRenderer::new(1);
+
+      renderer.set_threads(3);
+
+      assert_eq!(renderer.threads(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn setting_no_threads_should_panic() {
+      let mut renderer = TileRenderer::new(2);
+
+      renderer.set_threads(0);
+    }
+
+    #[test]
+    fn the_available_renderer_has_at_least_one_thread() {
+      assert!(TileRenderer::available().threads() >= 1);
+    }
+  }
+
+  mod render_tests {
+    use super::*;
+
+    #[test]
+    fn the_image_does_not_depend_on_the_thread_count() {
+      let mut single = TileRenderer::new(1);
+      single.tile_size = 5;
+      let mut many = TileRenderer::new(4);
+      many.tile_size = 3;
+
+      let expected = single.render(23, 17, noise);
+      let actual = many.render(23, 17, noise);
+
+      for y in 0..17 {
+        for x in 0..23 {
+          assert_feq!(actual.pixel_at(x, y), expected.pixel_at(x, y));
+          assert_feq!(actual.pixel_at(x, y), noise(x, y));
+        }
+      }
+    }
+
+    #[test]
+    fn shaded_pixels_come_back_in_row_order() {
+      let mut renderer = TileRenderer::new(3);
+      renderer.tile_size = 2;
+
+      let pixels = renderer.shade_pixels(5, 3, |x, y| (x, y));
+
+      assert_eq!(pixels.len(), 15);
+      assert_eq!(pixels[0], (0, 0));
+      assert_eq!(pixels[7], (2, 1));
+      assert_eq!(pixels[14], (4, 2));
+    }
+
+    #[test]
+    fn more_threads_than_tiles_still_render() {
+      let image = TileRenderer::new(8).render(3, 2, noise);
+
+      assert_feq!(image.pixel_at(2, 1), noise(2, 1));
+    }
+  }
+}
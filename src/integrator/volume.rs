@@ -0,0 +1,331 @@
+use super::scene::Scene;
+use crate::drawing::color::Color;
+use crate::lighting::fog::{Boundary, Fog};
+use crate::math::ray::Ray;
+use crate::math::sampler::Sampler;
+use std::fmt;
+use std::sync::Arc;
+
+//  Fog filling the space a boundary encloses, for an integrator to trace
+//    its rays through. The boundary is shared, so volumes clone cheaply.
+#[derive(Clone)]
+pub struct FogVolume {
+  pub fog: Fog,
+  pub boundary: Arc<dyn Boundary>,
+}
+
+//  Instantiations
+impl FogVolume {
+  pub fn new<B: Boundary + 'static>(fog: Fog, boundary: B) -> Self {
+    Self {
+      fog,
+      boundary: Arc::new(boundary),
+    }
+  }
+}
+
+impl fmt::Debug for FogVolume {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("FogVolume")
+      .field("fog", &self.fog)
+      .finish_non_exhaustive()
+  }
+}
+
+//  Methods
+impl FogVolume {
+  //  See fog_segment.
+  pub fn segment(
+    &self,
+    scene: &dyn Scene,
+    ray: &Ray,
+    distance: f64,
+    sampler: &mut dyn Sampler,
+  ) -> (Color, Color) {
+    fog_segment(
+      scene,
+      &self.fog,
+      self.boundary.as_ref(),
+      ray,
+      distance,
+      sampler,
+    )
+  }
+}
+
+//  Light from the scene's lights scattered once toward the ray's origin by
+//    the fog between start and end, gathered by jittered ray marching with
+//    one light sample per step, weighted by its density.
+pub fn in_scattered(
+  scene: &dyn Scene,
+  fog: &Fog,
+  boundary: &dyn Boundary,
+  ray: &Ray,
+  (start, end): (f64, f64),
+  sampler: &mut dyn Sampler,
+) -> Color {
+  let direction = ray.direction.clone().normalize();
+  let end = end.min(start + fog.max_distance);
+  let step = (end - start) / fog.steps as f64;
+  let entry = ray.position(start);
+  let lights = scene.lights();
+  let mut total = Color::black();
+  for i in 0..fog.steps {
+    let t = start + (i as f64 + sampler.next_1d()) * step;
+    let point = ray.position(t);
+    let toward_eye = fog.transmittance(&entry, &direction, t - start);
+    let scattering = fog.scattering.clone() * fog.density_at(&point);
+    for light in lights.iter() {
+      let (u1, u2) = sampler.next_2d();
+      let (sample, pdf) = match light.sample_incident(&point, u1, u2) {
+        Some((sample, pdf)) if pdf > 0.0 => (sample, pdf),
+        _ => continue,
+      };
+      if scene.is_occluded(&point, &sample, ray.time) {
+        continue;
+      }
+      let phase = fog
+        .phase
+        .phase(direction.clone().dot(sample.direction.clone()));
+      //  Only the stretch toward the light that lies inside the fog dims it.
+      let inside = boundary
+        .span(&Ray::new(point.clone(), sample.direction.clone()).with_time(ray.time))
+        .map_or(0.0, |(_, exit)| exit.max(0.0));
+      let toward_light = fog.transmittance(&point, &sample.direction, inside.min(sample.distance));
+      total += toward_eye.clone()
+        * scattering.clone()
+        * toward_light
+        * sample.intensity
+        * (phase * step / pdf);
+    }
+  }
+  total
+}
+
+//  Attenuates the radiance arriving from a surface at the given distance
+//    along the ray, and adds the light the fog scatters in front of it.
+pub fn through_fog(
+  scene: &dyn Scene,
+  fog: &Fog,
+  boundary: &dyn Boundary,
+  ray: &Ray,
+  distance: f64,
+  radiance: Color,
+  sampler: &mut dyn Sampler,
+) -> Color {
+  let (transmittance, scattered) = fog_segment(scene, fog, boundary, ray, distance, sampler);
+  radiance * transmittance + scattered
+}
+
+//  The transmittance along the ray up to a surface at the given distance,
+//    or to infinity when the ray escapes, paired with the light the fog
+//    scatters toward the ray's origin over that stretch.
+pub fn fog_segment(
+  scene: &dyn Scene,
+  fog: &Fog,
+  boundary: &dyn Boundary,
+  ray: &Ray,
+  distance: f64,
+  sampler: &mut dyn Sampler,
+) -> (Color, Color) {
+  let (start, end) = match boundary.span(ray) {
+    Some((entry, exit)) if exit > entry.max(0.0) && entry < distance => {
+      (entry.max(0.0), exit.min(distance))
+    }
+    _ => return (Color::white(), Color::black()),
+  };
+  let direction = ray.direction.clone().normalize();
+  let transmittance = fog.transmittance(&ray.position(start), &direction, end - start);
+  (
+    transmittance,
+    in_scattered(scene, fog, boundary, ray, (start, end), sampler),
+  )
+}
+
+#[cfg(test)]
+mod volume_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::integrator::scene::SurfaceInteraction;
+  use crate::lighting::fog::Everywhere;
+  use crate::lighting::light::Light;
+  use crate::lighting::pbr::PbrMaterial;
+  use crate::lighting::point_light::PointLight;
+  use crate::lighting::spot_light::SpotLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::sampler::RandomSampler;
+  use crate::math::tuple::Tuple;
+
+  //  Empty space lit by one light, optionally with everything in shadow.
+  struct Lit<L: Light> {
+    light: L,
+    blocked: bool,
+  }
+
+  impl<L: Light> Scene for Lit<L> {
+    fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction> {
+      self.blocked.then(|| SurfaceInteraction {
+        distance: 0.5,
+        point: ray.position(0.5),
+        normalv: -ray.direction.clone(),
+        material: PbrMaterial::new(Color::white(), 0.0, 1.0),
+        time: ray.time,
+      })
+    }
+
+    fn lights(&self) -> Vec<&dyn Light> {
+      vec![&self.light]
+    }
+  }
+
+  fn grey(value: f64) -> Color {
+    Color::new(value, value, value)
+  }
+
+  fn point_lit(blocked: bool) -> Lit<PointLight> {
+    Lit {
+      light: PointLight::new(Tuple::point(0.0, 5.0, 0.0), Color::white()),
+      blocked,
+    }
+  }
+
+  //  A unit sphere at the origin, for rays with unit directions.
+  fn unit_sphere(ray: &Ray) -> Option<(f64, f64)> {
+    let to_origin = ray.origin.clone() - Tuple::origin();
+    let b = to_origin.clone().dot(ray.direction.clone());
+    let c = to_origin.clone().dot(to_origin) - 1.0;
+    let discriminant = b * b - c;
+    (discriminant >= 0.0).then(|| (-b - discriminant.sqrt(), -b + discriminant.sqrt()))
+  }
+
+  mod boundary_tests {
+    use super::*;
+
+    #[test]
+    fn a_ray_that_misses_the_boundary_is_unchanged() {
+      let fog = Fog::homogeneous(grey(0.5), grey(0.5), 0.0);
+      let ray = Ray::new(Tuple::point(0.0, 5.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let color = through_fog(
+        &point_lit(false),
+        &fog,
+        &unit_sphere,
+        &ray,
+        10.0,
+        grey(0.3),
+        &mut sampler,
+      );
+
+      assert_feq!(color, grey(0.3));
+    }
+
+    #[test]
+    fn fog_inside_a_boundary_dims_what_lies_behind_it() {
+      let fog = Fog::homogeneous(grey(0.5), Color::black(), 0.0);
+      let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let color = through_fog(
+        &point_lit(false),
+        &fog,
+        &unit_sphere,
+        &ray,
+        10.0,
+        Color::white(),
+        &mut sampler,
+      );
+
+      assert_feq!(color, grey((-1.0_f64).exp()));
+    }
+  }
+
+  mod scattering_tests {
+    use super::*;
+
+    #[test]
+    fn shadowed_fog_scatters_nothing() {
+      let fog = Fog::homogeneous(grey(0.1), grey(0.1), 0.0);
+      let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+      let mut sampler = RandomSampler::new(1);
+
+      let lit = through_fog(
+        &point_lit(false),
+        &fog,
+        &Everywhere,
+        &ray,
+        10.0,
+        Color::black(),
+        &mut sampler,
+      );
+      let shadowed = through_fog(
+        &point_lit(true),
+        &fog,
+        &Everywhere,
+        &ray,
+        10.0,
+        Color::black(),
+        &mut sampler,
+      );
+
+      assert!(lit.luminance() > 0.0);
+      assert_feq!(shadowed, Color::black());
+    }
+
+    #[test]
+    fn a_spotlight_beam_is_visible_in_fog() {
+      let fog = Fog::homogeneous(grey(0.01), grey(0.05), 0.3);
+      let scene = Lit {
+        light: SpotLight::new(
+          Tuple::point(0.0, 10.0, 0.0),
+          Tuple::vector(0.0, -1.0, 0.0),
+          0.1,
+          0.15,
+          grey(50.0),
+        ),
+        blocked: false,
+      };
+      let through = Ray::new(Tuple::point(0.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+      let beside = Ray::new(Tuple::point(-5.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+      let edge = Ray::new(Tuple::point(1.2, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+      let mut sampler = RandomSampler::new(1);
+      let mut gather = |ray: &Ray| {
+        through_fog(
+          &scene,
+          &fog,
+          &Everywhere,
+          ray,
+          20.0,
+          Color::black(),
+          &mut sampler,
+        )
+      };
+
+      let (through, beside, edge) = (gather(&through), gather(&beside), gather(&edge));
+
+      assert_feq!(beside, Color::black());
+      assert!(edge.luminance() > 0.0);
+      assert!(edge.luminance() < through.luminance());
+    }
+
+    //  Across a unit ball of fog, 1 / (d^2 + z^2) integrates to
+    //    2 atan(1 / d) / d, which is within a percent of 2 / d^2 this far out.
+    #[test]
+    fn in_scattering_from_a_point_light_falls_off_with_the_squared_distance() {
+      let fog = Fog::homogeneous(Color::black(), grey(0.01), 0.0);
+      let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+      let mut sampler = RandomSampler::new(1);
+      let mut gather = |height: f64| {
+        let scene = Lit {
+          light: PointLight::new(Tuple::point(0.0, height, 0.0), Color::white()),
+          blocked: false,
+        };
+        in_scattered(&scene, &fog, &unit_sphere, &ray, (4.0, 6.0), &mut sampler)
+      };
+
+      let (near, far) = (gather(10.0), gather(20.0));
+
+      assert!((near.red / far.red - 4.0).abs() < 0.05);
+    }
+  }
+}
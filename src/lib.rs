@@ -1,5 +1,9 @@
 pub const EPSILON: f64 = 0.00001;
 
+pub mod camera;
 pub mod drawing;
+pub mod integrator;
+pub mod lighting;
 pub mod macros;
 pub mod math;
+pub mod texture;
@@ -0,0 +1,502 @@
+use super::light::{Light, LightSample};
+use crate::drawing::color::Color;
+use crate::math::random::Pcg32;
+use crate::math::ray::Ray;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+#[derive(Clone, Debug)]
+pub enum AreaLightShape {
+  Rectangle {
+    corner: Tuple,
+    full_uvec: Tuple,
+    full_vvec: Tuple,
+  },
+  Disk {
+    center: Tuple,
+    normal: Tuple,
+    radius: f64,
+  },
+}
+
+#[derive(Clone, Debug)]
+pub struct AreaLight {
+  pub shape: AreaLightShape,
+  pub intensity: Color,
+  pub usteps: usize,
+  pub vsteps: usize,
+  pub seed: u64,
+}
+
+//  Instantiations
+impl AreaLight {
+  pub fn rectangle(
+    corner: Tuple,
+    full_uvec: Tuple,
+    usteps: usize,
+    full_vvec: Tuple,
+    vsteps: usize,
+    intensity: Color,
+  ) -> Self {
+    if usteps == 0 || vsteps == 0 {
+      panic!("An area light needs at least one sample step in each direction.");
+    }
+    Self {
+      shape: AreaLightShape::Rectangle {
+        corner,
+        full_uvec,
+        full_vvec,
+      },
+      intensity,
+      usteps,
+      vsteps,
+      seed: 0,
+    }
+  }
+
+  pub fn disk(
+    center: Tuple,
+    normal: Tuple,
+    radius: f64,
+    usteps: usize,
+    vsteps: usize,
+    intensity: Color,
+  ) -> Self {
+    if usteps == 0 || vsteps == 0 {
+      panic!("An area light needs at least one sample step in each direction.");
+    }
+    Self {
+      shape: AreaLightShape::Disk {
+        center,
+        normal: normal.normalize(),
+        radius,
+      },
+      intensity,
+      usteps,
+      vsteps,
+      seed: 0,
+    }
+  }
+}
+
+//  Methods
+impl AreaLight {
+  pub fn sample_count(&self) -> usize {
+    self.usteps * self.vsteps
+  }
+
+  pub fn position(&self) -> Tuple {
+    self.point_on_light(0.5, 0.5)
+  }
+
+  //  One jittered sample per cell of the usteps by vsteps grid. The jitter is
+  //    seeded from the light's seed and the shaded point, so the same point
+  //    always sees the same samples.
+  pub fn sample_points(&self, point: &Tuple) -> Vec<Tuple> {
+    let mut rng = Pcg32::new(self.seed, hash_point(point));
+    let mut points = Vec::with_capacity(self.sample_count());
+    for v in 0..self.vsteps {
+      for u in 0..self.usteps {
+        let s = (u as f64 + rng.next_f64()) / self.usteps as f64;
+        let t = (v as f64 + rng.next_f64()) / self.vsteps as f64;
+        points.push(self.point_on_light(s, t));
+      }
+    }
+    points
+  }
+
+  pub fn intensity_at<F>(&self, point: &Tuple, mut is_shadowed: F) -> f64
+  where
+    F: FnMut(&LightSample) -> bool,
+  {
+    let samples = self.samples(point);
+    let lit = samples.iter().filter(|sample| !is_shadowed(sample)).count();
+    lit as f64 / samples.len() as f64
+  }
+
+  pub fn normal(&self) -> Tuple {
+    match &self.shape {
+      AreaLightShape::Rectangle {
+        full_uvec,
+        full_vvec,
+        ..
+      } => full_uvec.clone().cross(full_vvec.clone()).normalize(),
+      AreaLightShape::Disk { normal, .. } => normal.clone(),
+    }
+  }
+
+  pub fn area(&self) -> f64 {
+    match &self.shape {
+      AreaLightShape::Rectangle {
+        full_uvec,
+        full_vvec,
+        ..
+      } => full_uvec.clone().cross(full_vvec.clone()).magnitude(),
+      AreaLightShape::Disk { radius, .. } => PI * radius * radius,
+    }
+  }
+
+  fn contains(&self, point: &Tuple) -> bool {
+    match &self.shape {
+      AreaLightShape::Rectangle {
+        corner,
+        full_uvec,
+        full_vvec,
+      } => {
+        let offset = point.clone() - corner.clone();
+        let s = offset.clone().dot(full_uvec.clone()) / full_uvec.magnitude_squared();
+        let t = offset.dot(full_vvec.clone()) / full_vvec.magnitude_squared();
+        (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t)
+      }
+      AreaLightShape::Disk { center, radius, .. } => {
+        (point.clone() - center.clone()).magnitude() <= *radius
+      }
+    }
+  }
+
+  fn point_on_light(&self, s: f64, t: f64) -> Tuple {
+    match &self.shape {
+      AreaLightShape::Rectangle {
+        corner,
+        full_uvec,
+        full_vvec,
+      } => corner.clone() + full_uvec.clone() * s + full_vvec.clone() * t,
+      AreaLightShape::Disk {
+        center,
+        normal,
+        radius,
+      } => {
+        let (disk, _) = sampling::concentric_disk(s, t);
+        let (tangent, bitangent) = normal.clone().perpendicular_axes();
+        center.clone() + tangent * (disk.x * radius) + bitangent * (disk.y * radius)
+      }
+    }
+  }
+}
+
+impl Light for AreaLight {
+  fn incident(&self, point: &Tuple) -> LightSample {
+    LightSample::toward(&self.position(), point, self.intensity.clone())
+  }
+
+  fn intensity(&self) -> Color {
+    self.intensity.clone()
+  }
+
+  fn samples(&self, point: &Tuple) -> Vec<LightSample> {
+    self
+      .sample_points(point)
+      .iter()
+      .map(|sample| LightSample::toward(sample, point, self.intensity.clone()))
+      .collect()
+  }
+
+  //  When sampled by area, the intensity is treated as the radiance leaving
+  //    each point of the light, from either side.
+  fn power(&self) -> f64 {
+    2.0 * PI * self.area() * self.intensity.luminance()
+  }
+
+  fn is_delta(&self) -> bool {
+    false
+  }
+
+  //  Photons leave a uniformly chosen point toward either side, cosine
+  //    weighted, which leaves each carrying 2 pi A L.
+  fn emit(&self, u1: f64, u2: f64, u3: f64, u4: f64) -> Option<(Ray, Color)> {
+    let (normal, u3) = if u3 < 0.5 {
+      (self.normal(), 2.0 * u3)
+    } else {
+      (-self.normal(), 2.0 * u3 - 1.0)
+    };
+    let (direction, _) = sampling::cosine_hemisphere(&normal, u3, u4);
+    let flux = self.intensity.clone() * (2.0 * PI * self.area());
+    Some((Ray::new(self.point_on_light(u1, u2), direction), flux))
+  }
+
+  fn emits_photons(&self) -> bool {
+    true
+  }
+
+  fn sample_incident(&self, point: &Tuple, u1: f64, u2: f64) -> Option<(LightSample, f64)> {
+    LightSample::from_surface(
+      &self.point_on_light(u1, u2),
+      &self.normal(),
+      self.area(),
+      point,
+      self.intensity.clone(),
+    )
+  }
+
+  fn emitted_toward(&self, point: &Tuple, direction: &Tuple) -> Option<(LightSample, f64)> {
+    let normal = self.normal();
+    let denominator = direction.clone().dot(normal.clone());
+    if denominator.abs() < f64::EPSILON {
+      return None;
+    }
+    let t = (self.position() - point.clone()).dot(normal) / denominator;
+    if t <= 0.0 {
+      return None;
+    }
+    let light_point = point.clone() + direction.clone() * t;
+    if !self.contains(&light_point) {
+      return None;
+    }
+    LightSample::from_surface(
+      &light_point,
+      &self.normal(),
+      self.area(),
+      point,
+      self.intensity.clone(),
+    )
+  }
+}
+
+fn hash_point(point: &Tuple) -> u64 {
+  [point.x, point.y, point.z]
+    .iter()
+    .fold(0xcbf29ce484222325, |hash, coordinate| {
+      (hash ^ coordinate.to_bits()).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod area_light_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn rectangle_light() -> AreaLight {
+    AreaLight::rectangle(
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::vector(2.0, 0.0, 0.0),
+      4,
+      Tuple::vector(0.0, 0.0, 1.0),
+      2,
+      Color::white(),
+    )
+  }
+
+  mod instantiation_tests {
+    use super::*;
+
+    #[test]
+    fn creating_a_rectangular_area_light() {
+      let light = rectangle_light();
+
+      assert_eq!(light.sample_count(), 8);
+      assert_feq!(light.position(), Tuple::point(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn creating_a_disk_area_light() {
+      let light = AreaLight::disk(
+        Tuple::point(0.0, 5.0, 0.0),
+        Tuple::vector(0.0, -2.0, 0.0),
+        1.0,
+        3,
+        3,
+        Color::white(),
+      );
+
+      assert_eq!(light.sample_count(), 9);
+      assert_feq!(light.position(), Tuple::point(0.0, 5.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_rectangle_without_sample_steps_should_panic() {
+      let _light = AreaLight::rectangle(
+        Tuple::origin(),
+        Tuple::vector(2.0, 0.0, 0.0),
+        0,
+        Tuple::vector(0.0, 0.0, 1.0),
+        2,
+        Color::white(),
+      );
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_disk_without_sample_steps_should_panic() {
+      let _light = AreaLight::disk(
+        Tuple::origin(),
+        Tuple::vector(0.0, -1.0, 0.0),
+        1.0,
+        3,
+        0,
+        Color::white(),
+      );
+    }
+  }
+
+  mod sampling_tests {
+    use super::*;
+
+    #[test]
+    fn each_rectangle_sample_falls_inside_its_own_cell() {
+      let light = rectangle_light();
+
+      let points = light.sample_points(&Tuple::point(0.0, -1.0, 0.0));
+
+      assert_eq!(points.len(), 8);
+      for (index, point) in points.iter().enumerate() {
+        let (u, v) = ((index % 4) as f64, (index / 4) as f64);
+        assert!(point.x >= u * 0.5 && point.x < (u + 1.0) * 0.5);
+        assert!(point.z >= v * 0.5 && point.z < (v + 1.0) * 0.5);
+        assert_feq!(point.y, 0.0);
+      }
+    }
+
+    #[test]
+    fn disk_samples_lie_on_the_disk() {
+      let light = AreaLight::disk(
+        Tuple::point(1.0, 5.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        2.0,
+        4,
+        4,
+        Color::white(),
+      );
+
+      for point in light.sample_points(&Tuple::origin()) {
+        let offset = point - Tuple::point(1.0, 5.0, 0.0);
+        assert_feq!(offset.y, 0.0);
+        assert!(offset.magnitude() <= 2.0);
+      }
+    }
+
+    #[test]
+    fn sampling_is_deterministic_for_a_seed() {
+      let light = rectangle_light();
+      let mut reseeded = rectangle_light();
+      reseeded.seed = 99;
+      let point = Tuple::point(0.3, -1.0, 0.2);
+
+      let first = light.sample_points(&point);
+      let second = light.sample_points(&point);
+      let third = reseeded.sample_points(&point);
+
+      for (a, b) in first.iter().zip(second.iter()) {
+        assert_feq!(a.clone(), b.clone());
+      }
+      assert!(first.iter().zip(third.iter()).any(|(a, c)| a.fuzzy_ne(c)));
+    }
+
+    #[test]
+    fn the_intensity_is_the_fraction_of_unshadowed_samples() {
+      let light = rectangle_light();
+      let point = Tuple::point(1.0, -1.0, 0.0);
+
+      let unshadowed = light.intensity_at(&point, |_| false);
+      let shadowed = light.intensity_at(&point, |_| true);
+      let half = light.intensity_at(&point, |sample| sample.direction.x < 0.0);
+
+      assert_feq!(unshadowed, 1.0);
+      assert_feq!(shadowed, 0.0);
+      assert_feq!(half, 0.5);
+    }
+  }
+
+  mod area_sampling_tests {
+    use super::*;
+
+    #[test]
+    fn the_area_and_normal_of_each_shape() {
+      let rectangle = rectangle_light();
+      let disk = AreaLight::disk(
+        Tuple::point(0.0, 5.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        2.0,
+        1,
+        1,
+        Color::white(),
+      );
+
+      assert_feq!(rectangle.area(), 2.0);
+      assert_feq!(rectangle.normal(), Tuple::vector(0.0, -1.0, 0.0));
+      assert_feq!(disk.area(), 4.0 * PI);
+      assert_feq!(disk.normal(), Tuple::vector(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn area_lights_are_not_delta_lights() {
+      assert!(!rectangle_light().is_delta());
+    }
+
+    #[test]
+    fn a_sampled_direction_finds_the_same_point_on_the_light() {
+      let light = rectangle_light();
+      let point = Tuple::point(0.5, -2.0, 0.3);
+
+      let (sample, pdf) = light.sample_incident(&point, 0.3, 0.8).unwrap();
+      let (hit, hit_pdf) = light.emitted_toward(&point, &sample.direction).unwrap();
+
+      assert_feq!(hit.distance, sample.distance);
+      assert_feq!(hit_pdf, pdf);
+      assert_feq!(hit.intensity, Color::white());
+    }
+
+    #[test]
+    fn directions_that_miss_the_light_see_nothing() {
+      let light = rectangle_light();
+      let point = Tuple::point(0.5, -2.0, 0.3);
+
+      let beside = light.emitted_toward(&point, &Tuple::vector(1.0, 0.1, 0.0).normalize());
+      let away = light.emitted_toward(&point, &Tuple::vector(0.0, -1.0, 0.0));
+
+      assert!(beside.is_none());
+      assert!(away.is_none());
+    }
+
+    #[test]
+    fn the_solid_angle_density_integrates_to_one_over_the_light() {
+      let light = AreaLight::disk(
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        1.0,
+        1,
+        1,
+        Color::white(),
+      );
+      let point = Tuple::origin();
+      let steps = 400;
+      let mut integral = 0.0;
+      for i in 0..steps {
+        let theta = (i as f64 + 0.5) / steps as f64 * (PI / 2.0);
+        for j in 0..steps {
+          let phi = (j as f64 + 0.5) / steps as f64 * (2.0 * PI);
+          let direction = Tuple::vector(
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            theta.sin() * phi.sin(),
+          );
+          if let Some((_, pdf)) = light.emitted_toward(&point, &direction) {
+            integral += pdf * theta.sin();
+          }
+        }
+      }
+      integral *= (PI / 2.0) / steps as f64 * (2.0 * PI) / steps as f64;
+
+      assert!((integral - 1.0).abs() < 0.01);
+    }
+  }
+
+  mod photon_tests {
+    use super::*;
+
+    #[test]
+    fn photons_leave_the_light_from_either_side() {
+      let light = rectangle_light();
+
+      let (front, flux) = light.emit(0.25, 0.5, 0.25, 0.0).unwrap();
+      let (back, _) = light.emit(0.25, 0.5, 0.75, 0.0).unwrap();
+
+      assert_feq!(front.origin, Tuple::point(0.5, 0.0, 0.5));
+      assert!(front.direction.y < 0.0);
+      assert!(back.direction.y > 0.0);
+      assert_feq!(flux, Color::white() * (2.0 * PI * light.area()));
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use super::light::{Light, LightSample};
+use crate::drawing::color::Color;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+#[derive(Clone, Debug)]
+pub struct DirectionalLight {
+  pub direction: Tuple,
+  pub intensity: Color,
+}
+
+//  Instantiations
+impl DirectionalLight {
+  pub fn new(direction: Tuple, intensity: Color) -> Self {
+    Self {
+      direction: direction.normalize(),
+      intensity,
+    }
+  }
+}
+
+impl Light for DirectionalLight {
+  fn incident(&self, _point: &Tuple) -> LightSample {
+    LightSample::new(
+      -self.direction.clone(),
+      f64::INFINITY,
+      self.intensity.clone(),
+    )
+  }
+
+  fn intensity(&self) -> Color {
+    self.intensity.clone()
+  }
+
+  //  There is no scene extent to measure against, so a directional light is
+  //    weighed as the irradiance it delivers.
+  fn power(&self) -> f64 {
+    PI * self.intensity.luminance()
+  }
+}
+
+#[cfg(test)]
+mod directional_light_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  mod incident_tests {
+    use super::*;
+
+    #[test]
+    fn directional_light_arrives_from_the_same_direction_everywhere() {
+      let light = DirectionalLight::new(Tuple::vector(0.0, -2.0, 0.0), Color::white());
+
+      let near = light.incident(&Tuple::origin());
+      let far = light.incident(&Tuple::point(100.0, -50.0, 3.0));
+
+      assert_feq!(near.direction, Tuple::vector(0.0, 1.0, 0.0));
+      assert_feq!(far.direction, Tuple::vector(0.0, 1.0, 0.0));
+      assert_feq!(far.intensity, Color::white());
+    }
+
+    #[test]
+    fn shadows_from_a_directional_light_reach_to_infinity() {
+      let light = DirectionalLight::new(Tuple::vector(1.0, -1.0, 0.0), Color::white());
+
+      let sample = light.incident(&Tuple::origin());
+
+      assert!(sample.distance.is_infinite());
+    }
+  }
+}
@@ -0,0 +1,261 @@
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+//  The region of space a fog fills, as the entry and exit distances along a
+//    ray. Shapes act as boundaries by reporting where a ray enters and leaves
+//    them, and any such closure is a boundary. Boundaries are shared between
+//    render threads, so they must be Send and Sync.
+pub trait Boundary: Send + Sync {
+  fn span(&self, ray: &Ray) -> Option<(f64, f64)>;
+}
+
+impl<F: Fn(&Ray) -> Option<(f64, f64)> + Send + Sync> Boundary for F {
+  fn span(&self, ray: &Ray) -> Option<(f64, f64)> {
+    self(ray)
+  }
+}
+
+//  Fog filling all of space.
+#[derive(Clone, Copy, Debug)]
+pub struct Everywhere;
+
+impl Boundary for Everywhere {
+  fn span(&self, _ray: &Ray) -> Option<(f64, f64)> {
+    Some((0.0, f64::INFINITY))
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HenyeyGreenstein {
+  pub g: f64,
+}
+
+//  Instantiations
+impl HenyeyGreenstein {
+  pub fn new(g: f64) -> Self {
+    Self {
+      g: g.clamp(-0.99, 0.99),
+    }
+  }
+}
+
+//  Methods
+impl HenyeyGreenstein {
+  //  cos_theta is taken between the incoming and scattered directions of
+  //    travel, so a positive g favours forward scattering.
+  pub fn phase(&self, cos_theta: f64) -> f64 {
+    let g = self.g;
+    let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+  }
+
+  //  Picks a scattered direction of travel, distributed by the phase function.
+  pub fn sample(&self, direction: &Tuple, u1: f64, u2: f64) -> Tuple {
+    let g = self.g;
+    let cos_theta = if g.abs() < 1e-3 {
+      1.0 - 2.0 * u1
+    } else {
+      let term = (1.0 - g * g) / (1.0 - g + 2.0 * g * u1);
+      ((1.0 + g * g - term * term) / (2.0 * g)).clamp(-1.0, 1.0)
+    };
+    sampling::spherical_direction(&direction.clone().normalize(), cos_theta, 2.0 * PI * u2)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FogDensity {
+  Homogeneous,
+  //  Density of one at the base height, thinning exponentially above it.
+  Height { base: f64, falloff: f64 },
+}
+
+//  A participating medium. Coefficients are per unit distance at a density
+//    of one.
+#[derive(Clone, Debug)]
+pub struct Fog {
+  pub absorption: Color,
+  pub scattering: Color,
+  pub density: FogDensity,
+  pub phase: HenyeyGreenstein,
+  //  How finely, and how far past the last surface, light scattered toward
+  //    the eye is gathered.
+  pub steps: usize,
+  pub max_distance: f64,
+}
+
+//  Instantiations
+impl Fog {
+  pub fn homogeneous(absorption: Color, scattering: Color, g: f64) -> Self {
+    Self {
+      absorption,
+      scattering,
+      density: FogDensity::Homogeneous,
+      phase: HenyeyGreenstein::new(g),
+      steps: 32,
+      max_distance: 100.0,
+    }
+  }
+
+  pub fn height(absorption: Color, scattering: Color, g: f64, base: f64, falloff: f64) -> Self {
+    Self {
+      density: FogDensity::Height { base, falloff },
+      ..Self::homogeneous(absorption, scattering, g)
+    }
+  }
+}
+
+//  Methods
+impl Fog {
+  pub fn extinction(&self) -> Color {
+    self.absorption.clone() + self.scattering.clone()
+  }
+
+  pub fn density_at(&self, point: &Tuple) -> f64 {
+    match self.density {
+      FogDensity::Homogeneous => 1.0,
+      FogDensity::Height { base, falloff } => (-falloff * (point.y - base)).exp(),
+    }
+  }
+
+  //  The integrated density along a unit direction, in closed form.
+  pub fn optical_depth(&self, from: &Tuple, direction: &Tuple, distance: f64) -> f64 {
+    match self.density {
+      FogDensity::Homogeneous => distance,
+      FogDensity::Height { falloff, .. } => {
+        let rate = falloff * direction.y;
+        let start = self.density_at(from);
+        if rate.abs() < 1e-9 {
+          start * distance
+        } else {
+          start * (1.0 - (-rate * distance).exp()) / rate
+        }
+      }
+    }
+  }
+
+  pub fn transmittance(&self, from: &Tuple, direction: &Tuple, distance: f64) -> Color {
+    let depth = self.optical_depth(from, direction, distance);
+    let channel = |extinction: f64| {
+      if extinction == 0.0 {
+        1.0
+      } else {
+        (-extinction * depth).exp()
+      }
+    };
+    let extinction = self.extinction();
+    Color::new(
+      channel(extinction.red),
+      channel(extinction.green),
+      channel(extinction.blue),
+    )
+  }
+}
+
+#[cfg(test)]
+mod fog_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::random::Pcg32;
+
+  fn grey(value: f64) -> Color {
+    Color::new(value, value, value)
+  }
+
+  mod phase_tests {
+    use super::*;
+
+    #[test]
+    fn an_isotropic_phase_function_is_uniform() {
+      let phase = HenyeyGreenstein::new(0.0);
+
+      assert_feq!(phase.phase(1.0), 1.0 / (4.0 * PI));
+      assert_feq!(phase.phase(-0.3), 1.0 / (4.0 * PI));
+    }
+
+    #[test]
+    fn the_phase_function_integrates_to_one() {
+      let phase = HenyeyGreenstein::new(0.7);
+      let steps = 100000;
+
+      let integral: f64 = (0..steps)
+        .map(|i| {
+          let cos_theta = -1.0 + 2.0 * (i as f64 + 0.5) / steps as f64;
+          2.0 * PI * phase.phase(cos_theta) * 2.0 / steps as f64
+        })
+        .sum();
+
+      assert!((integral - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sampled_directions_average_to_the_asymmetry() {
+      let phase = HenyeyGreenstein::new(0.6);
+      let direction = Tuple::vector(0.0, 0.0, 1.0);
+      let mut rng = Pcg32::new(7, 0);
+      let count = 20000;
+
+      let mean: f64 = (0..count)
+        .map(|_| phase.sample(&direction, rng.next_f64(), rng.next_f64()).z)
+        .sum::<f64>()
+        / count as f64;
+
+      assert!((mean - 0.6).abs() < 0.02);
+    }
+  }
+
+  mod transmittance_tests {
+    use super::*;
+
+    #[test]
+    fn homogeneous_fog_follows_beers_law() {
+      let fog = Fog::homogeneous(Color::new(0.1, 0.2, 0.0), grey(0.1), 0.0);
+
+      let transmittance = fog.transmittance(&Tuple::origin(), &Tuple::vector(1.0, 0.0, 0.0), 2.0);
+
+      assert_feq!(
+        transmittance,
+        Color::new((-0.4_f64).exp(), (-0.6_f64).exp(), (-0.2_f64).exp())
+      );
+    }
+
+    #[test]
+    fn height_fog_matches_a_numerical_integral() {
+      let fog = Fog::height(grey(0.5), grey(0.5), 0.0, 0.0, 0.8);
+      let from = Tuple::point(0.0, 1.0, 0.0);
+      let direction = Tuple::vector(1.0, 2.0, 0.0).normalize();
+      let steps = 10000;
+
+      let numerical: f64 = (0..steps)
+        .map(|i| {
+          let t = 3.0 * (i as f64 + 0.5) / steps as f64;
+          fog.density_at(&(from.clone() + direction.clone() * t)) * 3.0 / steps as f64
+        })
+        .sum();
+
+      assert!((fog.optical_depth(&from, &direction, 3.0) - numerical).abs() < 1e-6);
+    }
+
+    #[test]
+    fn height_fog_lets_light_escape_upward() {
+      let fog = Fog::height(grey(0.5), grey(0.5), 0.0, 0.0, 1.0);
+
+      let up = fog.transmittance(
+        &Tuple::origin(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        f64::INFINITY,
+      );
+      let down = fog.transmittance(
+        &Tuple::origin(),
+        &Tuple::vector(0.0, -1.0, 0.0),
+        f64::INFINITY,
+      );
+
+      assert_feq!(up, grey((-1.0_f64).exp()));
+      assert_feq!(down, Color::black());
+    }
+  }
+}
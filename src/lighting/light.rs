@@ -0,0 +1,100 @@
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::tuple::Tuple;
+
+//  The direction points from the shaded point toward the light, and the
+//    distance is how far a shadow ray must travel to reach it.
+#[derive(Clone, Debug)]
+pub struct LightSample {
+  pub direction: Tuple,
+  pub distance: f64,
+  pub intensity: Color,
+}
+
+//  Lights without area are delta lights: they can only be reached by sampling
+//    them, never by a ray that happens to hit them. Lights are shared between
+//    render threads, so they must be Sync.
+pub trait Light: Sync {
+  fn incident(&self, point: &Tuple) -> LightSample;
+
+  //  The light's own intensity, before any falloff toward a point.
+  fn intensity(&self) -> Color;
+
+  fn power(&self) -> f64;
+
+  fn samples(&self, point: &Tuple) -> Vec<LightSample> {
+    vec![self.incident(point)]
+  }
+
+  fn is_delta(&self) -> bool {
+    true
+  }
+
+  //  Returns a sample along with its probability density over solid angle.
+  fn sample_incident(&self, point: &Tuple, _u1: f64, _u2: f64) -> Option<(LightSample, f64)> {
+    Some((self.incident(point), 1.0))
+  }
+
+  //  The light seen from a point looking along a direction, if the direction
+  //    reaches the light, with the density sample_incident gives it.
+  fn emitted_toward(&self, _point: &Tuple, _direction: &Tuple) -> Option<(LightSample, f64)> {
+    None
+  }
+
+  //  Launches a photon carrying the light's flux divided by the density it
+  //    was chosen with, so photons average to the power the light emits.
+  //    Lights that cannot place photons in the scene return None.
+  fn emit(&self, _u1: f64, _u2: f64, _u3: f64, _u4: f64) -> Option<(Ray, Color)> {
+    None
+  }
+
+  //  Whether emit launches photons, so photon maps only spend their budget
+  //    on lights that do.
+  fn emits_photons(&self) -> bool {
+    false
+  }
+}
+
+//  Instantiations
+impl LightSample {
+  pub fn new(direction: Tuple, distance: f64, intensity: Color) -> Self {
+    Self {
+      direction,
+      distance,
+      intensity,
+    }
+  }
+
+  //  A sample of a surface light chosen uniformly over its area, paired with
+  //    the equivalent density over solid angle seen from the point.
+  pub fn from_surface(
+    light_point: &Tuple,
+    light_normal: &Tuple,
+    area: f64,
+    point: &Tuple,
+    intensity: Color,
+  ) -> Option<(Self, f64)> {
+    let sample = Self::toward(light_point, point, intensity);
+    let cos_light = sample.direction.clone().dot(light_normal.clone()).abs();
+    if cos_light == 0.0 || sample.distance == 0.0 {
+      return None;
+    }
+    let pdf = sample.distance.powi(2) / (cos_light * area);
+    Some((sample, pdf))
+  }
+
+  //  A point at the light itself has no direction toward it, so it gets an
+  //    arbitrary unit direction and no light rather than NaNs.
+  pub fn toward(position: &Tuple, point: &Tuple, intensity: Color) -> Self {
+    let offset = position.clone() - point.clone();
+    let distance = offset.magnitude();
+    if distance == 0.0 {
+      return Self::new(Tuple::vector(0.0, 1.0, 0.0), 0.0, Color::black());
+    }
+    Self {
+      direction: offset / distance,
+      distance,
+      intensity,
+    }
+  }
+}
@@ -0,0 +1,99 @@
+use crate::drawing::color::Color;
+
+#[derive(Clone, Debug)]
+pub struct Material {
+  pub color: Color,
+  pub ambient: f64,
+  pub diffuse: f64,
+  pub specular: f64,
+  pub shininess: f64,
+  pub reflective: f64,
+  pub transparency: f64,
+  pub refractive_index: f64,
+  //  Light the surface gives off whether or not anything lights it.
+  pub emission: Color,
+}
+
+//  Instantiations
+impl Default for Material {
+  fn default() -> Self {
+    Self {
+      color: Color::white(),
+      ambient: 0.1,
+      diffuse: 0.9,
+      specular: 0.9,
+      shininess: 200.0,
+      reflective: 0.0,
+      transparency: 0.0,
+      refractive_index: 1.0,
+      emission: Color::black(),
+    }
+  }
+}
+
+impl Material {
+  pub fn glass() -> Self {
+    Self {
+      transparency: 1.0,
+      refractive_index: 1.5,
+      ..Self::default()
+    }
+  }
+
+  pub fn polished_metal(color: Color) -> Self {
+    Self {
+      color,
+      diffuse: 0.3,
+      specular: 1.0,
+      shininess: 300.0,
+      reflective: 0.9,
+      ..Self::default()
+    }
+  }
+}
+
+#[cfg(test)]
+mod material_tests {
+  use super::*;
+
+  mod instantiation_tests {
+    use super::*;
+    use crate::assert_feq;
+    use crate::macros::fuzzy_eq::FuzzyEq;
+
+    #[test]
+    fn the_default_material() {
+      let m = Material::default();
+
+      assert_feq!(m.color, Color::white());
+      assert_feq!(m.ambient, 0.1);
+      assert_feq!(m.diffuse, 0.9);
+      assert_feq!(m.specular, 0.9);
+      assert_feq!(m.shininess, 200.0);
+    }
+
+    #[test]
+    fn the_default_material_is_opaque_and_not_reflective() {
+      let m = Material::default();
+
+      assert_feq!(m.reflective, 0.0);
+      assert_feq!(m.transparency, 0.0);
+      assert_feq!(m.refractive_index, 1.0);
+    }
+
+    #[test]
+    fn the_default_material_gives_off_no_light() {
+      let m = Material::default();
+
+      assert_feq!(m.emission, Color::black());
+    }
+
+    #[test]
+    fn a_glass_material() {
+      let m = Material::glass();
+
+      assert_feq!(m.transparency, 1.0);
+      assert_feq!(m.refractive_index, 1.5);
+    }
+  }
+}
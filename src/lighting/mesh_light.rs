@@ -0,0 +1,322 @@
+use super::light::{Light, LightSample};
+use super::pbr::PbrMaterial;
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use crate::EPSILON;
+use std::f64::consts::PI;
+
+#[derive(Clone, Debug)]
+pub struct MeshLight {
+  pub triangles: Vec<[Tuple; 3]>,
+  pub emission: Color,
+  cdf: Vec<f64>,
+  area: f64,
+}
+
+//  Instantiations
+impl MeshLight {
+  pub fn new(triangles: Vec<[Tuple; 3]>, material: &PbrMaterial) -> Self {
+    if triangles.is_empty() {
+      panic!("A mesh light needs at least one triangle.");
+    }
+    let areas: Vec<f64> = triangles.iter().map(triangle_area).collect();
+    let area: f64 = areas.iter().sum();
+    let mut cdf = Vec::with_capacity(areas.len());
+    let mut running = 0.0;
+    for triangle_area in areas {
+      running += triangle_area / area;
+      cdf.push(running);
+    }
+    Self {
+      triangles,
+      emission: material.emission.clone(),
+      cdf,
+      area,
+    }
+  }
+}
+
+//  Methods
+impl MeshLight {
+  pub fn area(&self) -> f64 {
+    self.area
+  }
+
+  pub fn centroid(&self) -> Tuple {
+    let mut offset = Tuple::zero();
+    for triangle in self.triangles.iter() {
+      let center = (triangle[0].clone() - Tuple::origin())
+        + (triangle[1].clone() - Tuple::origin())
+        + (triangle[2].clone() - Tuple::origin());
+      offset += center * (triangle_area(triangle) / (3.0 * self.area));
+    }
+    Tuple::origin() + offset
+  }
+
+  //  A point chosen uniformly over the whole mesh, with the normal of the
+  //    triangle it lies on. u1 picks the triangle by area and is then
+  //    stretched back over [0, 1] to place the point within it.
+  fn point_on_mesh(&self, u1: f64, u2: f64) -> (Tuple, Tuple) {
+    let index = self
+      .cdf
+      .iter()
+      .position(|&bound| u1 < bound)
+      .unwrap_or(self.cdf.len() - 1);
+    let lower = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+    let u1 = ((u1 - lower) / (self.cdf[index] - lower)).clamp(0.0, 1.0);
+    let triangle = &self.triangles[index];
+    (
+      sampling::uniform_triangle(triangle, u1, u2).0,
+      triangle_normal(triangle),
+    )
+  }
+
+  fn intersect(&self, point: &Tuple, direction: &Tuple) -> Option<(usize, f64)> {
+    let mut nearest: Option<(usize, f64)> = None;
+    for (index, triangle) in self.triangles.iter().enumerate() {
+      if let Some(t) = intersect_triangle(triangle, point, direction) {
+        if nearest.as_ref().is_none_or(|(_, nearest_t)| t < *nearest_t) {
+          nearest = Some((index, t));
+        }
+      }
+    }
+    nearest
+  }
+}
+
+impl Light for MeshLight {
+  fn incident(&self, point: &Tuple) -> LightSample {
+    LightSample::toward(&self.centroid(), point, self.emission.clone())
+  }
+
+  fn intensity(&self) -> Color {
+    self.emission.clone()
+  }
+
+  //  Triangles emit from both faces, like the rectangular area lights.
+  fn power(&self) -> f64 {
+    2.0 * PI * self.area * self.emission.luminance()
+  }
+
+  fn is_delta(&self) -> bool {
+    false
+  }
+
+  fn sample_incident(&self, point: &Tuple, u1: f64, u2: f64) -> Option<(LightSample, f64)> {
+    let (light_point, normal) = self.point_on_mesh(u1, u2);
+    LightSample::from_surface(
+      &light_point,
+      &normal,
+      self.area,
+      point,
+      self.emission.clone(),
+    )
+  }
+
+  fn emitted_toward(&self, point: &Tuple, direction: &Tuple) -> Option<(LightSample, f64)> {
+    let (index, t) = self.intersect(point, direction)?;
+    let light_point = point.clone() + direction.clone() * t;
+    LightSample::from_surface(
+      &light_point,
+      &triangle_normal(&self.triangles[index]),
+      self.area,
+      point,
+      self.emission.clone(),
+    )
+  }
+
+  //  As for the rectangular area lights: a uniform point, either face and
+  //    a cosine weighted direction, each photon carrying 2 pi A L.
+  fn emit(&self, u1: f64, u2: f64, u3: f64, u4: f64) -> Option<(Ray, Color)> {
+    let (light_point, normal) = self.point_on_mesh(u1, u2);
+    let (normal, u3) = if u3 < 0.5 {
+      (normal, 2.0 * u3)
+    } else {
+      (-normal, 2.0 * u3 - 1.0)
+    };
+    let (direction, _) = sampling::cosine_hemisphere(&normal, u3, u4);
+    let flux = self.emission.clone() * (2.0 * PI * self.area);
+    Some((Ray::new(light_point, direction), flux))
+  }
+
+  fn emits_photons(&self) -> bool {
+    true
+  }
+}
+
+fn edges(triangle: &[Tuple; 3]) -> (Tuple, Tuple) {
+  (
+    triangle[1].clone() - triangle[0].clone(),
+    triangle[2].clone() - triangle[0].clone(),
+  )
+}
+
+fn triangle_area(triangle: &[Tuple; 3]) -> f64 {
+  let (e1, e2) = edges(triangle);
+  e1.cross(e2).magnitude() / 2.0
+}
+
+fn triangle_normal(triangle: &[Tuple; 3]) -> Tuple {
+  let (e1, e2) = edges(triangle);
+  e1.cross(e2).normalize()
+}
+
+//  Moller-Trumbore, returning the distance along the direction.
+fn intersect_triangle(triangle: &[Tuple; 3], point: &Tuple, direction: &Tuple) -> Option<f64> {
+  let (e1, e2) = edges(triangle);
+  let dir_cross_e2 = direction.clone().cross(e2.clone());
+  let determinant = e1.clone().dot(dir_cross_e2.clone());
+  if determinant.abs() < EPSILON {
+    return None;
+  }
+  let f = 1.0 / determinant;
+  let p1_to_origin = point.clone() - triangle[0].clone();
+  let u = f * p1_to_origin.clone().dot(dir_cross_e2);
+  if !(0.0..=1.0).contains(&u) {
+    return None;
+  }
+  let origin_cross_e1 = p1_to_origin.cross(e1);
+  let v = f * direction.clone().dot(origin_cross_e1.clone());
+  if v < 0.0 || u + v > 1.0 {
+    return None;
+  }
+  let t = f * e2.dot(origin_cross_e1);
+  if t > EPSILON {
+    Some(t)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod mesh_light_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  //  A unit square at y = 2, split along its diagonal.
+  fn panel() -> MeshLight {
+    let a = Tuple::point(0.0, 2.0, 0.0);
+    let b = Tuple::point(1.0, 2.0, 0.0);
+    let c = Tuple::point(1.0, 2.0, 1.0);
+    let d = Tuple::point(0.0, 2.0, 1.0);
+    MeshLight::new(
+      vec![[a.clone(), b, c.clone()], [a, c, d]],
+      &PbrMaterial::emissive(Color::new(2.0, 2.0, 2.0)),
+    )
+  }
+
+  mod instantiation_tests {
+    use super::*;
+
+    #[test]
+    fn a_mesh_light_takes_its_emission_from_the_material() {
+      let light = panel();
+
+      assert_feq!(light.emission, Color::new(2.0, 2.0, 2.0));
+      assert_feq!(light.area(), 1.0);
+      assert_feq!(light.centroid(), Tuple::point(0.5, 2.0, 0.5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_mesh_light_without_triangles_should_panic() {
+      let _light = MeshLight::new(vec![], &PbrMaterial::emissive(Color::white()));
+    }
+  }
+
+  mod sampling_tests {
+    use super::*;
+    use crate::math::random::Pcg32;
+
+    #[test]
+    fn a_sampled_direction_finds_the_same_point_on_the_mesh() {
+      let light = panel();
+      let point = Tuple::point(0.2, 0.0, 0.7);
+
+      for (u1, u2) in [(0.1, 0.3), (0.6, 0.9), (0.95, 0.05)] {
+        let (sample, pdf) = light.sample_incident(&point, u1, u2).unwrap();
+        let (hit, hit_pdf) = light.emitted_toward(&point, &sample.direction).unwrap();
+
+        assert_feq!(hit.distance, sample.distance);
+        assert_feq!(hit_pdf, pdf);
+      }
+    }
+
+    #[test]
+    fn samples_cover_triangles_in_proportion_to_their_area() {
+      let a = Tuple::point(0.0, 2.0, 0.0);
+      let light = MeshLight::new(
+        vec![
+          [
+            a.clone(),
+            Tuple::point(1.0, 2.0, 0.0),
+            Tuple::point(0.0, 2.0, 1.0),
+          ],
+          [
+            a,
+            Tuple::point(-3.0, 2.0, 0.0),
+            Tuple::point(0.0, 2.0, -1.0),
+          ],
+        ],
+        &PbrMaterial::emissive(Color::white()),
+      );
+      let mut rng = Pcg32::new(1, 0);
+      let count = 4000;
+
+      let mut on_large = 0;
+      for _ in 0..count {
+        let (sample, _) = light
+          .sample_incident(&Tuple::origin(), rng.next_f64(), rng.next_f64())
+          .unwrap();
+        if sample.direction.x < 0.0 {
+          on_large += 1;
+        }
+      }
+
+      assert!((on_large as f64 / count as f64 - 0.75).abs() < 0.03);
+    }
+
+    #[test]
+    fn the_solid_angle_density_integrates_to_one_over_the_mesh() {
+      let light = panel();
+      let point = Tuple::point(0.5, 1.0, 0.5);
+      let steps = 400;
+      let mut integral = 0.0;
+      for i in 0..steps {
+        let theta = (i as f64 + 0.5) / steps as f64 * (PI / 2.0);
+        for j in 0..steps {
+          let phi = (j as f64 + 0.5) / steps as f64 * (2.0 * PI);
+          let direction = Tuple::vector(
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            theta.sin() * phi.sin(),
+          );
+          if let Some((_, pdf)) = light.emitted_toward(&point, &direction) {
+            integral += pdf * theta.sin();
+          }
+        }
+      }
+      integral *= (PI / 2.0) / steps as f64 * (2.0 * PI) / steps as f64;
+
+      assert!((integral - 1.0).abs() < 0.01);
+    }
+  }
+  mod photon_tests {
+    use super::*;
+
+    #[test]
+    fn photons_leave_the_mesh_from_either_side() {
+      let light = panel();
+
+      let (front, flux) = light.emit(0.25, 0.5, 0.25, 0.0).unwrap();
+      let (back, _) = light.emit(0.25, 0.5, 0.75, 0.0).unwrap();
+
+      assert_feq!(front.origin.y, 2.0);
+      assert!(front.direction.y * back.direction.y < 0.0);
+      assert_feq!(flux, Color::new(2.0, 2.0, 2.0) * (2.0 * PI));
+    }
+  }
+}
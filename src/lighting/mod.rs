@@ -0,0 +1,13 @@
+pub mod area_light;
+pub mod directional_light;
+pub mod fog;
+pub mod light;
+pub mod material;
+pub mod mesh_light;
+pub mod optics;
+pub mod pbr;
+pub mod phong;
+pub mod point_light;
+pub mod sky;
+pub mod sphere_light;
+pub mod spot_light;
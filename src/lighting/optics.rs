@@ -0,0 +1,321 @@
+use super::material::Material;
+use crate::drawing::color::Color;
+use crate::math::tuple::Tuple;
+
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+
+//  Each hit pairs an object identifier with that object's refractive index, in
+//    the order the ray meets them.
+pub fn refractive_indices<T: PartialEq>(hits: &[(T, f64)], hit_index: usize) -> (f64, f64) {
+  let mut containers: Vec<&(T, f64)> = Vec::new();
+  let mut n1 = 1.0;
+  let mut n2 = 1.0;
+  for (index, hit) in hits.iter().enumerate() {
+    if index == hit_index {
+      n1 = containers.last().map_or(1.0, |container| container.1);
+    }
+    match containers.iter().position(|container| container.0 == hit.0) {
+      Some(position) => {
+        containers.remove(position);
+      }
+      None => containers.push(hit),
+    }
+    if index == hit_index {
+      n2 = containers.last().map_or(1.0, |container| container.1);
+      break;
+    }
+  }
+  (n1, n2)
+}
+
+pub fn schlick(eyev: &Tuple, normalv: &Tuple, n1: f64, n2: f64) -> f64 {
+  let mut cos = eyev.clone().dot(normalv.clone());
+  if n1 > n2 {
+    let ratio = n1 / n2;
+    let sin2_t = ratio.powi(2) * (1.0 - cos.powi(2));
+    if sin2_t > 1.0 {
+      return 1.0;
+    }
+    cos = (1.0 - sin2_t).sqrt();
+  }
+  let r0 = ((n1 - n2) / (n1 + n2)).powi(2);
+  r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+}
+
+//  The trace closure receives the new ray direction and the remaining depth,
+//    and returns the color seen along that direction.
+pub fn reflected_color<F>(
+  material: &Material,
+  direction: &Tuple,
+  normalv: &Tuple,
+  remaining: usize,
+  trace: F,
+) -> Color
+where
+  F: FnOnce(Tuple, usize) -> Color,
+{
+  if remaining == 0 || material.reflective == 0.0 {
+    return Color::black();
+  }
+  let reflectv = direction.clone().reflect(normalv.clone());
+  trace(reflectv, remaining - 1) * material.reflective
+}
+
+pub fn refracted_color<F>(
+  material: &Material,
+  eyev: &Tuple,
+  normalv: &Tuple,
+  (n1, n2): (f64, f64),
+  remaining: usize,
+  trace: F,
+) -> Color
+where
+  F: FnOnce(Tuple, usize) -> Color,
+{
+  if remaining == 0 || material.transparency == 0.0 {
+    return Color::black();
+  }
+  match (-eyev.clone()).refract(normalv.clone(), n1 / n2) {
+    Some(direction) => trace(direction, remaining - 1) * material.transparency,
+    None => Color::black(),
+  }
+}
+
+pub fn blend(
+  material: &Material,
+  surface: Color,
+  reflected: Color,
+  refracted: Color,
+  reflectance: f64,
+) -> Color {
+  if material.reflective > 0.0 && material.transparency > 0.0 {
+    surface + reflected * reflectance + refracted * (1.0 - reflectance)
+  } else {
+    surface + reflected + refracted
+  }
+}
+
+#[cfg(test)]
+mod optics_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use std::f64::consts::FRAC_1_SQRT_2;
+
+  mod refractive_index_tests {
+    use super::*;
+
+    #[test]
+    fn finding_n1_and_n2_at_various_intersections() {
+      let hits = [
+        ("a", 1.5),
+        ("b", 2.0),
+        ("c", 2.5),
+        ("b", 2.0),
+        ("c", 2.5),
+        ("a", 1.5),
+      ];
+      let expected = [
+        (1.0, 1.5),
+        (1.5, 2.0),
+        (2.0, 2.5),
+        (2.5, 2.5),
+        (2.5, 1.5),
+        (1.5, 1.0),
+      ];
+
+      for (index, (n1, n2)) in expected.iter().enumerate() {
+        let actual = refractive_indices(&hits, index);
+
+        assert_feq!(actual.0, *n1);
+        assert_feq!(actual.1, *n2);
+      }
+    }
+  }
+
+  mod schlick_tests {
+    use super::*;
+
+    #[test]
+    fn the_schlick_approximation_under_total_internal_reflection() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2);
+
+      let actual = schlick(&eyev, &normalv, 1.5, 1.0);
+
+      assert_feq!(actual, 1.0);
+    }
+
+    #[test]
+    fn the_schlick_approximation_with_a_perpendicular_viewing_angle() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = schlick(&eyev, &normalv, 1.0, 1.5);
+
+      assert_feq!(actual, 0.04);
+    }
+
+    #[test]
+    fn the_schlick_approximation_with_small_angle_and_n2_greater_than_n1() {
+      let eyev = Tuple::vector(0.0, 0.0, -1.0);
+      let normalv = Tuple::vector(0.0, 0.99, -(1.0_f64 - 0.99 * 0.99).sqrt());
+
+      let actual = schlick(&eyev, &normalv, 1.0, 1.5);
+
+      assert!((actual - 0.48873).abs() < 0.0001);
+    }
+  }
+
+  mod recursion_tests {
+    use super::*;
+
+    fn trace_gray(_direction: Tuple, _remaining: usize) -> Color {
+      Color::new(0.5, 0.5, 0.5)
+    }
+
+    #[test]
+    fn the_reflected_color_for_a_nonreflective_material_is_black() {
+      let direction = Tuple::vector(0.0, -1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = reflected_color(&Material::default(), &direction, &normalv, 5, trace_gray);
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn the_reflected_color_for_a_reflective_material_traces_the_mirror_direction() {
+      let material = Material {
+        reflective: 0.5,
+        ..Material::default()
+      };
+      let direction = Tuple::vector(1.0, -1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = reflected_color(&material, &direction, &normalv, 5, |reflectv, remaining| {
+        assert_feq!(reflectv, Tuple::vector(1.0, 1.0, 0.0));
+        assert_eq!(remaining, 4);
+        Color::white()
+      });
+
+      assert_feq!(actual, Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn the_reflected_color_at_the_maximum_recursive_depth_is_black() {
+      let material = Material {
+        reflective: 0.5,
+        ..Material::default()
+      };
+      let direction = Tuple::vector(0.0, -1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = reflected_color(&material, &direction, &normalv, 0, trace_gray);
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn the_refracted_color_with_an_opaque_surface_is_black() {
+      let eyev = Tuple::vector(0.0, 0.0, -1.0);
+      let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+      let actual = refracted_color(
+        &Material::default(),
+        &eyev,
+        &normalv,
+        (1.0, 1.5),
+        5,
+        trace_gray,
+      );
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn the_refracted_color_at_the_maximum_recursive_depth_is_black() {
+      let eyev = Tuple::vector(0.0, 0.0, -1.0);
+      let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+      let actual = refracted_color(
+        &Material::glass(),
+        &eyev,
+        &normalv,
+        (1.0, 1.5),
+        0,
+        trace_gray,
+      );
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn the_refracted_color_under_total_internal_reflection_is_black() {
+      let eyev = Tuple::vector(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = refracted_color(
+        &Material::glass(),
+        &eyev,
+        &normalv,
+        (1.5, 1.0),
+        5,
+        trace_gray,
+      );
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn the_refracted_color_traces_the_refracted_direction() {
+      let eyev = Tuple::vector(0.0, 0.0, -1.0);
+      let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+      let actual = refracted_color(
+        &Material::glass(),
+        &eyev,
+        &normalv,
+        (1.0, 1.5),
+        5,
+        |direction, _remaining| {
+          assert_feq!(direction, Tuple::vector(0.0, 0.0, 1.0));
+          Color::new(0.2, 0.4, 0.6)
+        },
+      );
+
+      assert_feq!(actual, Color::new(0.2, 0.4, 0.6));
+    }
+
+    #[test]
+    fn blending_a_reflective_transparent_material_uses_the_reflectance() {
+      let material = Material {
+        reflective: 0.5,
+        ..Material::glass()
+      };
+
+      let actual = blend(
+        &material,
+        Color::new(0.1, 0.1, 0.1),
+        Color::new(1.0, 0.0, 0.0),
+        Color::new(0.0, 1.0, 0.0),
+        0.25,
+      );
+
+      assert_feq!(actual, Color::new(0.35, 0.85, 0.1));
+    }
+
+    #[test]
+    fn blending_an_opaque_material_adds_the_contributions() {
+      let actual = blend(
+        &Material::default(),
+        Color::new(0.1, 0.1, 0.1),
+        Color::new(0.2, 0.0, 0.0),
+        Color::black(),
+        0.25,
+      );
+
+      assert_feq!(actual, Color::new(0.3, 0.1, 0.1));
+    }
+  }
+}
@@ -0,0 +1,563 @@
+use super::light::{Light, LightSample};
+use super::optics::schlick;
+use crate::drawing::color::Color;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+const DIELECTRIC_REFLECTANCE: f64 = 0.04;
+const MIN_ALPHA: f64 = 0.001;
+
+#[derive(Clone, Debug)]
+pub struct BsdfSample {
+  pub direction: Tuple,
+  pub value: Color,
+  pub pdf: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct PbrMaterial {
+  pub base_color: Color,
+  pub metallic: f64,
+  pub roughness: f64,
+  pub emission: Color,
+  //  The fraction of the light refracted into a smooth dielectric. Anything
+  //    transmissive is treated as perfectly smooth glass.
+  pub transmission: f64,
+  pub refractive_index: f64,
+}
+
+//  Instantiations
+impl PbrMaterial {
+  pub fn new(base_color: Color, metallic: f64, roughness: f64) -> Self {
+    Self {
+      base_color,
+      metallic: metallic.clamp(0.0, 1.0),
+      roughness: roughness.clamp(0.0, 1.0),
+      emission: Color::black(),
+      transmission: 0.0,
+      refractive_index: 1.5,
+    }
+  }
+
+  pub fn glass(refractive_index: f64) -> Self {
+    Self {
+      transmission: 1.0,
+      refractive_index,
+      ..Self::new(Color::white(), 0.0, 0.0)
+    }
+  }
+
+  pub fn emissive(emission: Color) -> Self {
+    Self {
+      emission,
+      ..Self::new(Color::black(), 0.0, 1.0)
+    }
+  }
+}
+
+//  Methods
+impl PbrMaterial {
+  //  Glass and perfect mirrors scatter into a single direction, so they are
+  //    followed rather than sampled.
+  pub fn is_specular(&self) -> bool {
+    self.transmission > 0.0 || (self.metallic == 1.0 && self.roughness == 0.0)
+  }
+
+  //  The normal faces the eye, and entering says whether the eye is outside
+  //    the surface. Glass picks reflection or refraction by their Fresnel
+  //    weights, so only the absorbed part is left in the returned weight.
+  pub fn sample_specular(
+    &self,
+    eyev: &Tuple,
+    normalv: &Tuple,
+    entering: bool,
+    u: f64,
+  ) -> (Tuple, Color) {
+    let reflected = (-eyev.clone()).reflect(normalv.clone());
+    if self.transmission <= 0.0 {
+      let cos = eyev.clone().dot(normalv.clone());
+      return (reflected, fresnel_schlick(cos, self.f0()));
+    }
+    let (n1, n2) = self.refractive_indices(entering);
+    if u < schlick(eyev, normalv, n1, n2) {
+      return (reflected, Color::white());
+    }
+    match (-eyev.clone()).refract(normalv.clone(), n1 / n2) {
+      Some(refracted) => (refracted, self.base_color.clone() * self.transmission),
+      None => (reflected, Color::white()),
+    }
+  }
+
+  //  The indices on the eye's side of the surface and on the far side.
+  pub fn refractive_indices(&self, entering: bool) -> (f64, f64) {
+    if entering {
+      (1.0, self.refractive_index)
+    } else {
+      (self.refractive_index, 1.0)
+    }
+  }
+
+  pub fn alpha(&self) -> f64 {
+    (self.roughness * self.roughness).max(MIN_ALPHA)
+  }
+
+  pub fn f0(&self) -> Color {
+    let dielectric = Color::new(
+      DIELECTRIC_REFLECTANCE,
+      DIELECTRIC_REFLECTANCE,
+      DIELECTRIC_REFLECTANCE,
+    );
+    dielectric * (1.0 - self.metallic) + self.base_color.clone() * self.metallic
+  }
+
+  //  Cook-Torrance: a Lambertian lobe for the light that enters dielectrics,
+  //    plus the GGX specular lobe D * G * F / (4 (n.l) (n.v)).
+  pub fn brdf(&self, lightv: &Tuple, eyev: &Tuple, normalv: &Tuple) -> Color {
+    let n_dot_l = normalv.clone().dot(lightv.clone());
+    let n_dot_v = normalv.clone().dot(eyev.clone());
+    if n_dot_l <= 0.0 || n_dot_v <= 0.0 {
+      return Color::black();
+    }
+    let halfway = (lightv.clone() + eyev.clone()).normalize();
+    let n_dot_h = normalv.clone().dot(halfway.clone()).max(0.0);
+    let v_dot_h = eyev.clone().dot(halfway).max(0.0);
+    let alpha = self.alpha();
+
+    let fresnel = fresnel_schlick(v_dot_h, self.f0());
+    let distribution = ggx_distribution(n_dot_h, alpha);
+    let geometry = smith_geometry(n_dot_v, n_dot_l, alpha);
+    let specular = fresnel.clone() * (distribution * geometry / (4.0 * n_dot_l * n_dot_v));
+
+    let white = Color::white();
+    let diffuse = (white - fresnel) * self.base_color.clone() * ((1.0 - self.metallic) / PI);
+    diffuse + specular
+  }
+
+  //  Half of the non-metallic samples go to the diffuse lobe, the rest
+  //    importance sample the GGX distribution of half vectors.
+  pub fn specular_probability(&self) -> f64 {
+    0.5 + 0.5 * self.metallic
+  }
+
+  pub fn pdf(&self, lightv: &Tuple, eyev: &Tuple, normalv: &Tuple) -> f64 {
+    let n_dot_l = normalv.clone().dot(lightv.clone());
+    if n_dot_l <= 0.0 {
+      return 0.0;
+    }
+    let halfway = (lightv.clone() + eyev.clone()).normalize();
+    let n_dot_h = normalv.clone().dot(halfway.clone()).max(0.0);
+    let v_dot_h = eyev.clone().dot(halfway).abs().max(f64::EPSILON);
+    let specular_pdf = ggx_distribution(n_dot_h, self.alpha()) * n_dot_h / (4.0 * v_dot_h);
+    let diffuse_pdf = n_dot_l / PI;
+    let p = self.specular_probability();
+    p * specular_pdf + (1.0 - p) * diffuse_pdf
+  }
+
+  pub fn sample(
+    &self,
+    eyev: &Tuple,
+    normalv: &Tuple,
+    u1: f64,
+    u2: f64,
+    u3: f64,
+  ) -> Option<BsdfSample> {
+    let direction = if u1 < self.specular_probability() {
+      let alpha = self.alpha();
+      let cos_theta = ((1.0 - u3) / (1.0 + (alpha * alpha - 1.0) * u3)).sqrt();
+      let halfway = sampling::spherical_direction(normalv, cos_theta, 2.0 * PI * u2);
+      (-eyev.clone()).reflect(halfway)
+    } else {
+      sampling::cosine_hemisphere(normalv, u3, u2).0
+    };
+    let pdf = self.pdf(&direction, eyev, normalv);
+    if pdf <= 0.0 {
+      return None;
+    }
+    Some(BsdfSample {
+      value: self.brdf(&direction, eyev, normalv),
+      direction,
+      pdf,
+    })
+  }
+}
+
+pub fn ggx_distribution(n_dot_h: f64, alpha: f64) -> f64 {
+  let alpha2 = alpha * alpha;
+  let denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+  alpha2 / (PI * denominator * denominator)
+}
+
+pub fn smith_g1(n_dot_x: f64, alpha: f64) -> f64 {
+  let alpha2 = alpha * alpha;
+  2.0 * n_dot_x / (n_dot_x + (alpha2 + (1.0 - alpha2) * n_dot_x * n_dot_x).sqrt())
+}
+
+pub fn smith_geometry(n_dot_v: f64, n_dot_l: f64, alpha: f64) -> f64 {
+  smith_g1(n_dot_v, alpha) * smith_g1(n_dot_l, alpha)
+}
+
+pub fn fresnel_schlick(cos_theta: f64, f0: Color) -> Color {
+  let factor = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+  f0.clone() + (Color::white() - f0) * factor
+}
+
+//  The light's intensity is taken as the irradiance it delivers to a surface
+//    facing it, so a lit sample contributes brdf * intensity * (n.l).
+pub fn pbr_lighting<F>(
+  material: &PbrMaterial,
+  light: &dyn Light,
+  point: &Tuple,
+  eyev: &Tuple,
+  normalv: &Tuple,
+  mut is_shadowed: F,
+) -> Color
+where
+  F: FnMut(&LightSample) -> bool,
+{
+  let samples = light.samples(point);
+  let mut sum = Color::black();
+  for sample in samples.iter() {
+    if is_shadowed(sample) {
+      continue;
+    }
+    let n_dot_l = normalv.clone().dot(sample.direction.clone());
+    if n_dot_l <= 0.0 {
+      continue;
+    }
+    let brdf = material.brdf(&sample.direction, eyev, normalv);
+    sum += brdf * sample.intensity.clone() * n_dot_l;
+  }
+  sum / samples.len() as f64
+}
+
+#[cfg(test)]
+mod pbr_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::lighting::point_light::PointLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  mod instantiation_tests {
+    use super::*;
+
+    #[test]
+    fn a_dielectric_reflects_four_percent_at_normal_incidence() {
+      let material = PbrMaterial::new(Color::red(), 0.0, 0.5);
+
+      assert_feq!(material.f0(), Color::new(0.04, 0.04, 0.04));
+    }
+
+    #[test]
+    fn a_metal_reflects_its_base_color_at_normal_incidence() {
+      let material = PbrMaterial::new(Color::new(1.0, 0.8, 0.3), 1.0, 0.5);
+
+      assert_feq!(material.f0(), Color::new(1.0, 0.8, 0.3));
+    }
+
+    #[test]
+    fn materials_do_not_glow_by_default() {
+      let material = PbrMaterial::new(Color::white(), 0.0, 0.5);
+
+      assert_feq!(material.emission, Color::black());
+    }
+
+    #[test]
+    fn an_emissive_material_only_emits() {
+      let material = PbrMaterial::emissive(Color::new(4.0, 2.0, 1.0));
+
+      assert_feq!(material.emission, Color::new(4.0, 2.0, 1.0));
+      assert_feq!(material.base_color, Color::black());
+    }
+
+    #[test]
+    fn parameters_are_clamped_to_the_unit_interval() {
+      let material = PbrMaterial::new(Color::white(), 1.5, -0.2);
+
+      assert_feq!(material.metallic, 1.0);
+      assert_feq!(material.roughness, 0.0);
+    }
+
+    #[test]
+    fn glass_and_mirrors_are_specular() {
+      assert!(PbrMaterial::glass(1.5).is_specular());
+      assert!(PbrMaterial::new(Color::white(), 1.0, 0.0).is_specular());
+      assert!(!PbrMaterial::new(Color::white(), 1.0, 0.1).is_specular());
+      assert!(!PbrMaterial::new(Color::white(), 0.0, 0.0).is_specular());
+    }
+  }
+
+  mod specular_tests {
+    use super::*;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn a_mirror_reflects_about_the_normal() {
+      let material = PbrMaterial::new(Color::new(1.0, 0.8, 0.3), 1.0, 0.0);
+      let eyev = Tuple::vector(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0);
+
+      let (direction, weight) =
+        material.sample_specular(&eyev, &Tuple::vector(0.0, 1.0, 0.0), true, 0.5);
+
+      assert_feq!(direction, Tuple::vector(-FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0));
+      assert!(weight.red > weight.blue);
+    }
+
+    #[test]
+    fn glass_mostly_refracts_at_normal_incidence() {
+      let material = PbrMaterial::glass(1.5);
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let (refracted, weight) = material.sample_specular(&eyev, &normalv, true, 0.5);
+      let (reflected, _) = material.sample_specular(&eyev, &normalv, true, 0.01);
+
+      assert_feq!(refracted, Tuple::vector(0.0, -1.0, 0.0));
+      assert_feq!(weight, Color::white());
+      assert_feq!(reflected, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn glass_bends_light_toward_the_normal_when_entering() {
+      let material = PbrMaterial::glass(1.5);
+      let eyev = Tuple::vector(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let (direction, _) = material.sample_specular(&eyev, &normalv, true, 0.99);
+
+      assert_feq!(direction.x, -FRAC_1_SQRT_2 / 1.5);
+      assert!(direction.y < 0.0);
+    }
+
+    #[test]
+    fn light_leaving_glass_at_a_grazing_angle_is_totally_reflected() {
+      let material = PbrMaterial::glass(1.5);
+      let eyev = Tuple::vector(0.9, 0.1, 0.0).normalize();
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let (direction, weight) = material.sample_specular(&eyev, &normalv, false, 0.99);
+
+      assert!(direction.y > 0.0);
+      assert_feq!(weight, Color::white());
+    }
+  }
+
+  mod term_tests {
+    use super::*;
+
+    #[test]
+    fn the_ggx_distribution_is_normalized_over_the_hemisphere() {
+      let alpha = 0.5;
+      let steps = 2000;
+      let mut integral = 0.0;
+      for step in 0..steps {
+        let theta = (step as f64 + 0.5) / steps as f64 * (PI / 2.0);
+        let cos = theta.cos();
+        integral += ggx_distribution(cos, alpha) * cos * theta.sin();
+      }
+      integral *= 2.0 * PI * (PI / 2.0) / steps as f64;
+
+      assert!((integral - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn fresnel_reflectance_rises_to_one_at_grazing_angles() {
+      let f0 = Color::new(0.04, 0.04, 0.04);
+
+      assert_feq!(fresnel_schlick(1.0, f0.clone()), f0.clone());
+      assert_feq!(fresnel_schlick(0.0, f0), Color::white());
+    }
+
+    #[test]
+    fn smith_shadowing_is_one_for_a_view_along_the_normal() {
+      assert_feq!(smith_g1(1.0, 0.3), 1.0);
+      assert!(smith_g1(0.1, 0.3) < 1.0);
+    }
+  }
+
+  mod sampling_tests {
+    use super::*;
+    use crate::math::random::Pcg32;
+
+    #[test]
+    fn sampled_directions_stay_above_the_surface() {
+      let material = PbrMaterial::new(Color::white(), 0.3, 0.4);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+      let eyev = Tuple::vector(1.0, 1.0, 0.0).normalize();
+      let mut rng = Pcg32::new(3, 0);
+
+      for _ in 0..500 {
+        let sample = material.sample(
+          &eyev,
+          &normalv,
+          rng.next_f64(),
+          rng.next_f64(),
+          rng.next_f64(),
+        );
+        if let Some(sample) = sample {
+          assert!(sample.direction.clone().dot(normalv.clone()) > 0.0);
+          assert_feq!(sample.direction.magnitude(), 1.0);
+          assert!(sample.pdf > 0.0);
+        }
+      }
+    }
+
+    #[test]
+    fn the_sampling_pdf_integrates_to_at_most_one() {
+      //  Reflected half vectors can point below the horizon, and those samples
+      //    are rejected, so a little of the density is lost.
+      let material = PbrMaterial::new(Color::white(), 0.5, 0.5);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+      let eyev = Tuple::vector(0.3, 1.0, 0.2).normalize();
+      let (theta_steps, phi_steps) = (400, 400);
+      let mut integral = 0.0;
+      for i in 0..theta_steps {
+        let theta = (i as f64 + 0.5) / theta_steps as f64 * (PI / 2.0);
+        for j in 0..phi_steps {
+          let phi = (j as f64 + 0.5) / phi_steps as f64 * (2.0 * PI);
+          let lightv = Tuple::vector(
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            theta.sin() * phi.sin(),
+          );
+          integral += material.pdf(&lightv, &eyev, &normalv) * theta.sin();
+        }
+      }
+      integral *= (PI / 2.0) / theta_steps as f64 * (2.0 * PI) / phi_steps as f64;
+
+      assert!(integral > 0.9 && integral < 1.01);
+    }
+
+    #[test]
+    fn the_sample_estimate_of_the_albedo_matches_integration() {
+      let material = PbrMaterial::new(Color::white(), 0.0, 0.6);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let mut rng = Pcg32::new(11, 0);
+      let count = 20000;
+      let mut estimate = 0.0;
+      for _ in 0..count {
+        if let Some(sample) = material.sample(
+          &eyev,
+          &normalv,
+          rng.next_f64(),
+          rng.next_f64(),
+          rng.next_f64(),
+        ) {
+          let cos = sample.direction.clone().dot(normalv.clone());
+          estimate += sample.value.red * cos / sample.pdf;
+        }
+      }
+      estimate /= count as f64;
+      let steps = 2000;
+      let mut integral = 0.0;
+      for i in 0..steps {
+        let theta = (i as f64 + 0.5) / steps as f64 * (PI / 2.0);
+        let lightv = Tuple::vector(theta.sin(), theta.cos(), 0.0);
+        integral += material.brdf(&lightv, &eyev, &normalv).red * theta.cos() * theta.sin();
+      }
+      integral *= 2.0 * PI * (PI / 2.0) / steps as f64;
+
+      assert!((estimate - integral).abs() < 0.02);
+    }
+  }
+
+  mod lighting_tests {
+    use super::*;
+
+    fn overhead_light() -> PointLight {
+      PointLight::new(Tuple::point(0.0, 10.0, 0.0), Color::white())
+    }
+
+    #[test]
+    fn a_surface_facing_away_from_the_light_is_black() {
+      let material = PbrMaterial::new(Color::white(), 0.0, 0.5);
+      let eyev = Tuple::vector(0.0, -1.0, 0.0);
+      let normalv = Tuple::vector(0.0, -1.0, 0.0);
+
+      let actual = pbr_lighting(
+        &material,
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn a_shadowed_surface_is_black() {
+      let material = PbrMaterial::new(Color::white(), 0.0, 0.5);
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = pbr_lighting(
+        &material,
+        &overhead_light(),
+        &Tuple::origin(),
+        &up,
+        &up,
+        |_| true,
+      );
+
+      assert_feq!(actual, Color::black());
+    }
+
+    #[test]
+    fn a_rough_dielectric_viewed_along_the_normal() {
+      let material = PbrMaterial::new(Color::white(), 0.0, 1.0);
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = pbr_lighting(
+        &material,
+        &overhead_light(),
+        &Tuple::origin(),
+        &up,
+        &up,
+        |_| false,
+      );
+      let expected = 0.96 / PI + 0.04 / (4.0 * PI);
+
+      assert_feq!(actual, Color::new(expected, expected, expected));
+    }
+
+    #[test]
+    fn a_metal_tints_its_reflection_with_the_base_color() {
+      let material = PbrMaterial::new(Color::new(1.0, 0.5, 0.25), 1.0, 0.3);
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = pbr_lighting(
+        &material,
+        &overhead_light(),
+        &Tuple::origin(),
+        &up,
+        &up,
+        |_| false,
+      );
+
+      assert_feq!(actual.green / actual.red, 0.5);
+      assert_feq!(actual.blue / actual.red, 0.25);
+    }
+
+    #[test]
+    fn smoother_surfaces_have_brighter_highlights() {
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+      let smooth = PbrMaterial::new(Color::white(), 1.0, 0.2);
+      let rough = PbrMaterial::new(Color::white(), 1.0, 0.8);
+
+      let shine = |material: &PbrMaterial| {
+        pbr_lighting(
+          material,
+          &overhead_light(),
+          &Tuple::origin(),
+          &up,
+          &up,
+          |_| false,
+        )
+      };
+
+      assert!(shine(&smooth).red > shine(&rough).red);
+    }
+  }
+}
@@ -0,0 +1,331 @@
+use super::light::{Light, LightSample};
+use super::material::Material;
+use crate::drawing::color::Color;
+use crate::math::tuple::Tuple;
+
+pub fn lighting<F>(
+  material: &Material,
+  light: &dyn Light,
+  point: &Tuple,
+  eyev: &Tuple,
+  normalv: &Tuple,
+  is_shadowed: F,
+) -> Color
+where
+  F: FnMut(&LightSample) -> bool,
+{
+  occluded_lighting(material, light, point, eyev, normalv, 1.0, is_shadowed)
+}
+
+//  As lighting, with the ambient term scaled by an ambient occlusion
+//    visibility between zero and one. Ambient light takes the light's full
+//    intensity; a spotlight's cone only limits the diffuse and specular
+//    terms.
+//    The material's emission is added to every call, so when summing
+//    over several lights add it for one of them only.
+pub fn occluded_lighting<F>(
+  material: &Material,
+  light: &dyn Light,
+  point: &Tuple,
+  eyev: &Tuple,
+  normalv: &Tuple,
+  visibility: f64,
+  mut is_shadowed: F,
+) -> Color
+where
+  F: FnMut(&LightSample) -> bool,
+{
+  let ambient = material.color.clone() * light.intensity() * (material.ambient * visibility);
+  let samples = light.samples(point);
+  let mut sum = Color::black();
+  for sample in samples.iter() {
+    if is_shadowed(sample) {
+      continue;
+    }
+    let effective_color = material.color.clone() * sample.intensity.clone();
+    let lightv = sample.direction.clone();
+    let light_dot_normal = lightv.clone().dot(normalv.clone());
+    if light_dot_normal < 0.0 {
+      continue;
+    }
+    sum += effective_color.clone() * material.diffuse * light_dot_normal;
+    let reflect_dot_eye = (-lightv).reflect(normalv.clone()).dot(eyev.clone());
+    if reflect_dot_eye > 0.0 {
+      let factor = reflect_dot_eye.powf(material.shininess);
+      sum += sample.intensity.clone() * material.specular * factor;
+    }
+  }
+  material.emission.clone() + ambient + sum / samples.len() as f64
+}
+
+#[cfg(test)]
+mod phong_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::lighting::area_light::AreaLight;
+  use crate::lighting::directional_light::DirectionalLight;
+  use crate::lighting::point_light::PointLight;
+  use crate::lighting::spot_light::SpotLight;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use std::f64::consts::FRAC_1_SQRT_2;
+
+  fn diffuse_only() -> Material {
+    Material {
+      ambient: 0.1,
+      diffuse: 0.9,
+      specular: 0.0,
+      ..Material::default()
+    }
+  }
+
+  //  A very small light straight above the origin behaves like a point light.
+  fn overhead_light() -> AreaLight {
+    AreaLight::rectangle(
+      Tuple::point(0.0, 10.0, 0.0),
+      Tuple::vector(0.0001, 0.0, 0.0),
+      2,
+      Tuple::vector(0.0, 0.0, 0.0001),
+      2,
+      Color::white(),
+    )
+  }
+
+  mod lighting_tests {
+    use super::*;
+
+    #[test]
+    fn lighting_with_every_sample_visible() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &diffuse_only(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::white());
+    }
+
+    #[test]
+    fn lighting_with_the_surface_in_shadow_leaves_only_ambient() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &diffuse_only(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| true,
+      );
+
+      assert_feq!(actual, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn ambient_occlusion_only_darkens_the_ambient_term() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let shadowed = occluded_lighting(
+        &diffuse_only(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        0.5,
+        |_| true,
+      );
+      let lit = occluded_lighting(
+        &diffuse_only(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        0.5,
+        |_| false,
+      );
+
+      assert_feq!(shadowed, Color::new(0.05, 0.05, 0.05));
+      assert_feq!(lit, Color::new(0.95, 0.95, 0.95));
+    }
+
+    #[test]
+    fn lighting_in_a_penumbra_gives_a_fractional_diffuse_term() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &diffuse_only(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |sample| sample.direction.x < 0.000005,
+      );
+
+      assert_feq!(actual, Color::new(0.55, 0.55, 0.55));
+    }
+
+    #[test]
+    fn lighting_with_the_light_behind_the_surface() {
+      let eyev = Tuple::vector(0.0, -1.0, 0.0);
+      let normalv = Tuple::vector(0.0, -1.0, 0.0);
+
+      let actual = lighting(
+        &Material::default(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_the_eye_in_the_path_of_the_reflection() {
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &Material::default(),
+        &overhead_light(),
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::new(1.9, 1.9, 1.9));
+    }
+  }
+
+  mod light_type_tests {
+    use super::*;
+
+    #[test]
+    fn lighting_with_the_eye_between_the_light_and_the_surface() {
+      let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::white());
+      let eyev = Tuple::vector(0.0, 0.0, -1.0);
+      let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+      let actual = lighting(
+        &Material::default(),
+        &light,
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::new(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn lighting_with_the_eye_offset_45_degrees() {
+      let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::white());
+      let eyev = Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+      let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+      let actual = lighting(
+        &Material::default(),
+        &light,
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn lighting_with_a_directional_light_at_an_angle() {
+      let light = DirectionalLight::new(Tuple::vector(-1.0, -1.0, 0.0), Color::white());
+      let eyev = Tuple::vector(-1.0, 0.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &diffuse_only(),
+        &light,
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+      let expected = 0.1 + 0.9 * FRAC_1_SQRT_2;
+
+      assert_feq!(actual, Color::new(expected, expected, expected));
+    }
+
+    #[test]
+    fn lighting_outside_a_spotlight_cone_leaves_only_ambient() {
+      let light = SpotLight::new(
+        Tuple::point(0.0, 10.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        0.1,
+        0.2,
+        Color::white(),
+      );
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &Material::default(),
+        &light,
+        &Tuple::point(10.0, 0.0, 0.0),
+        &eyev,
+        &normalv,
+        |_| false,
+      );
+
+      assert_feq!(actual, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn emission_shows_even_in_shadow() {
+      let light = PointLight::new(Tuple::point(0.0, 10.0, 0.0), Color::white());
+      let material = Material {
+        emission: Color::new(0.5, 0.25, 0.0),
+        ..diffuse_only()
+      };
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &material,
+        &light,
+        &Tuple::origin(),
+        &normalv,
+        &normalv,
+        |_| true,
+      );
+
+      assert_feq!(actual, Color::new(0.6, 0.35, 0.1));
+    }
+
+    #[test]
+    fn shadow_queries_receive_the_distance_to_the_light() {
+      let light = PointLight::new(Tuple::point(0.0, 10.0, 0.0), Color::white());
+      let eyev = Tuple::vector(0.0, 1.0, 0.0);
+      let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = lighting(
+        &diffuse_only(),
+        &light,
+        &Tuple::origin(),
+        &eyev,
+        &normalv,
+        |sample| sample.distance > 5.0,
+      );
+
+      assert_feq!(actual, Color::new(0.1, 0.1, 0.1));
+    }
+  }
+}
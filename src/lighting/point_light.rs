@@ -0,0 +1,145 @@
+use super::light::{Light, LightSample};
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+#[derive(Clone, Debug)]
+pub struct PointLight {
+  pub position: Tuple,
+  pub intensity: Color,
+}
+
+//  Instantiations
+impl PointLight {
+  pub fn new(position: Tuple, intensity: Color) -> Self {
+    Self {
+      position,
+      intensity,
+    }
+  }
+}
+
+impl Light for PointLight {
+  fn incident(&self, point: &Tuple) -> LightSample {
+    LightSample::toward(&self.position, point, self.intensity.clone())
+  }
+
+  fn intensity(&self) -> Color {
+    self.intensity.clone()
+  }
+
+  fn power(&self) -> f64 {
+    4.0 * PI * self.intensity.luminance()
+  }
+
+  //  The physically based integrators see the intensity fall off with the
+  //    squared distance, as the photons emit launches do. incident keeps the
+  //    unattenuated intensity Phong shading expects.
+  fn sample_incident(&self, point: &Tuple, _u1: f64, _u2: f64) -> Option<(LightSample, f64)> {
+    inverse_square(self.incident(point))
+  }
+
+  //  Photons carry the flux power describes.
+  fn emit(&self, u1: f64, u2: f64, _u3: f64, _u4: f64) -> Option<(Ray, Color)> {
+    let (direction, _) = sampling::uniform_sphere(u1, u2);
+    let flux = self.intensity.clone() * (4.0 * PI);
+    Some((Ray::new(self.position.clone(), direction), flux))
+  }
+
+  fn emits_photons(&self) -> bool {
+    true
+  }
+}
+
+//  A delta light's sample, dimmed by the squared distance to the light.
+//    Points at the light itself receive nothing that can be sampled.
+pub fn inverse_square(sample: LightSample) -> Option<(LightSample, f64)> {
+  if sample.distance == 0.0 {
+    return None;
+  }
+  let intensity = sample.intensity.clone() / sample.distance.powi(2);
+  Some((
+    LightSample {
+      intensity,
+      ..sample
+    },
+    1.0,
+  ))
+}
+
+#[cfg(test)]
+mod point_light_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  mod incident_tests {
+    use super::*;
+
+    #[test]
+    fn a_point_light_has_a_position_and_intensity() {
+      let light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), Color::white());
+
+      assert_feq!(light.position, Tuple::point(0.0, 0.0, 0.0));
+      assert_feq!(light.intensity, Color::white());
+    }
+
+    #[test]
+    fn the_incident_light_points_toward_the_light() {
+      let light = PointLight::new(Tuple::point(0.0, 3.0, 4.0), Color::white());
+
+      let sample = light.incident(&Tuple::origin());
+
+      assert_feq!(sample.direction, Tuple::vector(0.0, 0.6, 0.8));
+      assert_feq!(sample.distance, 5.0);
+      assert_feq!(sample.intensity, Color::white());
+    }
+
+    #[test]
+    fn sampled_light_falls_off_with_the_squared_distance() {
+      let light = PointLight::new(Tuple::point(0.0, 3.0, 4.0), Color::white());
+
+      let (sample, pdf) = light.sample_incident(&Tuple::origin(), 0.5, 0.5).unwrap();
+
+      assert_feq!(sample.intensity, Color::new(0.04, 0.04, 0.04));
+      assert_feq!(pdf, 1.0);
+    }
+
+    #[test]
+    fn nothing_is_sampled_at_the_light_itself() {
+      let light = PointLight::new(Tuple::point(0.0, 3.0, 4.0), Color::white());
+
+      assert!(light.sample_incident(&light.position, 0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn the_light_itself_receives_no_incident_light() {
+      let light = PointLight::new(Tuple::point(0.0, 3.0, 4.0), Color::white());
+
+      let sample = light.incident(&light.position);
+
+      assert_feq!(sample.distance, 0.0);
+      assert_feq!(sample.direction.magnitude(), 1.0);
+      assert_feq!(sample.intensity, Color::black());
+    }
+  }
+
+  mod photon_tests {
+    use super::*;
+
+    #[test]
+    fn photons_leave_a_point_light_in_every_direction() {
+      let light = PointLight::new(Tuple::point(1.0, 2.0, 3.0), Color::new(1.0, 0.5, 0.0));
+
+      let (up, flux) = light.emit(0.0, 0.3, 0.5, 0.5).unwrap();
+      let (down, _) = light.emit(1.0, 0.3, 0.5, 0.5).unwrap();
+
+      assert_feq!(up.origin, light.position);
+      assert_feq!(up.direction, Tuple::vector(0.0, 0.0, 1.0));
+      assert_feq!(down.direction, Tuple::vector(0.0, 0.0, -1.0));
+      assert_feq!(flux, Color::new(4.0 * PI, 2.0 * PI, 0.0));
+    }
+  }
+}
@@ -0,0 +1,326 @@
+use super::directional_light::DirectionalLight;
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::math::tuple::Tuple;
+use crate::texture::environment_map::EnvironmentMap;
+use crate::texture::uv_mapping::spherical_direction;
+use std::f64::consts::PI;
+
+//  The Preetham et al. analytic daylight model. Directions use +y as up, +x
+//    as east and -z as north.
+#[derive(Clone, Debug)]
+pub struct Sky {
+  pub sun_direction: Tuple,
+  pub turbidity: f64,
+  //  Converts the model's kcd/m^2 into scene radiance.
+  pub scale: f64,
+  //  Seen below the horizon, where the model is not defined.
+  pub ground: Color,
+}
+
+//  Instantiations
+impl Sky {
+  pub fn new(sun_direction: Tuple, turbidity: f64) -> Self {
+    Self {
+      sun_direction: sun_direction.normalize(),
+      turbidity,
+      scale: 0.1,
+      ground: Color::black(),
+    }
+  }
+
+  //  Azimuth is measured clockwise from north, both angles in radians.
+  pub fn from_angles(elevation: f64, azimuth: f64, turbidity: f64) -> Self {
+    Self::new(
+      Tuple::vector(
+        elevation.cos() * azimuth.sin(),
+        elevation.sin(),
+        -elevation.cos() * azimuth.cos(),
+      ),
+      turbidity,
+    )
+  }
+}
+
+//  Methods
+impl Sky {
+  pub fn color_at(&self, direction: &Tuple) -> Color {
+    let direction = direction.clone().normalize();
+    if direction.y < 0.0 {
+      return self.ground.clone();
+    }
+    let cos_theta = direction.y.max(0.01);
+    let gamma = direction
+      .dot(self.sun_direction.clone())
+      .clamp(-1.0, 1.0)
+      .acos();
+    let sun_theta = self.sun_zenith();
+    let t = self.turbidity;
+
+    let [zenith_luminance, zenith_x, zenith_y] = self.zenith();
+    let luminance =
+      perez(cos_theta, gamma, sun_theta, luminance_coefficients(t)) * zenith_luminance;
+    let x = perez(cos_theta, gamma, sun_theta, x_coefficients(t)) * zenith_x;
+    let y = perez(cos_theta, gamma, sun_theta, y_coefficients(t)) * zenith_y;
+
+    xyy_to_rgb(x, y, luminance.max(0.0) * self.scale)
+  }
+
+  pub fn sun_light(&self, intensity: Color) -> DirectionalLight {
+    DirectionalLight::new(-self.sun_direction.clone(), intensity)
+  }
+
+  //  Bakes the sky into an equirectangular map so it can be importance
+  //    sampled as a light.
+  pub fn environment_map(&self, width: usize, height: usize) -> EnvironmentMap {
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+      for x in 0..width {
+        let u = (x as f64 + 0.5) / width as f64;
+        let v = 1.0 - (y as f64 + 0.5) / height as f64;
+        canvas.write_pixel(x, y, self.color_at(&spherical_direction(u, v)));
+      }
+    }
+    EnvironmentMap::new(canvas)
+  }
+
+  fn sun_zenith(&self) -> f64 {
+    self.sun_direction.y.clamp(0.0, 1.0).acos()
+  }
+
+  fn zenith(&self) -> [f64; 3] {
+    let t = self.turbidity;
+    let theta = self.sun_zenith();
+    let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+    let luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+
+    let turbidities = [t * t, t, 1.0];
+    let angles = [theta.powi(3), theta.powi(2), theta, 1.0];
+    let chromaticity = |matrix: [[f64; 4]; 3]| -> f64 {
+      (0..3)
+        .map(|row| {
+          (0..4)
+            .map(|column| turbidities[row] * matrix[row][column] * angles[column])
+            .sum::<f64>()
+        })
+        .sum()
+    };
+    let x = chromaticity([
+      [0.00166, -0.00375, 0.00209, 0.0],
+      [-0.02903, 0.06377, -0.03202, 0.00394],
+      [0.11693, -0.21196, 0.06052, 0.25886],
+    ]);
+    let y = chromaticity([
+      [0.00275, -0.00610, 0.00317, 0.0],
+      [-0.04214, 0.08970, -0.04153, 0.00516],
+      [0.15346, -0.26756, 0.06670, 0.26688],
+    ]);
+    [luminance, x, y]
+  }
+}
+
+//  The direction toward the sun for a UTC time and a location in degrees,
+//    with north latitudes and east longitudes positive. Follows the NOAA
+//    approximation, good to a fraction of a degree.
+pub fn sun_direction(
+  year: i32,
+  month: u32,
+  day: u32,
+  hours: f64,
+  latitude: f64,
+  longitude: f64,
+) -> Tuple {
+  let days = day_of_year(year, month, day) as f64;
+  let year_angle = 2.0 * PI / 365.0 * (days - 1.0 + (hours - 12.0) / 24.0);
+  let (s1, c1) = year_angle.sin_cos();
+  let (s2, c2) = (2.0 * year_angle).sin_cos();
+  let (s3, c3) = (3.0 * year_angle).sin_cos();
+
+  let equation_of_time =
+    229.18 * (0.000075 + 0.001868 * c1 - 0.032077 * s1 - 0.014615 * c2 - 0.040849 * s2);
+  let declination = 0.006918 - 0.399912 * c1 + 0.070257 * s1 - 0.006758 * c2 + 0.000907 * s2
+    - 0.002697 * c3
+    + 0.00148 * s3;
+  let solar_minutes = hours * 60.0 + equation_of_time + 4.0 * longitude;
+  let hour_angle = (solar_minutes / 4.0 - 180.0).to_radians();
+
+  let latitude = latitude.to_radians();
+  let east = -declination.cos() * hour_angle.sin();
+  let north =
+    latitude.cos() * declination.sin() - latitude.sin() * declination.cos() * hour_angle.cos();
+  let up =
+    latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos();
+  Tuple::vector(east, up, -north).normalize()
+}
+
+fn day_of_year(year: i32, month: u32, day: u32) -> u32 {
+  let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+  let days_before = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+  let month_index = (month.clamp(1, 12) - 1) as usize;
+  days_before[month_index] + day + u32::from(leap && month > 2)
+}
+
+fn perez(cos_theta: f64, gamma: f64, sun_theta: f64, [a, b, c, d, e]: [f64; 5]) -> f64 {
+  let f = |cos_theta: f64, gamma: f64| {
+    (1.0 + a * (b / cos_theta).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos().powi(2))
+  };
+  f(cos_theta, gamma) / f(1.0, sun_theta)
+}
+
+fn luminance_coefficients(t: f64) -> [f64; 5] {
+  [
+    0.1787 * t - 1.4630,
+    -0.3554 * t + 0.4275,
+    -0.0227 * t + 5.3251,
+    0.1206 * t - 2.5771,
+    -0.0670 * t + 0.3703,
+  ]
+}
+
+fn x_coefficients(t: f64) -> [f64; 5] {
+  [
+    -0.0193 * t - 0.2592,
+    -0.0665 * t + 0.0008,
+    -0.0004 * t + 0.2125,
+    -0.0641 * t - 0.8989,
+    -0.0033 * t + 0.0452,
+  ]
+}
+
+fn y_coefficients(t: f64) -> [f64; 5] {
+  [
+    -0.0167 * t - 0.2608,
+    -0.0950 * t + 0.0092,
+    -0.0079 * t + 0.2102,
+    -0.0441 * t - 1.6537,
+    -0.0109 * t + 0.0529,
+  ]
+}
+
+//  CIE xyY to linear Rec.709 RGB.
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Color {
+  if y <= 0.0 {
+    return Color::black();
+  }
+  let big_x = x * luminance / y;
+  let big_z = (1.0 - x - y) * luminance / y;
+  Color::new(
+    3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z,
+    -0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z,
+    0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z,
+  )
+}
+
+#[cfg(test)]
+mod sky_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn elevation(direction: &Tuple) -> f64 {
+    direction.y.asin().to_degrees()
+  }
+
+  mod sky_color_tests {
+    use super::*;
+
+    #[test]
+    fn the_zenith_has_the_models_zenith_luminance() {
+      let sky = Sky::from_angles(40.0_f64.to_radians(), 0.0, 3.0);
+
+      let luminance = sky.color_at(&Tuple::vector(0.0, 1.0, 0.0)).luminance();
+
+      assert!((luminance - sky.zenith()[0] * sky.scale).abs() < 1e-3 * luminance);
+    }
+
+    #[test]
+    fn a_clear_midday_sky_is_blue_overhead() {
+      let sky = Sky::from_angles(60.0_f64.to_radians(), PI, 2.5);
+
+      let zenith = sky.color_at(&Tuple::vector(0.0, 1.0, 0.0));
+
+      assert!(zenith.blue > zenith.red);
+    }
+
+    #[test]
+    fn the_sky_is_brightest_around_the_sun() {
+      let sky = Sky::from_angles(30.0_f64.to_radians(), PI / 2.0, 3.0);
+      let near = Tuple::vector(1.0, 0.7, 0.1);
+      let away = Tuple::vector(-1.0, 0.7, 0.1);
+
+      assert!(sky.color_at(&near).luminance() > 2.0 * sky.color_at(&away).luminance());
+    }
+
+    #[test]
+    fn the_ground_is_seen_below_the_horizon() {
+      let mut sky = Sky::from_angles(30.0_f64.to_radians(), PI / 2.0, 3.0);
+      let below = Tuple::vector(1.0, -0.7, 0.1);
+
+      assert_feq!(sky.color_at(&below), Color::black());
+
+      sky.ground = Color::new(0.2, 0.15, 0.1);
+
+      assert_feq!(sky.color_at(&below), Color::new(0.2, 0.15, 0.1));
+    }
+
+    #[test]
+    fn the_sun_light_shines_from_the_sun() {
+      let sky = Sky::from_angles(30.0_f64.to_radians(), PI / 2.0, 3.0);
+
+      let light = sky.sun_light(Color::white());
+
+      assert_feq!(-light.direction, sky.sun_direction);
+    }
+
+    #[test]
+    fn a_baked_sky_is_sampled_toward_the_sun() {
+      let sky = Sky::from_angles(20.0_f64.to_radians(), PI, 3.0);
+
+      let map = sky.environment_map(64, 32);
+
+      assert!(map.pdf(&Tuple::vector(0.0, 0.4, 1.0)) > map.pdf(&Tuple::vector(0.0, 0.4, -1.0)));
+    }
+  }
+
+  mod sun_position_tests {
+    use super::*;
+
+    #[test]
+    fn the_equinox_sun_is_overhead_at_noon_on_the_equator() {
+      let sun = sun_direction(2024, 3, 20, 12.0, 0.0, 0.0);
+
+      assert!(elevation(&sun) > 88.0);
+    }
+
+    #[test]
+    fn the_equinox_sun_rises_in_the_east() {
+      let sun = sun_direction(2024, 3, 20, 6.0, 0.0, 0.0);
+
+      assert!(sun.x > 0.99);
+      assert!(elevation(&sun).abs() < 2.0);
+    }
+
+    #[test]
+    fn the_winter_sun_stays_low_in_the_south() {
+      let sun = sun_direction(2023, 12, 21, 12.0, 50.0, 0.0);
+
+      assert!(sun.z > 0.9);
+      assert!((elevation(&sun) - 16.5).abs() < 1.0);
+    }
+
+    #[test]
+    fn longitude_shifts_local_noon() {
+      let sun = sun_direction(2024, 6, 21, 4.0, 23.44, 120.0);
+
+      assert!(elevation(&sun) > 88.0);
+    }
+
+    #[test]
+    fn counting_days_accounts_for_leap_years() {
+      assert_eq!(day_of_year(2023, 3, 1), 60);
+      assert_eq!(day_of_year(2024, 3, 1), 61);
+      assert_eq!(day_of_year(1900, 3, 1), 60);
+      assert_eq!(day_of_year(2000, 3, 1), 61);
+    }
+  }
+}
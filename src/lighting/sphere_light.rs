@@ -0,0 +1,197 @@
+use super::light::{Light, LightSample};
+use super::pbr::PbrMaterial;
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+#[derive(Clone, Debug)]
+pub struct SphereLight {
+  pub center: Tuple,
+  pub radius: f64,
+  pub emission: Color,
+}
+
+//  Instantiations
+impl SphereLight {
+  pub fn new(center: Tuple, radius: f64, material: &PbrMaterial) -> Self {
+    Self {
+      center,
+      radius,
+      emission: material.emission.clone(),
+    }
+  }
+}
+
+//  Methods
+impl SphereLight {
+  pub fn area(&self) -> f64 {
+    4.0 * PI * self.radius * self.radius
+  }
+
+  //  Only the outside of the sphere glows, so points on the far side of it
+  //    contribute nothing.
+  fn facing_sample(&self, light_point: &Tuple, point: &Tuple) -> Option<(LightSample, f64)> {
+    let normal = (light_point.clone() - self.center.clone()).normalize();
+    let (sample, pdf) = LightSample::from_surface(
+      light_point,
+      &normal,
+      self.area(),
+      point,
+      self.emission.clone(),
+    )?;
+    if sample.direction.clone().dot(normal) >= 0.0 {
+      return None;
+    }
+    Some((sample, pdf))
+  }
+}
+
+impl Light for SphereLight {
+  fn incident(&self, point: &Tuple) -> LightSample {
+    LightSample::toward(&self.center, point, self.emission.clone())
+  }
+
+  fn intensity(&self) -> Color {
+    self.emission.clone()
+  }
+
+  fn power(&self) -> f64 {
+    PI * self.area() * self.emission.luminance()
+  }
+
+  fn is_delta(&self) -> bool {
+    false
+  }
+
+  fn sample_incident(&self, point: &Tuple, u1: f64, u2: f64) -> Option<(LightSample, f64)> {
+    let (direction, _) = sampling::uniform_sphere(u1, u2);
+    let offset = direction * self.radius;
+    self.facing_sample(&(self.center.clone() + offset), point)
+  }
+
+  fn emitted_toward(&self, point: &Tuple, direction: &Tuple) -> Option<(LightSample, f64)> {
+    let to_point = point.clone() - self.center.clone();
+    let a = direction.clone().dot(direction.clone());
+    let b = 2.0 * direction.clone().dot(to_point.clone());
+    let c = to_point.clone().dot(to_point) - self.radius * self.radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+      return None;
+    }
+    let root = discriminant.sqrt();
+    let near = (-b - root) / (2.0 * a);
+    let far = (-b + root) / (2.0 * a);
+    let t = if near > 0.0 { near } else { far };
+    if t <= 0.0 {
+      return None;
+    }
+    self.facing_sample(&(point.clone() + direction.clone() * t), point)
+  }
+
+  //  A uniform point on the sphere and a cosine weighted direction away
+  //    from it, each photon carrying pi A L.
+  fn emit(&self, u1: f64, u2: f64, u3: f64, u4: f64) -> Option<(Ray, Color)> {
+    let (normal, _) = sampling::uniform_sphere(u1, u2);
+    let origin = self.center.clone() + normal.clone() * self.radius;
+    let (direction, _) = sampling::cosine_hemisphere(&normal, u3, u4);
+    let flux = self.emission.clone() * (PI * self.area());
+    Some((Ray::new(origin, direction), flux))
+  }
+
+  fn emits_photons(&self) -> bool {
+    true
+  }
+}
+
+#[cfg(test)]
+mod sphere_light_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn bulb() -> SphereLight {
+    SphereLight::new(
+      Tuple::point(0.0, 4.0, 0.0),
+      1.0,
+      &PbrMaterial::emissive(Color::new(3.0, 3.0, 3.0)),
+    )
+  }
+
+  mod sampling_tests {
+    use super::*;
+
+    #[test]
+    fn a_sphere_light_takes_its_emission_from_the_material() {
+      let light = bulb();
+
+      assert_feq!(light.emission, Color::new(3.0, 3.0, 3.0));
+      assert_feq!(light.area(), 4.0 * PI);
+    }
+
+    #[test]
+    fn the_far_side_of_the_sphere_is_not_sampled() {
+      let light = bulb();
+
+      let far_side = light.sample_incident(&Tuple::origin(), 0.5, 0.25);
+      let (near_side, _) = light.sample_incident(&Tuple::origin(), 0.5, 0.75).unwrap();
+
+      assert!(far_side.is_none());
+      assert_feq!(near_side.distance, 3.0);
+      assert_feq!(near_side.direction, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn a_sampled_direction_finds_the_same_point_on_the_sphere() {
+      let light = bulb();
+      let point = Tuple::point(0.5, 0.0, 0.2);
+
+      let (sample, pdf) = light.sample_incident(&point, 0.5, 0.75).unwrap();
+      let (hit, hit_pdf) = light.emitted_toward(&point, &sample.direction).unwrap();
+
+      assert_feq!(hit.distance, sample.distance);
+      assert_feq!(hit_pdf, pdf);
+    }
+
+    #[test]
+    fn the_density_integrates_to_the_visible_fraction_of_the_sphere() {
+      let light = bulb();
+      let point = Tuple::origin();
+      let steps = 600;
+      let max_theta = (1.0_f64 / 4.0).asin();
+      let mut integral = 0.0;
+      for i in 0..steps {
+        let theta = (i as f64 + 0.5) / steps as f64 * max_theta;
+        for j in 0..steps {
+          let phi = (j as f64 + 0.5) / steps as f64 * (2.0 * PI);
+          let direction = Tuple::vector(
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            theta.sin() * phi.sin(),
+          );
+          if let Some((_, pdf)) = light.emitted_toward(&point, &direction) {
+            integral += pdf * theta.sin();
+          }
+        }
+      }
+      integral *= max_theta / steps as f64 * (2.0 * PI) / steps as f64;
+
+      assert!((integral - (1.0 - 1.0 / 4.0) / 2.0).abs() < 0.01);
+    }
+  }
+  mod photon_tests {
+    use super::*;
+
+    #[test]
+    fn photons_leave_the_bulb_outward() {
+      let light = bulb();
+
+      let (photon, flux) = light.emit(0.0, 0.0, 0.3, 0.6).unwrap();
+
+      assert_feq!(photon.origin, Tuple::point(0.0, 4.0, 1.0));
+      assert!(photon.direction.z > 0.0);
+      assert_feq!(flux, Color::new(3.0, 3.0, 3.0) * (4.0 * PI * PI));
+    }
+  }
+}
@@ -0,0 +1,213 @@
+use super::light::{Light, LightSample};
+use super::point_light::inverse_square;
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::sampling;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+#[derive(Clone, Debug)]
+pub struct SpotLight {
+  pub position: Tuple,
+  pub direction: Tuple,
+  pub inner_angle: f64,
+  pub outer_angle: f64,
+  pub intensity: Color,
+}
+
+//  Instantiations
+impl SpotLight {
+  pub fn new(
+    position: Tuple,
+    direction: Tuple,
+    inner_angle: f64,
+    outer_angle: f64,
+    intensity: Color,
+  ) -> Self {
+    if inner_angle > outer_angle {
+      panic!("A spotlight's inner cone cannot be wider than its outer cone.");
+    }
+    Self {
+      position,
+      direction: direction.normalize(),
+      inner_angle,
+      outer_angle,
+      intensity,
+    }
+  }
+}
+
+//  Methods
+impl SpotLight {
+  //  The apex belongs to every cone, so the light's own position is fully lit.
+  pub fn falloff(&self, point: &Tuple) -> f64 {
+    let offset = point.clone() - self.position.clone();
+    if offset.magnitude() == 0.0 {
+      return 1.0;
+    }
+    let to_point = offset.normalize();
+    let cos_angle = to_point.dot(self.direction.clone());
+    let cos_inner = self.inner_angle.cos();
+    let cos_outer = self.outer_angle.cos();
+    if cos_angle >= cos_inner {
+      return 1.0;
+    }
+    if cos_angle <= cos_outer {
+      return 0.0;
+    }
+    let t = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+    t * t * (3.0 - 2.0 * t)
+  }
+}
+
+impl Light for SpotLight {
+  fn incident(&self, point: &Tuple) -> LightSample {
+    let intensity = self.intensity.clone() * self.falloff(point);
+    LightSample::toward(&self.position, point, intensity)
+  }
+
+  fn intensity(&self) -> Color {
+    self.intensity.clone()
+  }
+
+  fn power(&self) -> f64 {
+    let cone = 1.0 - 0.5 * (self.inner_angle.cos() + self.outer_angle.cos());
+    2.0 * PI * cone * self.intensity.luminance()
+  }
+
+  //  Falls off with the squared distance, like a point light's.
+  fn sample_incident(&self, point: &Tuple, _u1: f64, _u2: f64) -> Option<(LightSample, f64)> {
+    inverse_square(self.incident(point))
+  }
+
+  //  Photons fill the outer cone uniformly and carry the falloff in their flux.
+  fn emit(&self, u1: f64, u2: f64, _u3: f64, _u4: f64) -> Option<(Ray, Color)> {
+    let cos_outer = self.outer_angle.cos();
+    let (direction, _) = sampling::uniform_cone(&self.direction, cos_outer, u1, u2);
+    let falloff = self.falloff(&(self.position.clone() + direction.clone()));
+    let flux = self.intensity.clone() * (falloff * 2.0 * PI * (1.0 - cos_outer));
+    Some((Ray::new(self.position.clone(), direction), flux))
+  }
+
+  fn emits_photons(&self) -> bool {
+    true
+  }
+}
+
+#[cfg(test)]
+mod spot_light_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn downward_spot() -> SpotLight {
+    SpotLight::new(
+      Tuple::point(0.0, 10.0, 0.0),
+      Tuple::vector(0.0, -1.0, 0.0),
+      PI / 8.0,
+      PI / 4.0,
+      Color::white(),
+    )
+  }
+
+  mod instantiation_tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn an_inner_cone_wider_than_the_outer_cone_should_panic() {
+      let _light = SpotLight::new(
+        Tuple::origin(),
+        Tuple::vector(0.0, -1.0, 0.0),
+        PI / 4.0,
+        PI / 8.0,
+        Color::white(),
+      );
+    }
+  }
+
+  mod incident_tests {
+    use super::*;
+
+    #[test]
+    fn points_inside_the_inner_cone_receive_full_intensity() {
+      let sample = downward_spot().incident(&Tuple::point(1.0, 0.0, 0.0));
+
+      assert_feq!(sample.intensity, Color::white());
+      assert_feq!(sample.distance, 101.0_f64.sqrt());
+    }
+
+    #[test]
+    fn points_outside_the_outer_cone_are_unlit() {
+      let sample = downward_spot().incident(&Tuple::point(20.0, 0.0, 0.0));
+
+      assert_feq!(sample.intensity, Color::black());
+    }
+
+    #[test]
+    fn intensity_falls_off_smoothly_between_the_cones() {
+      let light = downward_spot();
+      let cos_middle = ((PI / 8.0).cos() + (PI / 4.0).cos()) / 2.0;
+      let x = 10.0 * cos_middle.acos().tan();
+
+      let sample = light.incident(&Tuple::point(x, 0.0, 0.0));
+
+      assert_feq!(sample.intensity, Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn the_light_position_itself_is_fully_lit() {
+      let light = downward_spot();
+
+      assert_feq!(light.falloff(&light.position), 1.0);
+    }
+
+    #[test]
+    fn the_apex_receives_no_incident_light() {
+      let light = downward_spot();
+
+      let sample = light.incident(&light.position);
+
+      assert_feq!(sample.distance, 0.0);
+      assert_feq!(sample.direction.magnitude(), 1.0);
+      assert_feq!(sample.intensity, Color::black());
+    }
+  }
+
+  mod photon_tests {
+    use super::*;
+    use crate::math::random::Pcg32;
+
+    #[test]
+    fn photons_stay_within_the_outer_cone() {
+      let light = downward_spot();
+      let mut rng = Pcg32::new(3, 0);
+
+      for _ in 0..100 {
+        let (ray, flux) = light
+          .emit(
+            rng.next_f64(),
+            rng.next_f64(),
+            rng.next_f64(),
+            rng.next_f64(),
+          )
+          .unwrap();
+
+        assert!(
+          ray.direction.clone().dot(light.direction.clone()) >= light.outer_angle.cos() - 1e-9
+        );
+        assert!(flux.red <= 2.0 * PI * (1.0 - light.outer_angle.cos()) + 1e-9);
+      }
+    }
+
+    #[test]
+    fn photons_along_the_axis_carry_the_full_intensity() {
+      let light = downward_spot();
+
+      let (ray, flux) = light.emit(0.0, 0.0, 0.5, 0.5).unwrap();
+
+      assert_feq!(ray.direction, light.direction);
+      assert_feq!(flux.red, 2.0 * PI * (1.0 - light.outer_angle.cos()));
+    }
+  }
+}
@@ -0,0 +1,236 @@
+use super::tuple::Tuple;
+
+//  A static tree over points, split at the median of the widest axis. The
+//    nodes are stored in place: each subtree occupies a contiguous range
+//    with its root in the middle.
+#[derive(Clone, Debug)]
+pub struct KdTree<T> {
+  nodes: Vec<(Tuple, T)>,
+  axes: Vec<usize>,
+}
+
+//  Instantiations
+impl<T> KdTree<T> {
+  pub fn new(items: Vec<(Tuple, T)>) -> Self {
+    let mut nodes = items;
+    let mut axes = vec![0; nodes.len()];
+    build(&mut nodes, &mut axes);
+    Self { nodes, axes }
+  }
+}
+
+//  Methods
+impl<T> KdTree<T> {
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  //  Up to count items within max_distance of the point, nearest first,
+  //    each with its distance.
+  pub fn nearest(&self, point: &Tuple, count: usize, max_distance: f64) -> Vec<(&T, f64)> {
+    let mut found: Vec<(usize, f64)> = Vec::with_capacity(count + 1);
+    if count > 0 {
+      self.search(
+        point,
+        0,
+        self.nodes.len(),
+        count,
+        max_distance * max_distance,
+        &mut found,
+      );
+    }
+    found
+      .into_iter()
+      .map(|(index, distance2)| (&self.nodes[index].1, distance2.sqrt()))
+      .collect()
+  }
+
+  fn search(
+    &self,
+    point: &Tuple,
+    start: usize,
+    end: usize,
+    count: usize,
+    max_distance2: f64,
+    found: &mut Vec<(usize, f64)>,
+  ) {
+    if start >= end {
+      return;
+    }
+    let middle = start + (end - start) / 2;
+    let (position, _) = &self.nodes[middle];
+    let axis = self.axes[middle];
+    let offset = coordinate(point, axis) - coordinate(position, axis);
+    let (near, far) = if offset < 0.0 {
+      ((start, middle), (middle + 1, end))
+    } else {
+      ((middle + 1, end), (start, middle))
+    };
+
+    self.search(point, near.0, near.1, count, max_distance2, found);
+
+    let distance2 = (position.clone() - point.clone()).magnitude().powi(2);
+    if distance2 <= max_distance2 && (found.len() < count || distance2 < found[found.len() - 1].1) {
+      let at = found.partition_point(|&(_, other)| other <= distance2);
+      found.insert(at, (middle, distance2));
+      found.truncate(count);
+    }
+
+    let bound = if found.len() < count {
+      max_distance2
+    } else {
+      found[found.len() - 1].1.min(max_distance2)
+    };
+    if offset * offset <= bound {
+      self.search(point, far.0, far.1, count, max_distance2, found);
+    }
+  }
+}
+
+fn build<T>(nodes: &mut [(Tuple, T)], axes: &mut [usize]) {
+  if nodes.is_empty() {
+    return;
+  }
+  let axis = widest_axis(nodes);
+  let middle = nodes.len() / 2;
+  nodes.select_nth_unstable_by(middle, |a, b| {
+    coordinate(&a.0, axis).total_cmp(&coordinate(&b.0, axis))
+  });
+  axes[middle] = axis;
+  let (left, right) = nodes.split_at_mut(middle);
+  let (left_axes, right_axes) = axes.split_at_mut(middle);
+  build(left, left_axes);
+  build(&mut right[1..], &mut right_axes[1..]);
+}
+
+fn widest_axis<T>(nodes: &[(Tuple, T)]) -> usize {
+  let extent = |axis: usize| {
+    let (low, high) = nodes.iter().fold(
+      (f64::INFINITY, f64::NEG_INFINITY),
+      |(low, high), (position, _)| {
+        let value = coordinate(position, axis);
+        (low.min(value), high.max(value))
+      },
+    );
+    high - low
+  };
+  (0..3).fold(0, |widest, axis| {
+    if extent(axis) > extent(widest) {
+      axis
+    } else {
+      widest
+    }
+  })
+}
+
+fn coordinate(position: &Tuple, axis: usize) -> f64 {
+  match axis {
+    0 => position.x,
+    1 => position.y,
+    _ => position.z,
+  }
+}
+
+#[cfg(test)]
+mod kd_tree_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::random::Pcg32;
+
+  fn scattered_points(count: usize) -> Vec<(Tuple, usize)> {
+    let mut rng = Pcg32::new(11, 0);
+    (0..count)
+      .map(|index| {
+        let point = Tuple::point(
+          rng.next_f64() * 10.0,
+          rng.next_f64() * 2.0,
+          rng.next_f64() * 5.0,
+        );
+        (point, index)
+      })
+      .collect()
+  }
+
+  fn brute_force(
+    points: &[(Tuple, usize)],
+    point: &Tuple,
+    count: usize,
+    max_distance: f64,
+  ) -> Vec<usize> {
+    let mut by_distance: Vec<(f64, usize)> = points
+      .iter()
+      .map(|(position, index)| ((position.clone() - point.clone()).magnitude(), *index))
+      .filter(|(distance, _)| *distance <= max_distance)
+      .collect();
+    by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+    by_distance
+      .into_iter()
+      .take(count)
+      .map(|(_, index)| index)
+      .collect()
+  }
+
+  #[test]
+  fn an_empty_tree_finds_nothing() {
+    let tree: KdTree<usize> = KdTree::new(Vec::new());
+
+    assert!(tree.is_empty());
+    assert!(tree.nearest(&Tuple::origin(), 5, 1.0).is_empty());
+  }
+
+  #[test]
+  fn the_nearest_items_match_a_brute_force_search() {
+    let points = scattered_points(500);
+    let tree = KdTree::new(points.clone());
+    let mut rng = Pcg32::new(12, 0);
+
+    assert_eq!(tree.len(), 500);
+    for _ in 0..50 {
+      let point = Tuple::point(
+        rng.next_f64() * 10.0,
+        rng.next_f64() * 2.0,
+        rng.next_f64() * 5.0,
+      );
+
+      let found: Vec<usize> = tree
+        .nearest(&point, 8, 100.0)
+        .into_iter()
+        .map(|(index, _)| *index)
+        .collect();
+
+      assert_eq!(found, brute_force(&points, &point, 8, 100.0));
+    }
+  }
+
+  #[test]
+  fn items_beyond_the_maximum_distance_are_ignored() {
+    let points = scattered_points(500);
+    let tree = KdTree::new(points.clone());
+    let point = Tuple::point(5.0, 1.0, 2.5);
+
+    let found = tree.nearest(&point, 1000, 0.8);
+
+    assert_eq!(found.len(), brute_force(&points, &point, 1000, 0.8).len());
+    assert!(found.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    assert!(found.iter().all(|(_, distance)| *distance <= 0.8));
+  }
+
+  #[test]
+  fn distances_are_reported_with_each_item() {
+    let tree = KdTree::new(vec![
+      (Tuple::point(0.0, 0.0, 3.0), 'a'),
+      (Tuple::point(1.0, 0.0, 0.0), 'b'),
+    ]);
+
+    let found = tree.nearest(&Tuple::origin(), 2, 10.0);
+
+    assert_eq!(*found[0].0, 'b');
+    assert_feq!(found[0].1, 1.0);
+    assert_feq!(found[1].1, 3.0);
+  }
+}
@@ -1 +1,9 @@
+pub mod kd_tree;
+pub mod motion;
+pub mod quaternion;
+pub mod random;
+pub mod ray;
+pub mod sampler;
+pub mod sampling;
+pub mod special;
 pub mod tuple;
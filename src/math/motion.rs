@@ -0,0 +1,227 @@
+use super::quaternion::Quaternion;
+use super::ray::{Ray, RayDifferentials};
+use super::tuple::Tuple;
+
+//  A rotation about the origin followed by a translation.
+#[derive(Clone, Debug)]
+pub struct RigidTransform {
+  pub translation: Tuple,
+  pub rotation: Quaternion,
+}
+
+//  Keyframed rigid motion, evaluated at a time by interpolating the
+//    translations linearly and the rotations with slerp. Before the first
+//    key and after the last the transform holds still.
+#[derive(Clone, Debug)]
+pub struct AnimatedTransform {
+  keyframes: Vec<(f64, RigidTransform)>,
+}
+
+//  Instantiations
+impl RigidTransform {
+  pub fn new(translation: Tuple, rotation: Quaternion) -> Self {
+    if !translation.is_vector() {
+      panic!("A rigid transform translates by a vector.");
+    }
+    Self {
+      translation,
+      rotation: rotation.normalize(),
+    }
+  }
+
+  pub fn identity() -> Self {
+    Self::new(Tuple::zero(), Quaternion::identity())
+  }
+
+  pub fn translation(x: f64, y: f64, z: f64) -> Self {
+    Self::new(Tuple::vector(x, y, z), Quaternion::identity())
+  }
+}
+
+impl AnimatedTransform {
+  pub fn new(keyframes: Vec<(f64, RigidTransform)>) -> Self {
+    if keyframes.is_empty() {
+      panic!("An animated transform needs at least one keyframe.");
+    }
+    let mut keyframes = keyframes;
+    keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Self { keyframes }
+  }
+
+  //  Moves from one transform at time 0 to another at time 1.
+  pub fn between(from: RigidTransform, to: RigidTransform) -> Self {
+    Self::new(vec![(0.0, from), (1.0, to)])
+  }
+}
+
+//  Methods
+impl RigidTransform {
+  pub fn apply_point(&self, point: &Tuple) -> Tuple {
+    Tuple::origin()
+      + self.rotation.rotate(&(point.clone() - Tuple::origin()))
+      + self.translation.clone()
+  }
+
+  pub fn apply_vector(&self, vector: &Tuple) -> Tuple {
+    self.rotation.rotate(vector)
+  }
+
+  pub fn apply_ray(&self, ray: &Ray) -> Ray {
+    let differentials = ray
+      .differentials
+      .as_ref()
+      .map(|differentials| RayDifferentials {
+        x_origin: self.apply_point(&differentials.x_origin),
+        x_direction: self.apply_vector(&differentials.x_direction),
+        y_origin: self.apply_point(&differentials.y_origin),
+        y_direction: self.apply_vector(&differentials.y_direction),
+      });
+    Ray::new(
+      self.apply_point(&ray.origin),
+      self.apply_vector(&ray.direction),
+    )
+    .with_time(ray.time)
+    .with_differentials(differentials)
+  }
+
+  pub fn inverse(&self) -> Self {
+    let rotation = self.rotation.conjugate();
+    Self {
+      translation: -rotation.rotate(&self.translation),
+      rotation,
+    }
+  }
+
+  pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+    Self {
+      translation: self.translation.clone() * (1.0 - t) + other.translation.clone() * t,
+      rotation: self.rotation.slerp(&other.rotation, t),
+    }
+  }
+}
+
+impl AnimatedTransform {
+  pub fn at(&self, time: f64) -> RigidTransform {
+    let (first_time, first) = &self.keyframes[0];
+    if time <= *first_time {
+      return first.clone();
+    }
+    for pair in self.keyframes.windows(2) {
+      let ((start, from), (end, to)) = (&pair[0], &pair[1]);
+      if time < *end {
+        return from.interpolate(to, (time - start) / (end - start));
+      }
+    }
+    self.keyframes[self.keyframes.len() - 1].1.clone()
+  }
+}
+
+#[cfg(test)]
+mod motion_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use std::f64::consts::PI;
+
+  mod rigid_transform_tests {
+    use super::*;
+
+    #[test]
+    fn a_rigid_transform_rotates_then_translates_points() {
+      let transform = RigidTransform::new(
+        Tuple::vector(0.0, 0.0, 5.0),
+        Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), PI / 2.0),
+      );
+
+      assert_feq!(
+        transform.apply_point(&Tuple::point(1.0, 0.0, 0.0)),
+        Tuple::point(0.0, 1.0, 5.0)
+      );
+      assert_feq!(
+        transform.apply_vector(&Tuple::vector(1.0, 0.0, 0.0)),
+        Tuple::vector(0.0, 1.0, 0.0)
+      );
+    }
+
+    #[test]
+    fn the_inverse_undoes_a_rigid_transform() {
+      let transform = RigidTransform::new(
+        Tuple::vector(1.0, -2.0, 3.0),
+        Quaternion::from_axis_angle(Tuple::vector(1.0, 1.0, 0.0), 0.7),
+      );
+      let point = Tuple::point(0.5, 4.0, -1.0);
+
+      let there = transform.apply_point(&point);
+
+      assert_feq!(transform.inverse().apply_point(&there), point);
+    }
+
+    #[test]
+    fn ray_differentials_move_with_the_ray() {
+      let transform = RigidTransform::translation(0.0, 3.0, 0.0);
+      let ray = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0)).with_differentials(Some(
+        RayDifferentials {
+          x_origin: Tuple::point(1.0, 0.0, 0.0),
+          x_direction: Tuple::vector(0.0, 0.0, 1.0),
+          y_origin: Tuple::origin(),
+          y_direction: Tuple::vector(0.0, 0.1, 1.0),
+        },
+      ));
+
+      let moved = transform.apply_ray(&ray).differentials.unwrap();
+
+      assert_feq!(moved.x_origin, Tuple::point(1.0, 3.0, 0.0));
+      assert_feq!(moved.y_direction, Tuple::vector(0.0, 0.1, 1.0));
+    }
+  }
+
+  mod animated_transform_tests {
+    use super::*;
+
+    fn sweep() -> AnimatedTransform {
+      AnimatedTransform::between(
+        RigidTransform::identity(),
+        RigidTransform::new(
+          Tuple::vector(2.0, 0.0, 0.0),
+          Quaternion::from_axis_angle(Tuple::vector(0.0, 1.0, 0.0), PI / 2.0),
+        ),
+      )
+    }
+
+    #[test]
+    fn translations_are_interpolated_linearly() {
+      let transform = sweep().at(0.25);
+
+      assert_feq!(transform.translation, Tuple::vector(0.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn rotations_are_interpolated_with_slerp() {
+      let transform = sweep().at(0.5);
+
+      assert_feq!(
+        transform.apply_vector(&Tuple::vector(1.0, 0.0, 0.0)),
+        Tuple::vector((PI / 4.0).cos(), 0.0, -(PI / 4.0).sin())
+      );
+    }
+
+    #[test]
+    fn the_transform_holds_outside_its_keyframes() {
+      let motion = sweep();
+
+      assert_feq!(motion.at(-1.0).translation, Tuple::zero());
+      assert_feq!(motion.at(3.0).translation, Tuple::vector(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn keyframes_are_ordered_by_time() {
+      let motion = AnimatedTransform::new(vec![
+        (2.0, RigidTransform::translation(0.0, 4.0, 0.0)),
+        (0.0, RigidTransform::identity()),
+        (1.0, RigidTransform::translation(0.0, 1.0, 0.0)),
+      ]);
+
+      assert_feq!(motion.at(1.5).translation, Tuple::vector(0.0, 2.5, 0.0));
+    }
+  }
+}
@@ -0,0 +1,339 @@
+use super::tuple::Tuple;
+use crate::macros::fuzzy_eq::FuzzyEq;
+use std::ops::Mul;
+
+//  A rotation as w + xi + yj + zk. Only unit quaternions rotate.
+#[derive(Clone, Debug)]
+pub struct Quaternion {
+  pub w: f64,
+  pub x: f64,
+  pub y: f64,
+  pub z: f64,
+}
+
+//  Instantiations
+impl Quaternion {
+  pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+    Self { w, x, y, z }
+  }
+
+  pub fn identity() -> Self {
+    Self::new(1.0, 0.0, 0.0, 0.0)
+  }
+
+  //  A right-handed rotation by angle radians about the axis.
+  pub fn from_axis_angle(axis: Tuple, angle: f64) -> Self {
+    let axis = axis.normalize();
+    let (sin, cos) = (angle / 2.0).sin_cos();
+    Self::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+  }
+
+  //  Rotates about x, then y, then z, each about the fixed world axes.
+  pub fn from_euler(x: f64, y: f64, z: f64) -> Self {
+    Self::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), z)
+      * Self::from_axis_angle(Tuple::vector(0.0, 1.0, 0.0), y)
+      * Self::from_axis_angle(Tuple::vector(1.0, 0.0, 0.0), x)
+  }
+
+  //  Reads the rotation from the upper 3x3 of a row-major matrix that
+  //    multiplies column vectors. Any translation is ignored.
+  pub fn from_matrix(m: &[[f64; 4]; 4]) -> Self {
+    //  Solving for the largest component first keeps the division stable.
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    let q = if trace > 0.0 {
+      let s = 2.0 * (trace + 1.0).sqrt();
+      Self::new(
+        s / 4.0,
+        (m[2][1] - m[1][2]) / s,
+        (m[0][2] - m[2][0]) / s,
+        (m[1][0] - m[0][1]) / s,
+      )
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+      let s = 2.0 * (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt();
+      Self::new(
+        (m[2][1] - m[1][2]) / s,
+        s / 4.0,
+        (m[0][1] + m[1][0]) / s,
+        (m[0][2] + m[2][0]) / s,
+      )
+    } else if m[1][1] > m[2][2] {
+      let s = 2.0 * (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt();
+      Self::new(
+        (m[0][2] - m[2][0]) / s,
+        (m[0][1] + m[1][0]) / s,
+        s / 4.0,
+        (m[1][2] + m[2][1]) / s,
+      )
+    } else {
+      let s = 2.0 * (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt();
+      Self::new(
+        (m[1][0] - m[0][1]) / s,
+        (m[0][2] + m[2][0]) / s,
+        (m[1][2] + m[2][1]) / s,
+        s / 4.0,
+      )
+    };
+    q.normalize()
+  }
+}
+
+//  Methods
+impl Quaternion {
+  pub fn magnitude(&self) -> f64 {
+    self.dot(self).sqrt()
+  }
+
+  pub fn normalize(&self) -> Self {
+    let magnitude = self.magnitude();
+    Self::new(
+      self.w / magnitude,
+      self.x / magnitude,
+      self.y / magnitude,
+      self.z / magnitude,
+    )
+  }
+
+  pub fn dot(&self, rhs: &Self) -> f64 {
+    self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+  }
+
+  pub fn conjugate(&self) -> Self {
+    Self::new(self.w, -self.x, -self.y, -self.z)
+  }
+
+  pub fn rotate(&self, vector: &Tuple) -> Tuple {
+    let v = Self::new(0.0, vector.x, vector.y, vector.z);
+    let rotated = self.clone() * v * self.conjugate();
+    Tuple::new(rotated.x, rotated.y, rotated.z, vector.w)
+  }
+
+  //  The rotation as a row-major 4x4 matrix that multiplies column vectors.
+  pub fn to_matrix(&self) -> [[f64; 4]; 4] {
+    let Self { w, x, y, z } = self.normalize();
+    [
+      [
+        1.0 - 2.0 * (y * y + z * z),
+        2.0 * (x * y - w * z),
+        2.0 * (x * z + w * y),
+        0.0,
+      ],
+      [
+        2.0 * (x * y + w * z),
+        1.0 - 2.0 * (x * x + z * z),
+        2.0 * (y * z - w * x),
+        0.0,
+      ],
+      [
+        2.0 * (x * z - w * y),
+        2.0 * (y * z + w * x),
+        1.0 - 2.0 * (x * x + y * y),
+        0.0,
+      ],
+      [0.0, 0.0, 0.0, 1.0],
+    ]
+  }
+
+  //  Blends componentwise along the shorter arc and renormalizes. Cheaper
+  //    than slerp, but the angular speed varies across the interval.
+  pub fn nlerp(&self, other: &Self, t: f64) -> Self {
+    let sign = if self.dot(other) < 0.0 { -1.0 } else { 1.0 };
+    Self::new(
+      self.w + (sign * other.w - self.w) * t,
+      self.x + (sign * other.x - self.x) * t,
+      self.y + (sign * other.y - self.y) * t,
+      self.z + (sign * other.z - self.z) * t,
+    )
+    .normalize()
+  }
+
+  //  Interpolates at constant angular speed along the shorter arc.
+  pub fn slerp(&self, other: &Self, t: f64) -> Self {
+    let mut cos = self.dot(other);
+    let mut other = other.clone();
+    if cos < 0.0 {
+      cos = -cos;
+      other = Self::new(-other.w, -other.x, -other.y, -other.z);
+    }
+    //  Nearly parallel rotations divide by a vanishing sine; blend linearly.
+    if cos > 0.9995 {
+      return self.nlerp(&other, t);
+    }
+    let theta = cos.acos();
+    let a = ((1.0 - t) * theta).sin() / theta.sin();
+    let b = (t * theta).sin() / theta.sin();
+    Self::new(
+      self.w * a + other.w * b,
+      self.x * a + other.x * b,
+      self.y * a + other.y * b,
+      self.z * a + other.z * b,
+    )
+  }
+}
+
+impl Mul<Self> for Quaternion {
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    Self::new(
+      self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+      self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+      self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+      self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+    )
+  }
+}
+
+impl FuzzyEq<Quaternion> for Quaternion {
+  fn fuzzy_eq(&self, rhs: &Self) -> bool {
+    self.w.fuzzy_eq(&rhs.w)
+      && self.x.fuzzy_eq(&rhs.x)
+      && self.y.fuzzy_eq(&rhs.y)
+      && self.z.fuzzy_eq(&rhs.z)
+  }
+}
+
+#[cfg(test)]
+mod quaternion_tests {
+  use super::*;
+  use crate::assert_feq;
+  use std::f64::consts::PI;
+
+  mod instantiation_tests {
+    use super::*;
+
+    #[test]
+    fn an_axis_angle_rotation_is_a_unit_quaternion() {
+      let q = Quaternion::from_axis_angle(Tuple::vector(1.0, 1.0, 0.0), 1.2);
+
+      assert_feq!(q.magnitude(), 1.0);
+    }
+
+    #[test]
+    fn a_single_euler_angle_matches_the_axis_angle_rotation() {
+      assert_feq!(
+        Quaternion::from_euler(0.0, 0.8, 0.0),
+        Quaternion::from_axis_angle(Tuple::vector(0.0, 1.0, 0.0), 0.8)
+      );
+    }
+
+    #[test]
+    fn euler_angles_rotate_about_x_then_y_then_z() {
+      let q = Quaternion::from_euler(PI / 2.0, 0.0, PI / 2.0);
+
+      //  x takes +y to +z, and z then leaves +z alone.
+      assert_feq!(
+        q.rotate(&Tuple::vector(0.0, 1.0, 0.0)),
+        Tuple::vector(0.0, 0.0, 1.0)
+      );
+      //  x leaves +x alone, and z then takes it to +y.
+      assert_feq!(
+        q.rotate(&Tuple::vector(1.0, 0.0, 0.0)),
+        Tuple::vector(0.0, 1.0, 0.0)
+      );
+    }
+
+    #[test]
+    fn a_quaternion_survives_a_trip_through_a_matrix() {
+      for q in [
+        Quaternion::from_euler(0.3, -1.1, 2.0),
+        Quaternion::from_axis_angle(Tuple::vector(1.0, 0.0, 0.0), PI),
+        Quaternion::from_axis_angle(Tuple::vector(0.0, 1.0, 0.0), PI),
+        Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), 0.99 * PI),
+      ] {
+        let back = Quaternion::from_matrix(&q.to_matrix());
+
+        //  q and -q are the same rotation.
+        assert_feq!(back.dot(&q).abs(), 1.0);
+      }
+    }
+  }
+
+  mod method_tests {
+    use super::*;
+
+    #[test]
+    fn rotating_a_vector_about_the_y_axis() {
+      let q = Quaternion::from_axis_angle(Tuple::vector(0.0, 1.0, 0.0), PI / 2.0);
+
+      assert_feq!(
+        q.rotate(&Tuple::vector(1.0, 0.0, 0.0)),
+        Tuple::vector(0.0, 0.0, -1.0)
+      );
+    }
+
+    #[test]
+    fn the_matrix_rotates_like_the_quaternion() {
+      let q = Quaternion::from_euler(0.4, 1.2, -0.7);
+      let v = Tuple::vector(1.0, -2.0, 0.5);
+      let m = q.to_matrix();
+
+      let row = |r: usize| m[r][0] * v.x + m[r][1] * v.y + m[r][2] * v.z;
+
+      assert_feq!(q.rotate(&v), Tuple::vector(row(0), row(1), row(2)));
+    }
+
+    #[test]
+    fn the_book_rotation_about_x_converts_to_a_quaternion() {
+      let (sin, cos) = (PI / 4.0).sin_cos();
+      let m = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, cos, -sin, 0.0],
+        [0.0, sin, cos, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+      ];
+
+      assert_feq!(
+        Quaternion::from_matrix(&m),
+        Quaternion::from_axis_angle(Tuple::vector(1.0, 0.0, 0.0), PI / 4.0)
+      );
+    }
+
+    #[test]
+    fn nlerp_stays_on_the_unit_sphere_and_meets_slerp_at_the_middle() {
+      let from = Quaternion::identity();
+      let to = Quaternion::from_axis_angle(Tuple::vector(0.0, 1.0, 0.0), 2.0);
+
+      assert_feq!(from.nlerp(&to, 0.3).magnitude(), 1.0);
+      assert_feq!(from.nlerp(&to, 0.5), from.slerp(&to, 0.5));
+      assert_feq!(from.nlerp(&to, 1.0), to);
+    }
+
+    #[test]
+    fn multiplying_quaternions_composes_rotations() {
+      let quarter = Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), PI / 4.0);
+
+      let half = quarter.clone() * quarter;
+
+      assert_feq!(
+        half.rotate(&Tuple::vector(1.0, 0.0, 0.0)),
+        Tuple::vector(0.0, 1.0, 0.0)
+      );
+    }
+
+    #[test]
+    fn slerp_halfway_rotates_by_half_the_angle() {
+      let from = Quaternion::identity();
+      let to = Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), PI / 2.0);
+
+      let halfway = from.slerp(&to, 0.5);
+
+      assert_feq!(
+        halfway,
+        Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), PI / 4.0)
+      );
+    }
+
+    #[test]
+    fn slerp_takes_the_shorter_arc() {
+      let from = Quaternion::identity();
+      let to = Quaternion::from_axis_angle(Tuple::vector(0.0, 0.0, 1.0), 1.5 * PI);
+
+      let halfway = from.slerp(&to, 0.5);
+
+      assert_feq!(
+        halfway.rotate(&Tuple::vector(1.0, 0.0, 0.0)),
+        Tuple::vector((PI / 4.0).cos(), -(PI / 4.0).sin(), 0.0)
+      );
+    }
+  }
+}
@@ -0,0 +1,75 @@
+const MULTIPLIER: u64 = 6364136223846793005;
+
+#[derive(Clone, Debug)]
+pub struct Pcg32 {
+  state: u64,
+  increment: u64,
+}
+
+//  Instantiations
+impl Pcg32 {
+  pub fn new(seed: u64, stream: u64) -> Self {
+    let mut generator = Self {
+      state: 0,
+      increment: (stream << 1) | 1,
+    };
+    generator.next_u32();
+    generator.state = generator.state.wrapping_add(seed);
+    generator.next_u32();
+    generator
+  }
+}
+
+//  Methods
+impl Pcg32 {
+  pub fn next_u32(&mut self) -> u32 {
+    let old = self.state;
+    self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+    let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+    let rotation = (old >> 59) as u32;
+    xorshifted.rotate_right(rotation)
+  }
+
+  pub fn next_f64(&mut self) -> f64 {
+    self.next_u32() as f64 / 4294967296.0
+  }
+}
+
+#[cfg(test)]
+mod random_tests {
+  use super::*;
+
+  mod method_tests {
+    use super::*;
+
+    #[test]
+    fn the_generator_matches_the_reference_sequence() {
+      let mut rng = Pcg32::new(42, 54);
+
+      let actual: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+      let expected = vec![
+        0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e,
+      ];
+
+      assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn floats_lie_in_the_unit_interval() {
+      let mut rng = Pcg32::new(7, 0);
+
+      for _ in 0..1000 {
+        let value = rng.next_f64();
+        assert!((0.0..1.0).contains(&value));
+      }
+    }
+
+    #[test]
+    fn different_streams_give_different_sequences() {
+      let mut first = Pcg32::new(7, 0);
+      let mut second = Pcg32::new(7, 1);
+
+      assert_ne!(first.next_u32(), second.next_u32());
+    }
+  }
+}
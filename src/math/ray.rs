@@ -0,0 +1,233 @@
+use super::tuple::Tuple;
+
+#[derive(Clone, Debug)]
+pub struct Ray {
+  pub origin: Tuple,
+  pub direction: Tuple,
+  pub time: f64,
+  pub differentials: Option<RayDifferentials>,
+}
+
+//  Offset rays through the next pixel across (x) and down (y) the image,
+//    traced alongside a camera ray to measure how much of a surface one
+//    pixel covers.
+#[derive(Clone, Debug)]
+pub struct RayDifferentials {
+  pub x_origin: Tuple,
+  pub x_direction: Tuple,
+  pub y_origin: Tuple,
+  pub y_direction: Tuple,
+}
+
+//  Instantiations
+impl Ray {
+  pub fn new(origin: Tuple, direction: Tuple) -> Self {
+    if !origin.is_point() || !direction.is_vector() {
+      panic!("A ray needs a point for its origin and a vector for its direction.");
+    }
+    Self {
+      origin,
+      direction,
+      time: 0.0,
+      differentials: None,
+    }
+  }
+
+  //  The same ray, cast at a moment within the shutter interval.
+  pub fn with_time(self, time: f64) -> Self {
+    Self { time, ..self }
+  }
+
+  pub fn with_differentials(self, differentials: Option<RayDifferentials>) -> Self {
+    Self {
+      differentials,
+      ..self
+    }
+  }
+}
+
+//  Methods
+impl Ray {
+  pub fn position(&self, t: f64) -> Tuple {
+    self.origin.clone() + self.direction.clone() * t
+  }
+}
+
+impl RayDifferentials {
+  //  Where the offset rays meet the plane through point facing normalv, or
+  //    None if either runs parallel to it.
+  pub fn meet(&self, point: &Tuple, normalv: &Tuple) -> Option<(Tuple, Tuple)> {
+    let meet = |origin: &Tuple, direction: &Tuple| {
+      let facing = direction.clone().dot(normalv.clone());
+      if facing == 0.0 {
+        return None;
+      }
+      let t = (point.clone() - origin.clone()).dot(normalv.clone()) / facing;
+      Some(origin.clone() + direction.clone() * t)
+    };
+    Some((
+      meet(&self.x_origin, &self.x_direction)?,
+      meet(&self.y_origin, &self.y_direction)?,
+    ))
+  }
+
+  //  The offset rays after a mirror at point. They are reflected off the
+  //    tangent plane, so the surface's curvature is ignored.
+  pub fn reflect(&self, point: &Tuple, normalv: &Tuple) -> Option<Self> {
+    let (x_origin, y_origin) = self.meet(point, normalv)?;
+    Some(Self {
+      x_origin,
+      x_direction: self.x_direction.clone().reflect(normalv.clone()),
+      y_origin,
+      y_direction: self.y_direction.clone().reflect(normalv.clone()),
+    })
+  }
+
+  //  As reflect, for rays refracted into the surface with the ratio n1 / n2.
+  //    The normal faces the incoming rays.
+  pub fn refract(&self, point: &Tuple, normalv: &Tuple, refractive_ratio: f64) -> Option<Self> {
+    let (x_origin, y_origin) = self.meet(point, normalv)?;
+    let bend = |direction: &Tuple| {
+      direction
+        .clone()
+        .normalize()
+        .refract(normalv.clone(), refractive_ratio)
+    };
+    Some(Self {
+      x_origin,
+      x_direction: bend(&self.x_direction)?,
+      y_origin,
+      y_direction: bend(&self.y_direction)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod ray_tests {
+  use super::*;
+
+  mod instantiation_tests {
+    use super::*;
+    use crate::assert_feq;
+    use crate::macros::fuzzy_eq::FuzzyEq;
+
+    #[test]
+    fn creating_and_querying_a_ray() {
+      let origin = Tuple::point(1.0, 2.0, 3.0);
+      let direction = Tuple::vector(4.0, 5.0, 6.0);
+
+      let ray = Ray::new(origin.clone(), direction.clone());
+
+      assert_feq!(ray.origin, origin);
+      assert_feq!(ray.direction, direction);
+      assert_feq!(ray.time, 0.0);
+    }
+
+    #[test]
+    fn a_ray_can_be_cast_at_a_later_time() {
+      let ray = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(4.0, 5.0, 6.0)).with_time(0.25);
+
+      assert_feq!(ray.origin, Tuple::point(1.0, 2.0, 3.0));
+      assert_feq!(ray.time, 0.25);
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_ray_with_a_vector_origin_should_panic() {
+      let _ray = Ray::new(Tuple::vector(1.0, 2.0, 3.0), Tuple::vector(4.0, 5.0, 6.0));
+    }
+  }
+
+  mod method_tests {
+    use super::*;
+    use crate::assert_feq;
+    use crate::macros::fuzzy_eq::FuzzyEq;
+
+    #[test]
+    fn computing_a_point_from_a_distance() {
+      let ray = Ray::new(Tuple::point(2.0, 3.0, 4.0), Tuple::vector(1.0, 0.0, 0.0));
+
+      assert_feq!(ray.position(0.0), Tuple::point(2.0, 3.0, 4.0));
+      assert_feq!(ray.position(1.0), Tuple::point(3.0, 3.0, 4.0));
+      assert_feq!(ray.position(-1.0), Tuple::point(1.0, 3.0, 4.0));
+      assert_feq!(ray.position(2.5), Tuple::point(4.5, 3.0, 4.0));
+    }
+  }
+
+  mod differential_tests {
+    use super::*;
+    use crate::assert_feq;
+    use crate::macros::fuzzy_eq::FuzzyEq;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    //  Offset rays spreading from above the origin toward the floor.
+    fn spreading() -> RayDifferentials {
+      RayDifferentials {
+        x_origin: Tuple::point(0.0, 2.0, 0.0),
+        x_direction: Tuple::vector(0.1, -1.0, 0.0),
+        y_origin: Tuple::point(0.0, 2.0, 0.0),
+        y_direction: Tuple::vector(0.0, -1.0, 0.2),
+      }
+    }
+
+    #[test]
+    fn a_new_ray_has_no_differentials() {
+      let ray = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(4.0, 5.0, 6.0));
+
+      assert!(ray.differentials.is_none());
+    }
+
+    #[test]
+    fn offset_rays_meet_the_tangent_plane() {
+      let (x, y) = spreading()
+        .meet(&Tuple::origin(), &Tuple::vector(0.0, 1.0, 0.0))
+        .unwrap();
+
+      assert_feq!(x, Tuple::point(0.2, 0.0, 0.0));
+      assert_feq!(y, Tuple::point(0.0, 0.0, 0.4));
+    }
+
+    #[test]
+    fn offset_rays_parallel_to_the_plane_never_meet_it() {
+      let mut differentials = spreading();
+      differentials.y_direction = Tuple::vector(0.0, 0.0, 1.0);
+
+      assert!(differentials
+        .meet(&Tuple::origin(), &Tuple::vector(0.0, 1.0, 0.0))
+        .is_none());
+    }
+
+    #[test]
+    fn reflected_offset_rays_leave_from_the_mirror() {
+      let reflected = spreading()
+        .reflect(&Tuple::origin(), &Tuple::vector(0.0, 1.0, 0.0))
+        .unwrap();
+
+      assert_feq!(reflected.x_origin, Tuple::point(0.2, 0.0, 0.0));
+      assert_feq!(reflected.x_direction, Tuple::vector(0.1, 1.0, 0.0));
+      assert_feq!(reflected.y_direction, Tuple::vector(0.0, 1.0, 0.2));
+    }
+
+    #[test]
+    fn refracted_offset_rays_bend_toward_the_normal() {
+      let differentials = RayDifferentials {
+        x_origin: Tuple::point(-1.0, 1.0, 0.0),
+        x_direction: Tuple::vector(1.0, -1.0, 0.0),
+        y_origin: Tuple::point(0.0, 1.0, 0.0),
+        y_direction: Tuple::vector(0.0, -1.0, 0.0),
+      };
+
+      let refracted = differentials
+        .refract(&Tuple::origin(), &Tuple::vector(0.0, 1.0, 0.0), 1.0 / 1.5)
+        .unwrap();
+      let sin_t = FRAC_1_SQRT_2 / 1.5;
+
+      assert_feq!(refracted.x_origin, Tuple::origin());
+      assert_feq!(
+        refracted.x_direction,
+        Tuple::vector(sin_t, -(1.0 - sin_t * sin_t).sqrt(), 0.0)
+      );
+      assert_feq!(refracted.y_direction, Tuple::vector(0.0, -1.0, 0.0));
+    }
+  }
+}
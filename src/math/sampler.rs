@@ -0,0 +1,918 @@
+use super::random::Pcg32;
+use std::sync::OnceLock;
+
+//  Supplies the random numbers for one pixel sample at a time. Each call
+//    to next_1d or next_2d moves on to the next dimension, so the same
+//    decision in a path always draws from the same dimension. Dimensions a
+//    sampler has no sequence for come from an independent random stream.
+//    Renders hand every pixel its own copy, so samplers must be shareable
+//    between threads.
+pub trait Sampler: Send + Sync {
+  fn samples_per_pixel(&self) -> usize;
+
+  //  A copy with the same settings, free to start pixel samples of its own.
+  fn clone_sampler(&self) -> Box<dyn Sampler>;
+
+  fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize);
+
+  fn next_1d(&mut self) -> f64;
+
+  fn next_2d(&mut self) -> (f64, f64) {
+    (self.next_1d(), self.next_1d())
+  }
+}
+
+//  Where a sampler is within the image, and the stream it falls back on.
+#[derive(Clone, Debug)]
+struct PixelSample {
+  x: usize,
+  y: usize,
+  index: usize,
+  dimension: usize,
+  rng: Pcg32,
+}
+
+//  Independent uniform numbers for every dimension.
+#[derive(Clone, Debug)]
+pub struct RandomSampler {
+  pub samples_per_pixel: usize,
+  pub seed: u64,
+  state: PixelSample,
+}
+
+//  Splits every dimension into samples_per_pixel strata, with each pixel
+//    sample taking a different stratum. The strata are shuffled per pixel
+//    and dimension so dimensions do not line up. Two-dimensional draws
+//    stratify an x_strata by y_strata grid.
+#[derive(Clone, Debug)]
+pub struct StratifiedSampler {
+  pub x_strata: usize,
+  pub y_strata: usize,
+  pub jitter: bool,
+  pub seed: u64,
+  state: PixelSample,
+}
+
+//  The Halton sequence, using the nth prime as the base of dimension n.
+//    Unscrambled, every pixel sees the same points; scrambling permutes the
+//    digits randomly per pixel and dimension.
+#[derive(Clone, Debug)]
+pub struct HaltonSampler {
+  pub samples_per_pixel: usize,
+  pub scramble: bool,
+  pub seed: u64,
+  state: PixelSample,
+}
+
+//  The Sobol sequence over the first SOBOL_DIMENSIONS dimensions.
+//    Scrambling XORs a random shift into each pixel and dimension, which
+//    keeps the sequence's stratification.
+#[derive(Clone, Debug)]
+pub struct SobolSampler {
+  pub samples_per_pixel: usize,
+  pub scramble: bool,
+  pub seed: u64,
+  directions: Vec<[u32; 32]>,
+  state: PixelSample,
+}
+
+const PRIMES: [u64; 32] = [
+  2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+  101, 103, 107, 109, 113, 127, 131,
+];
+
+//  Primitive polynomial degree, coefficients and initial direction numbers
+//    from Joe and Kuo, for the dimensions after the first.
+const SOBOL_TABLE: [(usize, u32, &[u32]); 15] = [
+  (1, 0, &[1]),
+  (2, 1, &[1, 3]),
+  (3, 1, &[1, 3, 1]),
+  (3, 2, &[1, 1, 1]),
+  (4, 1, &[1, 1, 3, 3]),
+  (4, 4, &[1, 3, 5, 13]),
+  (5, 2, &[1, 1, 5, 5, 17]),
+  (5, 4, &[1, 1, 5, 5, 5]),
+  (5, 7, &[1, 1, 7, 11, 19]),
+  (5, 11, &[1, 1, 5, 1, 1]),
+  (5, 13, &[1, 1, 1, 3, 11]),
+  (5, 14, &[1, 3, 5, 5, 31]),
+  (6, 1, &[1, 3, 3, 9, 7, 49]),
+  (6, 13, &[1, 1, 1, 15, 21, 21]),
+  (6, 16, &[1, 3, 1, 13, 27, 49]),
+];
+
+pub const SOBOL_DIMENSIONS: usize = SOBOL_TABLE.len() + 1;
+
+//  Every pixel sees the same well spread points, each dimension visiting
+//    them in its own order. A pixel shifts the points by a value read from
+//    a tiled blue-noise mask (a Cranley-Patterson rotation), so neighboring
+//    pixels err in different directions and what noise is left is fine
+//    grained. Each dimension reads the mask at its own offset.
+#[derive(Clone, Debug)]
+pub struct BlueNoiseSampler {
+  pub samples_per_pixel: usize,
+  pub seed: u64,
+  state: PixelSample,
+}
+
+//  The side of the square blue-noise mask, which tiles the image.
+pub const BLUE_NOISE_SIZE: usize = 64;
+
+//  Steps of the golden ratio and plastic number sequences, whose points
+//    stay evenly spread however many are taken.
+const GOLDEN_STEP: f64 = 0.6180339887498949;
+const PLASTIC_STEPS: (f64, f64) = (0.7548776662466927, 0.5698402909980532);
+
+//  Instantiations
+impl PixelSample {
+  fn new() -> Self {
+    Self {
+      x: 0,
+      y: 0,
+      index: 0,
+      dimension: 0,
+      rng: Pcg32::new(0, 0),
+    }
+  }
+
+  fn start(&mut self, seed: u64, x: usize, y: usize, index: usize) {
+    self.x = x;
+    self.y = y;
+    self.index = index;
+    self.dimension = 0;
+    self.rng = Pcg32::new(seed, hash(&[x as u64, y as u64, index as u64]));
+  }
+}
+
+impl RandomSampler {
+  pub fn new(samples_per_pixel: usize) -> Self {
+    require_samples(samples_per_pixel);
+    Self {
+      samples_per_pixel,
+      seed: 0,
+      state: PixelSample::new(),
+    }
+  }
+}
+
+impl StratifiedSampler {
+  pub fn new(x_strata: usize, y_strata: usize) -> Self {
+    require_samples(x_strata * y_strata);
+    Self {
+      x_strata,
+      y_strata,
+      jitter: true,
+      seed: 0,
+      state: PixelSample::new(),
+    }
+  }
+}
+
+impl HaltonSampler {
+  pub fn new(samples_per_pixel: usize) -> Self {
+    require_samples(samples_per_pixel);
+    Self {
+      samples_per_pixel,
+      scramble: true,
+      seed: 0,
+      state: PixelSample::new(),
+    }
+  }
+}
+
+impl BlueNoiseSampler {
+  pub fn new(samples_per_pixel: usize) -> Self {
+    require_samples(samples_per_pixel);
+    Self {
+      samples_per_pixel,
+      seed: 0,
+      state: PixelSample::new(),
+    }
+  }
+}
+
+impl SobolSampler {
+  pub fn new(samples_per_pixel: usize) -> Self {
+    require_samples(samples_per_pixel);
+    let mut directions = vec![core::array::from_fn(|bit| 1 << (31 - bit))];
+    for (degree, coefficients, initial) in SOBOL_TABLE.iter() {
+      let mut v = [0u32; 32];
+      for bit in 0..32 {
+        v[bit] = if bit < *degree {
+          initial[bit] << (31 - bit)
+        } else {
+          let mut value = v[bit - degree] ^ (v[bit - degree] >> degree);
+          for k in 1..*degree {
+            if (coefficients >> (degree - 1 - k)) & 1 == 1 {
+              value ^= v[bit - k];
+            }
+          }
+          value
+        };
+      }
+      directions.push(v);
+    }
+    Self {
+      samples_per_pixel,
+      scramble: true,
+      seed: 0,
+      directions,
+      state: PixelSample::new(),
+    }
+  }
+}
+
+//  Methods
+impl PixelSample {
+  //  A stream fixed by the seed, the pixel and the dimension, shared by
+  //    every sample of the pixel.
+  fn dimension_rng(&self, seed: u64, dimension: usize) -> Pcg32 {
+    Pcg32::new(
+      seed,
+      hash(&[self.x as u64, self.y as u64, dimension as u64]),
+    )
+  }
+
+  fn shuffled(&self, seed: u64, dimension: usize, count: usize) -> Vec<usize> {
+    shuffle(&mut self.dimension_rng(seed, dimension), count)
+  }
+
+  //  Where the current sample lands in a random permutation of 0..count
+  //    fixed by the seed, the pixel and the dimension.
+  fn permuted(&self, seed: u64, dimension: usize, count: usize) -> usize {
+    let key = hash(&[seed, self.x as u64, self.y as u64, dimension as u64]);
+    permute(self.index % count, count, key as u32)
+  }
+}
+
+impl BlueNoiseSampler {
+  //  The mask value this pixel reads for the dimension, and the point its
+  //    current sample takes there. Neither the offset into the mask nor the
+  //    order of the points depends on the pixel.
+  fn rotation(&self, dimension: usize) -> (f64, usize) {
+    let key = hash(&[self.seed, dimension as u64]);
+    let x = (self.state.x + (key as u32) as usize) % BLUE_NOISE_SIZE;
+    let y = (self.state.y + (key >> 32) as usize) % BLUE_NOISE_SIZE;
+    let count = self.samples_per_pixel;
+    (
+      blue_noise_mask()[y * BLUE_NOISE_SIZE + x],
+      permute(self.state.index % count, count, (key >> 16) as u32),
+    )
+  }
+}
+
+impl Sampler for RandomSampler {
+  fn samples_per_pixel(&self) -> usize {
+    self.samples_per_pixel
+  }
+
+  fn clone_sampler(&self) -> Box<dyn Sampler> {
+    Box::new(self.clone())
+  }
+
+  fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+    self.state.start(self.seed, x, y, index);
+  }
+
+  fn next_1d(&mut self) -> f64 {
+    self.state.dimension += 1;
+    self.state.rng.next_f64()
+  }
+}
+
+impl Sampler for StratifiedSampler {
+  fn samples_per_pixel(&self) -> usize {
+    self.x_strata * self.y_strata
+  }
+
+  fn clone_sampler(&self) -> Box<dyn Sampler> {
+    Box::new(self.clone())
+  }
+
+  fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+    self.state.start(self.seed, x, y, index);
+  }
+
+  fn next_1d(&mut self) -> f64 {
+    let count = self.samples_per_pixel();
+    let dimension = self.state.dimension;
+    self.state.dimension += 1;
+    let stratum = self.state.permuted(self.seed, dimension, count);
+    let offset = if self.jitter {
+      self.state.rng.next_f64()
+    } else {
+      0.5
+    };
+    (stratum as f64 + offset) / count as f64
+  }
+
+  fn next_2d(&mut self) -> (f64, f64) {
+    let count = self.samples_per_pixel();
+    let dimension = self.state.dimension;
+    self.state.dimension += 2;
+    let stratum = self.state.permuted(self.seed, dimension, count);
+    let (du, dv) = if self.jitter {
+      (self.state.rng.next_f64(), self.state.rng.next_f64())
+    } else {
+      (0.5, 0.5)
+    };
+    (
+      ((stratum % self.x_strata) as f64 + du) / self.x_strata as f64,
+      ((stratum / self.x_strata) as f64 + dv) / self.y_strata as f64,
+    )
+  }
+}
+
+impl Sampler for HaltonSampler {
+  fn samples_per_pixel(&self) -> usize {
+    self.samples_per_pixel
+  }
+
+  fn clone_sampler(&self) -> Box<dyn Sampler> {
+    Box::new(self.clone())
+  }
+
+  fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+    self.state.start(self.seed, x, y, index);
+  }
+
+  fn next_1d(&mut self) -> f64 {
+    let dimension = self.state.dimension;
+    self.state.dimension += 1;
+    if dimension >= PRIMES.len() {
+      return self.state.rng.next_f64();
+    }
+    let base = PRIMES[dimension];
+    let digits: Vec<u64> = if self.scramble {
+      let order = self.state.shuffled(self.seed, dimension, base as usize);
+      order.into_iter().map(|digit| digit as u64).collect()
+    } else {
+      (0..base).collect()
+    };
+    radical_inverse(self.state.index as u64, base, &digits)
+  }
+}
+
+impl Sampler for SobolSampler {
+  fn samples_per_pixel(&self) -> usize {
+    self.samples_per_pixel
+  }
+
+  fn clone_sampler(&self) -> Box<dyn Sampler> {
+    Box::new(self.clone())
+  }
+
+  fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+    self.state.start(self.seed, x, y, index);
+  }
+
+  fn next_1d(&mut self) -> f64 {
+    let dimension = self.state.dimension;
+    self.state.dimension += 1;
+    if dimension >= self.directions.len() {
+      return self.state.rng.next_f64();
+    }
+    let mut value = 0u32;
+    let mut index = self.state.index as u32;
+    let mut bit = 0;
+    while index != 0 {
+      if index & 1 == 1 {
+        value ^= self.directions[dimension][bit];
+      }
+      index >>= 1;
+      bit += 1;
+    }
+    if self.scramble {
+      value ^= self.state.dimension_rng(self.seed, dimension).next_u32();
+    }
+    value as f64 / 4294967296.0
+  }
+}
+
+impl Sampler for BlueNoiseSampler {
+  fn samples_per_pixel(&self) -> usize {
+    self.samples_per_pixel
+  }
+
+  fn clone_sampler(&self) -> Box<dyn Sampler> {
+    Box::new(self.clone())
+  }
+
+  fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+    self.state.start(self.seed, x, y, index);
+  }
+
+  fn next_1d(&mut self) -> f64 {
+    let (shift, point) = self.rotation(self.state.dimension);
+    self.state.dimension += 1;
+    (point as f64 * GOLDEN_STEP + shift).fract()
+  }
+
+  fn next_2d(&mut self) -> (f64, f64) {
+    let (shift_u, point) = self.rotation(self.state.dimension);
+    let (shift_v, _) = self.rotation(self.state.dimension + 1);
+    self.state.dimension += 2;
+    let (step_u, step_v) = PLASTIC_STEPS;
+    (
+      (point as f64 * step_u + shift_u).fract(),
+      (point as f64 * step_v + shift_v).fract(),
+    )
+  }
+}
+
+//  Renders take as many samples per pixel as their sampler is made for,
+//    so a sampler for none would leave every pixel undefined.
+fn require_samples(samples_per_pixel: usize) {
+  if samples_per_pixel == 0 {
+    panic!("A sampler needs at least one sample per pixel.");
+  }
+}
+
+//  Mirrors the base-b digits of index about the radix point, passing each
+//    digit through the permutation. Leading zeros are permuted too, so a
+//    scrambled sequence stays stratified. Stopping well short of an f64's
+//    precision keeps a run of high digits from rounding up into the next
+//    stratum.
+fn radical_inverse(index: u64, base: u64, digits: &[u64]) -> f64 {
+  let inverse_base = 1.0 / base as f64;
+  let mut weight = inverse_base;
+  let mut value = 0.0;
+  let mut index = index;
+  while weight > 1e-12 {
+    value += digits[(index % base) as usize] as f64 * weight;
+    index /= base;
+    weight *= inverse_base;
+  }
+  value.min(1.0 - f64::EPSILON)
+}
+
+//  A random permutation of 0..count.
+fn shuffle(rng: &mut Pcg32, count: usize) -> Vec<usize> {
+  let mut order: Vec<usize> = (0..count).collect();
+  for i in (1..count).rev() {
+    let j = ((i + 1) as f64 * rng.next_f64()) as usize;
+    order.swap(i, j.min(i));
+  }
+  order
+}
+
+//  Kensler's hashed permutation from Correlated Multi-Jittered Sampling:
+//    index's place in a random permutation of 0..count chosen by key,
+//    found without building the permutation. The hash is invertible on
+//    the enclosing power of two, so walking past count until the value
+//    falls inside it stays a permutation.
+fn permute(index: usize, count: usize, key: u32) -> usize {
+  let count = count as u32;
+  let mut mask = count - 1;
+  mask |= mask >> 1;
+  mask |= mask >> 2;
+  mask |= mask >> 4;
+  mask |= mask >> 8;
+  mask |= mask >> 16;
+  let mut i = index as u32;
+  loop {
+    i ^= key;
+    i = i.wrapping_mul(0xe170893d);
+    i ^= key >> 16;
+    i ^= (i & mask) >> 4;
+    i ^= key >> 8;
+    i = i.wrapping_mul(0x0929eb3f);
+    i ^= key >> 23;
+    i ^= (i & mask) >> 1;
+    i = i.wrapping_mul(1 | key >> 27);
+    i = i.wrapping_mul(0x6935fa69);
+    i ^= (i & mask) >> 11;
+    i = i.wrapping_mul(0x74dcb303);
+    i ^= (i & mask) >> 2;
+    i = i.wrapping_mul(0x9e501cc3);
+    i ^= (i & mask) >> 2;
+    i = i.wrapping_mul(0xc860a3df);
+    i &= mask;
+    i ^= i >> 5;
+    if i < count {
+      return (i.wrapping_add(key) % count) as usize;
+    }
+  }
+}
+
+//  Built on first use and shared by every blue-noise sampler.
+fn blue_noise_mask() -> &'static [f64] {
+  static MASK: OnceLock<Vec<f64>> = OnceLock::new();
+  MASK.get_or_init(|| void_and_cluster(BLUE_NOISE_SIZE))
+}
+
+//  Ulichney's void-and-cluster method. A sparse random pattern of dots is
+//    evened out by moving the dot in the tightest cluster into the largest
+//    void until that changes nothing. Ranks count down as dots are taken
+//    from the tightest clusters and up as new ones fill the largest voids,
+//    so every threshold of the mask leaves an even spread of dots. Crowding
+//    is measured by a Gaussian that wraps around the edges, letting the
+//    mask tile.
+fn void_and_cluster(size: usize) -> Vec<f64> {
+  let count = size * size;
+  let kernel: Vec<f64> = (0..count)
+    .map(|i| {
+      let dx = (i % size).min(size - i % size) as f64;
+      let dy = (i / size).min(size - i / size) as f64;
+      (-(dx * dx + dy * dy) / (2.0 * 1.5 * 1.5)).exp()
+    })
+    .collect();
+  let toggle = |dots: &mut [bool], energy: &mut [f64], at: usize| {
+    dots[at] = !dots[at];
+    let sign = if dots[at] { 1.0 } else { -1.0 };
+    let (ax, ay) = (at % size, at / size);
+    for (i, e) in energy.iter_mut().enumerate() {
+      let dx = (i % size + size - ax) % size;
+      let dy = (i / size + size - ay) % size;
+      *e += sign * kernel[dy * size + dx];
+    }
+  };
+  //  The dot with the most energy, or the gap with the least.
+  let extreme = |dots: &[bool], energy: &[f64], dot: bool| {
+    (0..count)
+      .filter(|&i| dots[i] == dot)
+      .max_by(|&a, &b| {
+        let order = energy[a].total_cmp(&energy[b]);
+        if dot {
+          order
+        } else {
+          order.reverse()
+        }
+      })
+      .unwrap()
+  };
+
+  let mut dots = vec![false; count];
+  let mut energy = vec![0.0; count];
+  let mut rng = Pcg32::new(0, 0);
+  let initial = count / 10;
+  for &at in shuffle(&mut rng, count)[..initial].iter() {
+    toggle(&mut dots, &mut energy, at);
+  }
+  loop {
+    let cluster = extreme(&dots, &energy, true);
+    toggle(&mut dots, &mut energy, cluster);
+    let void = extreme(&dots, &energy, false);
+    toggle(&mut dots, &mut energy, void);
+    if void == cluster {
+      break;
+    }
+  }
+
+  let mut rank = vec![0; count];
+  let (mut thinned, mut thinned_energy) = (dots.clone(), energy.clone());
+  for ones in (0..initial).rev() {
+    let cluster = extreme(&thinned, &thinned_energy, true);
+    toggle(&mut thinned, &mut thinned_energy, cluster);
+    rank[cluster] = ones;
+  }
+  for ones in initial..count {
+    let void = extreme(&dots, &energy, false);
+    toggle(&mut dots, &mut energy, void);
+    rank[void] = ones;
+  }
+  rank
+    .into_iter()
+    .map(|rank| (rank as f64 + 0.5) / count as f64)
+    .collect()
+}
+
+fn hash(values: &[u64]) -> u64 {
+  values.iter().fold(0, |hash, value| {
+    let mut z = (hash ^ value).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  })
+}
+
+#[cfg(test)]
+mod sampler_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  //  The values of one dimension across all of a pixel's samples.
+  fn column(sampler: &mut dyn Sampler, dimension: usize, count: usize) -> Vec<f64> {
+    (0..count)
+      .map(|index| {
+        sampler.start_pixel_sample(3, 5, index);
+        for _ in 0..dimension {
+          sampler.next_1d();
+        }
+        sampler.next_1d()
+      })
+      .collect()
+  }
+
+  //  Whether count values fall one in each of count equal bins.
+  fn stratified(values: &[f64]) -> bool {
+    let mut seen = vec![false; values.len()];
+    for value in values {
+      let bin = (value * values.len() as f64) as usize;
+      if bin >= values.len() || seen[bin] {
+        return false;
+      }
+      seen[bin] = true;
+    }
+    true
+  }
+
+  mod permutation_tests {
+    use super::*;
+
+    #[test]
+    fn hashed_permutations_visit_every_index_once() {
+      for count in [1, 2, 3, 7, 16, 100, 1000] {
+        for key in [0, 1, 0xdeadbeef] {
+          let mut seen = vec![false; count];
+          for index in 0..count {
+            let place = permute(index, count, key);
+            assert!(!seen[place]);
+            seen[place] = true;
+          }
+        }
+      }
+    }
+
+    #[test]
+    fn different_keys_give_different_permutations() {
+      let order = |key| {
+        (0..16)
+          .map(|index| permute(index, 16, key))
+          .collect::<Vec<_>>()
+      };
+
+      assert_ne!(order(1), order(2));
+    }
+  }
+
+  mod random_sampler_tests {
+    use super::*;
+
+    #[test]
+    fn a_pixel_sample_repeats_when_restarted() {
+      let mut sampler = RandomSampler::new(4);
+
+      sampler.start_pixel_sample(1, 2, 3);
+      let first = (sampler.next_1d(), sampler.next_2d());
+      sampler.start_pixel_sample(1, 2, 3);
+      let again = (sampler.next_1d(), sampler.next_2d());
+
+      assert_eq!(first, again);
+    }
+
+    #[test]
+    fn neighboring_pixels_draw_different_numbers() {
+      let mut sampler = RandomSampler::new(4);
+
+      sampler.start_pixel_sample(1, 2, 0);
+      let here = sampler.next_1d();
+      sampler.start_pixel_sample(2, 2, 0);
+      let there = sampler.next_1d();
+
+      assert_ne!(here, there);
+    }
+
+    #[test]
+    #[should_panic(expected = "A sampler needs at least one sample per pixel.")]
+    fn a_sampler_without_samples_should_panic() {
+      let _sampler = RandomSampler::new(0);
+    }
+  }
+
+  mod stratified_sampler_tests {
+    use super::*;
+
+    #[test]
+    fn each_dimension_puts_one_sample_in_each_stratum() {
+      let mut sampler = StratifiedSampler::new(4, 4);
+
+      for dimension in 0..5 {
+        assert!(stratified(&column(&mut sampler, dimension, 16)));
+      }
+    }
+
+    #[test]
+    fn two_dimensional_draws_cover_every_cell_of_the_grid() {
+      let mut sampler = StratifiedSampler::new(4, 2);
+      let mut cells = [false; 8];
+
+      for index in 0..8 {
+        sampler.start_pixel_sample(0, 0, index);
+        let (u, v) = sampler.next_2d();
+        cells[(v * 2.0) as usize * 4 + (u * 4.0) as usize] = true;
+      }
+
+      assert!(cells.iter().all(|&covered| covered));
+    }
+
+    #[test]
+    fn without_jitter_samples_sit_at_stratum_centers() {
+      let mut sampler = StratifiedSampler::new(2, 2);
+      sampler.jitter = false;
+
+      let mut values = column(&mut sampler, 0, 4);
+      values.sort_by(f64::total_cmp);
+
+      assert_eq!(values, vec![0.125, 0.375, 0.625, 0.875]);
+    }
+
+    #[test]
+    #[should_panic(expected = "A sampler needs at least one sample per pixel.")]
+    fn a_sampler_without_strata_should_panic() {
+      let _sampler = StratifiedSampler::new(0, 4);
+    }
+  }
+
+  mod halton_sampler_tests {
+    use super::*;
+
+    #[test]
+    fn unscrambled_dimensions_follow_the_radical_inverse_in_prime_bases() {
+      let mut sampler = HaltonSampler::new(8);
+      sampler.scramble = false;
+
+      let base_two = column(&mut sampler, 0, 4);
+      let base_three = column(&mut sampler, 1, 4);
+
+      assert_eq!(base_two, vec![0.0, 0.5, 0.25, 0.75]);
+      assert_feq!(base_three[1], 1.0 / 3.0);
+      assert_feq!(base_three[2], 2.0 / 3.0);
+      assert_feq!(base_three[3], 1.0 / 9.0);
+    }
+
+    #[test]
+    fn scrambled_dimensions_stay_stratified() {
+      let mut sampler = HaltonSampler::new(25);
+
+      assert!(stratified(&column(&mut sampler, 0, 8)));
+      assert!(stratified(&column(&mut sampler, 1, 9)));
+      assert!(stratified(&column(&mut sampler, 2, 25)));
+    }
+
+    #[test]
+    fn scrambling_differs_between_pixels() {
+      let mut sampler = HaltonSampler::new(8);
+
+      let mut draw = |x: usize| {
+        sampler.start_pixel_sample(x, 0, 1);
+        (0..4).map(|_| sampler.next_1d()).collect::<Vec<f64>>()
+      };
+      let here = draw(0);
+      let there = draw(1);
+
+      assert_ne!(here, there);
+    }
+
+    #[test]
+    fn dimensions_beyond_the_prime_table_are_still_uniform() {
+      let mut sampler = HaltonSampler::new(1);
+      sampler.start_pixel_sample(0, 0, 0);
+
+      for _ in 0..40 {
+        let value = sampler.next_1d();
+        assert!((0.0..1.0).contains(&value));
+      }
+    }
+
+    #[test]
+    #[should_panic(expected = "A sampler needs at least one sample per pixel.")]
+    fn a_sampler_without_samples_should_panic() {
+      let _sampler = HaltonSampler::new(0);
+    }
+  }
+
+  mod sobol_sampler_tests {
+    use super::*;
+
+    #[test]
+    fn the_first_dimensions_match_the_reference_sequence() {
+      let mut sampler = SobolSampler::new(4);
+      sampler.scramble = false;
+
+      assert_eq!(column(&mut sampler, 0, 4), vec![0.0, 0.5, 0.25, 0.75]);
+      assert_eq!(column(&mut sampler, 1, 4), vec![0.0, 0.5, 0.75, 0.25]);
+    }
+
+    #[test]
+    fn every_dimension_is_stratified_over_a_power_of_two() {
+      let mut sampler = SobolSampler::new(32);
+
+      for dimension in 0..SOBOL_DIMENSIONS {
+        assert!(stratified(&column(&mut sampler, dimension, 32)));
+      }
+    }
+
+    #[test]
+    fn the_first_two_dimensions_fill_every_square_of_a_grid() {
+      let mut sampler = SobolSampler::new(16);
+      let mut cells = [false; 16];
+
+      for index in 0..16 {
+        sampler.start_pixel_sample(2, 7, index);
+        let (u, v) = sampler.next_2d();
+        cells[(v * 4.0) as usize * 4 + (u * 4.0) as usize] = true;
+      }
+
+      assert!(cells.iter().all(|&covered| covered));
+    }
+
+    #[test]
+    #[should_panic(expected = "A sampler needs at least one sample per pixel.")]
+    fn a_sampler_without_samples_should_panic() {
+      let _sampler = SobolSampler::new(0);
+    }
+  }
+
+  mod blue_noise_sampler_tests {
+    use super::*;
+
+    #[test]
+    fn the_mask_holds_every_rank_once() {
+      let count = BLUE_NOISE_SIZE * BLUE_NOISE_SIZE;
+      let mut values = blue_noise_mask().to_vec();
+      values.sort_by(f64::total_cmp);
+
+      for (rank, value) in values.iter().enumerate() {
+        assert_feq!(*value, (rank as f64 + 0.5) / count as f64);
+      }
+    }
+
+    //  Averaging 3 x 3 blocks of white noise leaves a ninth of its variance
+    //    of 1 / 12. Blue noise has little low frequency content, so much
+    //    less survives.
+    #[test]
+    fn the_mask_has_little_low_frequency_content() {
+      let mask = blue_noise_mask();
+      let size = BLUE_NOISE_SIZE;
+      let mut variance = 0.0;
+      for y in 0..size {
+        for x in 0..size {
+          let mut sum = 0.0;
+          for dy in 0..3 {
+            for dx in 0..3 {
+              sum += mask[(y + dy) % size * size + (x + dx) % size];
+            }
+          }
+          variance += (sum / 9.0 - 0.5).powi(2);
+        }
+      }
+      variance /= (size * size) as f64;
+
+      assert!(variance < 1.0 / 12.0 / 9.0 / 3.0);
+    }
+
+    #[test]
+    fn neighboring_pixels_see_the_same_points_shifted() {
+      let mut sampler = BlueNoiseSampler::new(8);
+      let mut draw = |x: usize, index: usize| {
+        sampler.start_pixel_sample(x, 5, index);
+        sampler.next_1d();
+        sampler.next_1d()
+      };
+      let mut shift = |index: usize| (draw(4, index) - draw(3, index)).rem_euclid(1.0);
+      let first = shift(0);
+
+      for index in 1..8 {
+        //  Equal up to wrapping around the unit interval.
+        let difference = (shift(index) - first).abs();
+        assert!(difference.min(1.0 - difference) < 1e-9);
+      }
+    }
+
+    #[test]
+    fn a_pixel_spreads_its_samples_over_each_dimension() {
+      let mut sampler = BlueNoiseSampler::new(16);
+
+      for dimension in 0..4 {
+        let values = column(&mut sampler, dimension, 16);
+        for quarter in 0..4 {
+          let inside = values
+            .iter()
+            .filter(|value| (*value * 4.0) as usize == quarter)
+            .count();
+          assert!((3..=5).contains(&inside));
+        }
+      }
+    }
+
+    #[test]
+    fn two_dimensional_draws_stay_in_the_unit_square() {
+      let mut sampler = BlueNoiseSampler::new(4);
+
+      for index in 0..4 {
+        sampler.start_pixel_sample(70, 130, index);
+        let (u, v) = sampler.next_2d();
+        assert!((0.0..1.0).contains(&u));
+        assert!((0.0..1.0).contains(&v));
+      }
+    }
+
+    #[test]
+    #[should_panic(expected = "A sampler needs at least one sample per pixel.")]
+    fn a_sampler_without_samples_should_panic() {
+      let _sampler = BlueNoiseSampler::new(0);
+    }
+  }
+}
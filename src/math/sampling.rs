@@ -0,0 +1,372 @@
+use super::tuple::Tuple;
+use std::f64::consts::PI;
+
+//  Maps uniform numbers in [0, 1) to points and directions, each returned
+//    with its density. Directions are unit vectors with densities per unit
+//    solid angle; points have densities per unit area.
+
+//  The direction at polar angle theta from the axis and azimuth phi around
+//    it, given the cosine of theta.
+pub fn spherical_direction(axis: &Tuple, cos_theta: f64, phi: f64) -> Tuple {
+  let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+  let (tangent, bitangent) = axis.clone().perpendicular_axes();
+  tangent * (sin_theta * phi.cos()) + bitangent * (sin_theta * phi.sin()) + axis.clone() * cos_theta
+}
+
+pub fn uniform_hemisphere(normal: &Tuple, u1: f64, u2: f64) -> (Tuple, f64) {
+  (
+    spherical_direction(normal, 1.0 - u1, 2.0 * PI * u2),
+    1.0 / (2.0 * PI),
+  )
+}
+
+//  Malley's method: a uniform point on the disk lifted onto the hemisphere.
+pub fn cosine_hemisphere(normal: &Tuple, u1: f64, u2: f64) -> (Tuple, f64) {
+  let (tangent, bitangent) = normal.clone().perpendicular_axes();
+  let radius = u1.sqrt();
+  let phi = 2.0 * PI * u2;
+  let cos_theta = (1.0 - u1).max(0.0).sqrt();
+  let direction =
+    tangent * (radius * phi.cos()) + bitangent * (radius * phi.sin()) + normal.clone() * cos_theta;
+  (direction, cos_theta / PI)
+}
+
+pub fn uniform_sphere(u1: f64, u2: f64) -> (Tuple, f64) {
+  let z = 1.0 - 2.0 * u1;
+  let radius = (1.0 - z * z).max(0.0).sqrt();
+  let phi = 2.0 * PI * u2;
+  (
+    Tuple::vector(radius * phi.cos(), radius * phi.sin(), z),
+    1.0 / (4.0 * PI),
+  )
+}
+
+//  Shirley and Chiu's mapping onto the unit disk in the xy plane. It keeps
+//    the strata of the unit square compact on the disk.
+pub fn concentric_disk(u1: f64, u2: f64) -> (Tuple, f64) {
+  let a = 2.0 * u1 - 1.0;
+  let b = 2.0 * u2 - 1.0;
+  if a == 0.0 && b == 0.0 {
+    return (Tuple::point(0.0, 0.0, 0.0), 1.0 / PI);
+  }
+  let (radius, theta) = if a.abs() > b.abs() {
+    (a, (PI / 4.0) * (b / a))
+  } else {
+    (b, (PI / 2.0) - (PI / 4.0) * (a / b))
+  };
+  (
+    Tuple::point(radius * theta.cos(), radius * theta.sin(), 0.0),
+    1.0 / PI,
+  )
+}
+
+//  Directions within the cone around the axis whose half-angle has the
+//    given cosine.
+pub fn uniform_cone(axis: &Tuple, cos_max: f64, u1: f64, u2: f64) -> (Tuple, f64) {
+  let cos_theta = 1.0 - u1 * (1.0 - cos_max);
+  (
+    spherical_direction(axis, cos_theta, 2.0 * PI * u2),
+    1.0 / (2.0 * PI * (1.0 - cos_max)),
+  )
+}
+
+pub fn uniform_triangle(triangle: &[Tuple; 3], u1: f64, u2: f64) -> (Tuple, f64) {
+  let root = u1.sqrt();
+  let e1 = triangle[1].clone() - triangle[0].clone();
+  let e2 = triangle[2].clone() - triangle[0].clone();
+  let area = e1.clone().cross(e2.clone()).magnitude() / 2.0;
+  (
+    triangle[0].clone() + e1 * (root * (1.0 - u2)) + e2 * (root * u2),
+    1.0 / area,
+  )
+}
+
+#[cfg(test)]
+mod sampling_tests {
+  use super::*;
+  use crate::math::random::Pcg32;
+
+  const COUNT: usize = 20000;
+
+  //  The mean of f over COUNT samples from the mapping.
+  fn mean<F: Fn(f64, f64) -> f64>(f: F) -> f64 {
+    let mut rng = Pcg32::new(3, 0);
+    let total: f64 = (0..COUNT).map(|_| f(rng.next_f64(), rng.next_f64())).sum();
+    total / COUNT as f64
+  }
+
+  fn close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+      (actual - expected).abs() < tolerance,
+      "{} is not within {} of {}",
+      actual,
+      tolerance,
+      expected
+    );
+  }
+
+  fn tilted() -> Tuple {
+    Tuple::vector(1.0, 2.0, -0.5).normalize()
+  }
+
+  //  The fraction of COUNT samples from the mapping that land in each bin.
+  fn histogram<F: Fn(f64, f64) -> usize>(bins: usize, bin_of: F) -> Vec<f64> {
+    let mut rng = Pcg32::new(7, 0);
+    let mut counts = vec![0usize; bins];
+    for _ in 0..COUNT {
+      counts[bin_of(rng.next_f64(), rng.next_f64()).min(bins - 1)] += 1;
+    }
+    counts
+      .into_iter()
+      .map(|count| count as f64 / COUNT as f64)
+      .collect()
+  }
+
+  //  Which of four equal bins in [0, 1] the value falls in, and which
+  //    quarter turn around the z axis (x, y) lies in. Together they split
+  //    the samples into 16 bins.
+  fn bin(value: f64, (x, y): (f64, f64)) -> usize {
+    let quarter = ((value * 4.0) as usize).min(3);
+    let turn = (((y.atan2(x) + PI) / (PI / 2.0)) as usize).min(3);
+    quarter * 4 + turn
+  }
+
+  fn evenly_filled(fractions: Vec<f64>) {
+    let expected = 1.0 / fractions.len() as f64;
+    for fraction in fractions {
+      close(fraction, expected, 0.006);
+    }
+  }
+
+  mod direction_tests {
+    use super::*;
+
+    #[test]
+    fn uniform_hemisphere_directions_are_unit_and_above_the_surface() {
+      let normal = tilted();
+
+      close(
+        mean(|u1, u2| {
+          let (direction, _) = uniform_hemisphere(&normal, u1, u2);
+          assert!((direction.magnitude() - 1.0).abs() < 1e-9);
+          direction.dot(normal.clone())
+        }),
+        0.5,
+        0.01,
+      );
+      close(
+        mean(|u1, u2| 1.0 / uniform_hemisphere(&normal, u1, u2).1),
+        2.0 * PI,
+        1e-9,
+      );
+    }
+
+    #[test]
+    fn cosine_hemisphere_directions_favor_the_normal() {
+      let normal = tilted();
+
+      //  E[cos] = 2/3, and E[cos / pdf] recovers the cosine's integral, pi.
+      close(
+        mean(|u1, u2| cosine_hemisphere(&normal, u1, u2).0.dot(normal.clone())),
+        2.0 / 3.0,
+        0.01,
+      );
+      close(
+        mean(|u1, u2| {
+          let (direction, pdf) = cosine_hemisphere(&normal, u1, u2);
+          direction.dot(normal.clone()) / pdf
+        }),
+        PI,
+        1e-6,
+      );
+    }
+
+    #[test]
+    fn cosine_hemisphere_pdf_matches_the_histogram_of_cosines() {
+      //  With pdf cos / pi, cos^2 is uniform on [0, 1].
+      let normal = Tuple::vector(0.0, 0.0, 1.0);
+      let mut bins = [0usize; 4];
+      let mut rng = Pcg32::new(5, 0);
+      for _ in 0..COUNT {
+        let (direction, pdf) = cosine_hemisphere(&normal, rng.next_f64(), rng.next_f64());
+        assert!((pdf - direction.z / PI).abs() < 1e-9);
+        bins[((direction.z * direction.z * 4.0) as usize).min(3)] += 1;
+      }
+
+      for bin in bins {
+        close(bin as f64 / COUNT as f64, 0.25, 0.015);
+      }
+    }
+
+    #[test]
+    fn uniform_sphere_directions_cover_the_sphere_evenly() {
+      close(mean(|u1, u2| uniform_sphere(u1, u2).0.z), 0.0, 0.02);
+      close(
+        mean(|u1, u2| uniform_sphere(u1, u2).0.x.powi(2)),
+        1.0 / 3.0,
+        0.01,
+      );
+      close(
+        mean(|u1, u2| uniform_sphere(u1, u2).0.magnitude()),
+        1.0,
+        1e-9,
+      );
+      close(
+        mean(|u1, u2| 1.0 / uniform_sphere(u1, u2).1),
+        4.0 * PI,
+        1e-9,
+      );
+    }
+
+    #[test]
+    fn cone_directions_stay_within_the_cone() {
+      let axis = tilted();
+      let cos_max = 0.8;
+
+      close(
+        mean(|u1, u2| {
+          let cos = uniform_cone(&axis, cos_max, u1, u2).0.dot(axis.clone());
+          assert!(cos >= cos_max - 1e-9);
+          cos
+        }),
+        (1.0 + cos_max) / 2.0,
+        0.005,
+      );
+      close(
+        mean(|u1, u2| 1.0 / uniform_cone(&axis, cos_max, u1, u2).1),
+        2.0 * PI * (1.0 - cos_max),
+        1e-9,
+      );
+    }
+  }
+
+  mod point_tests {
+    use super::*;
+
+    #[test]
+    fn concentric_disk_points_fill_the_unit_disk_evenly() {
+      close(
+        mean(|u1, u2| {
+          let (point, _) = concentric_disk(u1, u2);
+          let r2 = point.x * point.x + point.y * point.y;
+          assert!(r2 <= 1.0 + 1e-9 && point.z == 0.0);
+          r2
+        }),
+        0.5,
+        0.01,
+      );
+      close(mean(|u1, u2| concentric_disk(u1, u2).0.x), 0.0, 0.02);
+      close(mean(|u1, u2| 1.0 / concentric_disk(u1, u2).1), PI, 1e-9);
+    }
+
+    #[test]
+    fn the_center_of_the_square_maps_to_the_center_of_the_disk() {
+      let (point, _) = concentric_disk(0.5, 0.5);
+
+      assert_eq!((point.x, point.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn triangle_points_are_centered_on_the_centroid() {
+      let triangle = [
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::point(4.0, 0.0, 0.0),
+        Tuple::point(0.0, 3.0, 1.0),
+      ];
+
+      close(
+        mean(|u1, u2| uniform_triangle(&triangle, u1, u2).0.x),
+        4.0 / 3.0,
+        0.03,
+      );
+      close(
+        mean(|u1, u2| uniform_triangle(&triangle, u1, u2).0.y),
+        1.0,
+        0.03,
+      );
+      close(
+        mean(|u1, u2| uniform_triangle(&triangle, u1, u2).0.z),
+        1.0 / 3.0,
+        0.01,
+      );
+      close(
+        mean(|u1, u2| 1.0 / uniform_triangle(&triangle, u1, u2).1),
+        0.5 * 10.0_f64.sqrt() * 4.0,
+        1e-9,
+      );
+    }
+  }
+
+  //  Each mapping spreads samples over bins of equal measure: equal bands
+  //    of cosine or height on the sphere, equal rings of squared radius on
+  //    the disk, each split into quarter turns.
+  mod histogram_tests {
+    use super::*;
+
+    fn up() -> Tuple {
+      Tuple::vector(0.0, 0.0, 1.0)
+    }
+
+    #[test]
+    fn uniform_hemisphere_fills_equal_solid_angles_evenly() {
+      evenly_filled(histogram(16, |u1, u2| {
+        let (direction, _) = uniform_hemisphere(&up(), u1, u2);
+        bin(direction.z, (direction.x, direction.y))
+      }));
+    }
+
+    #[test]
+    fn uniform_sphere_fills_equal_solid_angles_evenly() {
+      evenly_filled(histogram(16, |u1, u2| {
+        let (direction, _) = uniform_sphere(u1, u2);
+        bin((direction.z + 1.0) / 2.0, (direction.x, direction.y))
+      }));
+    }
+
+    #[test]
+    fn uniform_cone_fills_equal_solid_angles_evenly() {
+      let cos_max = 0.8;
+
+      evenly_filled(histogram(16, |u1, u2| {
+        let (direction, _) = uniform_cone(&up(), cos_max, u1, u2);
+        bin(
+          (direction.z - cos_max) / (1.0 - cos_max),
+          (direction.x, direction.y),
+        )
+      }));
+    }
+
+    #[test]
+    fn concentric_disk_fills_equal_areas_evenly() {
+      evenly_filled(histogram(16, |u1, u2| {
+        let (point, _) = concentric_disk(u1, u2);
+        bin(point.x * point.x + point.y * point.y, (point.x, point.y))
+      }));
+    }
+
+    //  The midpoints of the edges cut the triangle into four of equal area:
+    //    one at each corner and one in the middle.
+    #[test]
+    fn uniform_triangle_fills_equal_areas_evenly() {
+      let triangle = [
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::point(4.0, 0.0, 0.0),
+        Tuple::point(0.0, 3.0, 0.0),
+      ];
+
+      evenly_filled(histogram(4, |u1, u2| {
+        let (point, _) = uniform_triangle(&triangle, u1, u2);
+        let (b1, b2) = (point.x / 4.0, point.y / 3.0);
+        if b1 > 0.5 {
+          1
+        } else if b2 > 0.5 {
+          2
+        } else if b1 + b2 < 0.5 {
+          0
+        } else {
+          3
+        }
+      }));
+    }
+  }
+}
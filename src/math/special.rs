@@ -0,0 +1,29 @@
+use std::f64::consts::PI;
+
+//  The normalized sinc function, sin(pi x) / (pi x). Near zero it is taken
+//    as one, where the quotient would lose its precision.
+pub fn sinc(x: f64) -> f64 {
+  if x.abs() < 1e-5 {
+    return 1.0;
+  }
+  (PI * x).sin() / (PI * x)
+}
+
+#[cfg(test)]
+mod special_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  mod sinc_tests {
+    use super::*;
+
+    #[test]
+    fn sinc_is_one_at_zero_and_vanishes_at_the_integers() {
+      assert_feq!(sinc(0.0), 1.0);
+      assert_feq!(sinc(1.0), 0.0);
+      assert_feq!(sinc(-2.0), 0.0);
+      assert_feq!(sinc(0.5), 2.0 / PI);
+    }
+  }
+}
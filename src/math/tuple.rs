@@ -82,6 +82,32 @@ impl Tuple {
       (self.x * rhs.y) - (self.y * rhs.x),
     )
   }
+
+  pub fn perpendicular_axes(self) -> (Self, Self) {
+    let helper = if self.x.abs() > 0.9 {
+      Tuple::vector(0.0, 1.0, 0.0)
+    } else {
+      Tuple::vector(1.0, 0.0, 0.0)
+    };
+    let tangent = helper.cross(self.clone()).normalize();
+    let bitangent = self.cross(tangent.clone());
+    (tangent, bitangent)
+  }
+
+  pub fn reflect(self, normal: Self) -> Self {
+    let scale = 2.0 * self.clone().dot(normal.clone());
+    self - normal * scale
+  }
+
+  pub fn refract(self, normal: Self, refractive_ratio: f64) -> Option<Self> {
+    let cos_i = -self.clone().dot(normal.clone());
+    let sin2_t = refractive_ratio.powi(2) * (1.0 - cos_i.powi(2));
+    if sin2_t > 1.0 {
+      return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(self * refractive_ratio + normal * (refractive_ratio * cos_i - cos_t))
+  }
 }
 
 //  Operations
@@ -445,6 +471,7 @@ mod tuple_tests {
   mod method_tests {
     use super::*;
     use crate::assert_feq;
+    use std::f64::consts::FRAC_1_SQRT_2;
 
     #[test]
     fn computing_the_magnitude_of_a_vector() {
@@ -534,5 +561,72 @@ mod tuple_tests {
 
       let _actual = Tuple::cross(a, b);
     }
+
+    #[test]
+    fn perpendicular_axes_form_an_orthonormal_basis() {
+      let n = Tuple::vector(1.0, 2.0, 3.0).normalize();
+
+      let (t, b) = n.clone().perpendicular_axes();
+
+      assert_feq!(t.clone().magnitude(), 1.0);
+      assert_feq!(b.clone().magnitude(), 1.0);
+      assert_feq!(t.clone().dot(n.clone()), 0.0);
+      assert_feq!(b.clone().dot(n.clone()), 0.0);
+      assert_feq!(t.cross(b), n);
+    }
+
+    #[test]
+    fn reflecting_a_vector_approaching_at_45_degrees() {
+      let v = Tuple::vector(1.0, -1.0, 0.0);
+      let n = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = v.reflect(n);
+      let expected = Tuple::vector(1.0, 1.0, 0.0);
+
+      assert_feq!(actual, expected);
+    }
+
+    #[test]
+    fn reflecting_a_vector_off_a_slanted_surface() {
+      let v = Tuple::vector(0.0, -1.0, 0.0);
+      let n = Tuple::vector(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0);
+
+      let actual = v.reflect(n);
+      let expected = Tuple::vector(1.0, 0.0, 0.0);
+
+      assert_feq!(actual, expected);
+    }
+
+    #[test]
+    fn refracting_a_vector_with_matching_indices_leaves_it_unchanged() {
+      let v = Tuple::vector(1.0, -1.0, 0.0).normalize();
+      let n = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = v.clone().refract(n, 1.0).unwrap();
+
+      assert_feq!(actual, v);
+    }
+
+    #[test]
+    fn refracting_a_vector_into_a_denser_medium_bends_it_toward_the_normal() {
+      let v = Tuple::vector(1.0, -1.0, 0.0).normalize();
+      let n = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = v.refract(n, 1.0 / 1.5).unwrap();
+      let sin_t = FRAC_1_SQRT_2 / 1.5;
+      let expected = Tuple::vector(sin_t, -(1.0 - sin_t * sin_t).sqrt(), 0.0);
+
+      assert_feq!(actual, expected);
+    }
+
+    #[test]
+    fn refracting_past_the_critical_angle_gives_total_internal_reflection() {
+      let v = Tuple::vector(1.0, -1.0, 0.0).normalize();
+      let n = Tuple::vector(0.0, 1.0, 0.0);
+
+      let actual = v.refract(n, 1.5);
+
+      assert!(actual.is_none());
+    }
   }
 }
@@ -0,0 +1,298 @@
+use super::uv_mapping::{spherical_direction, spherical_map};
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::lighting::light::{Light, LightSample};
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+//  An equirectangular image surrounding the scene at infinity. Its pixels are
+//    importance sampled by luminance, weighted by the solid angle each row of
+//    pixels covers.
+#[derive(Clone)]
+pub struct EnvironmentMap {
+  pub canvas: Canvas,
+  marginal: Vec<f64>,
+  conditional: Vec<Vec<f64>>,
+  probabilities: Vec<f64>,
+  //  The mean pixel, kept so power and intensity need not scan the image.
+  average: Color,
+}
+
+//  Instantiations
+impl EnvironmentMap {
+  pub fn new(canvas: Canvas) -> Self {
+    let (width, height) = (canvas.width, canvas.height);
+    let mut weights = vec![0.0; width * height];
+    let mut sum = Color::black();
+    for y in 0..height {
+      let sin_phi = (PI * (y as f64 + 0.5) / height as f64).sin();
+      for x in 0..width {
+        let color = canvas.pixel_at(x, y);
+        weights[y * width + x] = color.luminance().max(0.0) * sin_phi;
+        sum += color;
+      }
+    }
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+      weights.iter_mut().for_each(|weight| *weight = 1.0);
+    }
+    let total: f64 = weights.iter().sum();
+    let probabilities: Vec<f64> = weights.iter().map(|weight| weight / total).collect();
+
+    let mut marginal = Vec::with_capacity(height);
+    let mut conditional = Vec::with_capacity(height);
+    let mut running = 0.0;
+    for row in probabilities.chunks(width) {
+      let row_total: f64 = row.iter().sum();
+      running += row_total;
+      marginal.push(running);
+      conditional.push(cumulative(row, row_total));
+    }
+    Self {
+      canvas,
+      marginal,
+      conditional,
+      probabilities,
+      average: sum / (width * height) as f64,
+    }
+  }
+
+  pub fn from_hdr(filename: &str) -> Self {
+    Self::new(Canvas::from_hdr(filename))
+  }
+}
+
+//  Methods
+impl EnvironmentMap {
+  pub fn color_at(&self, direction: &Tuple) -> Color {
+    let (x, y) = self.pixel_for(direction);
+    self.canvas.pixel_at(x, y)
+  }
+
+  pub fn pdf(&self, direction: &Tuple) -> f64 {
+    let direction = direction.clone().normalize();
+    let sin_phi = (1.0 - direction.y * direction.y).max(0.0).sqrt();
+    if sin_phi == 0.0 {
+      return 0.0;
+    }
+    let (x, y) = self.pixel_for(&direction);
+    let pixels = (self.canvas.width * self.canvas.height) as f64;
+    self.probabilities[y * self.canvas.width + x] * pixels / (2.0 * PI * PI * sin_phi)
+  }
+
+  pub fn sample(&self, u1: f64, u2: f64) -> Option<(Tuple, Color, f64)> {
+    let (y, fy) = sample_cdf(&self.marginal, u1);
+    let (x, fx) = sample_cdf(&self.conditional[y], u2);
+    let u = (x as f64 + fx) / self.canvas.width as f64;
+    let v = 1.0 - (y as f64 + fy) / self.canvas.height as f64;
+    let direction = spherical_direction(u, v);
+    let pdf = self.pdf(&direction);
+    if pdf <= 0.0 {
+      return None;
+    }
+    Some((direction, self.canvas.pixel_at(x, y), pdf))
+  }
+
+  fn pixel_for(&self, direction: &Tuple) -> (usize, usize) {
+    let (u, v) = spherical_map(&(Tuple::origin() + direction.clone()));
+    let x = ((u * self.canvas.width as f64) as usize).min(self.canvas.width - 1);
+    let y = (((1.0 - v) * self.canvas.height as f64) as usize).min(self.canvas.height - 1);
+    (x, y)
+  }
+}
+
+impl Light for EnvironmentMap {
+  fn incident(&self, point: &Tuple) -> LightSample {
+    match self.sample_incident(point, 0.5, 0.5) {
+      Some((sample, _)) => sample,
+      None => LightSample::new(Tuple::vector(0.0, 1.0, 0.0), f64::INFINITY, Color::black()),
+    }
+  }
+
+  //  The mean of the map's pixels.
+  fn intensity(&self) -> Color {
+    self.average.clone()
+  }
+
+  //  Like a directional light, the environment is weighed by the irradiance
+  //    it delivers, as there is no scene extent to measure against.
+  fn power(&self) -> f64 {
+    PI * self.average.luminance()
+  }
+
+  fn is_delta(&self) -> bool {
+    false
+  }
+
+  fn sample_incident(&self, _point: &Tuple, u1: f64, u2: f64) -> Option<(LightSample, f64)> {
+    let (direction, color, pdf) = self.sample(u1, u2)?;
+    Some((LightSample::new(direction, f64::INFINITY, color), pdf))
+  }
+
+  fn emitted_toward(&self, _point: &Tuple, direction: &Tuple) -> Option<(LightSample, f64)> {
+    let direction = direction.clone().normalize();
+    let pdf = self.pdf(&direction);
+    let color = self.color_at(&direction);
+    Some((LightSample::new(direction, f64::INFINITY, color), pdf))
+  }
+}
+
+fn cumulative(values: &[f64], total: f64) -> Vec<f64> {
+  let mut running = 0.0;
+  values
+    .iter()
+    .map(|value| {
+      running += if total > 0.0 {
+        value / total
+      } else {
+        1.0 / values.len() as f64
+      };
+      running
+    })
+    .collect()
+}
+
+//  Returns the chosen bucket and how far through it the sample landed.
+fn sample_cdf(cdf: &[f64], u: f64) -> (usize, f64) {
+  let index = cdf
+    .iter()
+    .position(|&bound| u < bound)
+    .unwrap_or(cdf.len() - 1);
+  let lower = if index == 0 { 0.0 } else { cdf[index - 1] };
+  let width = cdf[index] - lower;
+  let fraction = if width > 0.0 {
+    ((u - lower) / width).clamp(0.0, 1.0)
+  } else {
+    0.5
+  };
+  (index, fraction)
+}
+
+#[cfg(test)]
+mod environment_map_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::math::random::Pcg32;
+
+  fn uniform(width: usize, height: usize, color: Color) -> Canvas {
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+      for x in 0..width {
+        canvas.write_pixel(x, y, color.clone());
+      }
+    }
+    canvas
+  }
+
+  fn with_sun() -> EnvironmentMap {
+    let mut canvas = uniform(32, 16, Color::new(0.01, 0.01, 0.01));
+    canvas.write_pixel(20, 4, Color::new(100.0, 90.0, 80.0));
+    EnvironmentMap::new(canvas)
+  }
+
+  mod lookup_tests {
+    use super::*;
+
+    #[test]
+    fn directions_map_onto_the_equirectangular_image() {
+      let mut canvas = Canvas::new(4, 2);
+      canvas.write_pixel(0, 0, Color::red());
+      canvas.write_pixel(2, 1, Color::blue());
+      let map = EnvironmentMap::new(canvas);
+
+      assert_feq!(map.color_at(&Tuple::vector(0.1, 0.5, -1.0)), Color::red());
+      assert_feq!(map.color_at(&Tuple::vector(-0.1, -0.5, 1.0)), Color::blue());
+    }
+
+    //  The density is constant per pixel, so it only tracks the continuous
+    //    sin(phi) closely away from the poles.
+    #[test]
+    fn a_uniform_map_has_a_uniform_density_over_the_sphere() {
+      let map = EnvironmentMap::new(uniform(64, 32, Color::white()));
+
+      for direction in [
+        Tuple::vector(1.0, 0.0, 0.0),
+        Tuple::vector(0.0, 0.05, -1.0).normalize(),
+        Tuple::vector(-1.0, -0.05, 0.3).normalize(),
+      ] {
+        assert!((map.pdf(&direction) * 4.0 * PI - 1.0).abs() < 0.01);
+      }
+    }
+  }
+
+  mod sampling_tests {
+    use super::*;
+
+    #[test]
+    fn a_sampled_direction_reports_its_own_pixel_and_density() {
+      let map = with_sun();
+      let mut rng = Pcg32::new(4, 0);
+
+      for _ in 0..100 {
+        let (direction, color, pdf) = map.sample(rng.next_f64(), rng.next_f64()).unwrap();
+
+        assert_feq!(map.color_at(&direction), color);
+        assert!((map.pdf(&direction) - pdf).abs() < 1e-9 * pdf.max(1.0));
+      }
+    }
+
+    #[test]
+    fn bright_pixels_attract_most_of_the_samples() {
+      let map = with_sun();
+      let mut rng = Pcg32::new(5, 0);
+      let count = 1000;
+
+      let hits = (0..count)
+        .filter(|_| map.sample(rng.next_f64(), rng.next_f64()).unwrap().1.red > 1.0)
+        .count();
+
+      assert!(hits as f64 / count as f64 > 0.9);
+    }
+
+    #[test]
+    fn importance_sampling_estimates_the_total_radiance() {
+      let map = with_sun();
+      let (width, height) = (map.canvas.width, map.canvas.height);
+      let mut expected = 0.0;
+      for y in 0..height {
+        let top = (PI * y as f64 / height as f64).cos();
+        let bottom = (PI * (y + 1) as f64 / height as f64).cos();
+        let solid_angle = (2.0 * PI / width as f64) * (top - bottom);
+        for x in 0..width {
+          expected += map.canvas.pixel_at(x, y).red * solid_angle;
+        }
+      }
+      let mut rng = Pcg32::new(6, 0);
+      let count = 20000;
+
+      let mut estimate = 0.0;
+      for _ in 0..count {
+        let (_, color, pdf) = map.sample(rng.next_f64(), rng.next_f64()).unwrap();
+        estimate += color.red / pdf;
+      }
+      estimate /= count as f64;
+
+      assert!((estimate - expected).abs() < 0.02 * expected);
+    }
+  }
+
+  mod light_tests {
+    use super::*;
+
+    #[test]
+    fn the_environment_is_an_infinitely_distant_light() {
+      let map = with_sun();
+
+      let (sample, pdf) = map.sample_incident(&Tuple::origin(), 0.5, 0.5).unwrap();
+      let (seen, seen_pdf) = map
+        .emitted_toward(&Tuple::origin(), &sample.direction)
+        .unwrap();
+
+      assert!(!map.is_delta());
+      assert!(sample.distance.is_infinite());
+      assert_feq!(seen.intensity, sample.intensity);
+      assert!((seen_pdf - pdf).abs() < 1e-9 * pdf);
+    }
+  }
+}
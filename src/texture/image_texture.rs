@@ -0,0 +1,218 @@
+use super::uv_mapping::UvMapping;
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::math::tuple::Tuple;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextureFilter {
+  Nearest,
+  Bilinear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapMode {
+  Wrap,
+  Clamp,
+  Mirror,
+}
+
+#[derive(Clone)]
+pub struct ImageTexture {
+  pub canvas: Canvas,
+  pub mapping: UvMapping,
+  pub filter: TextureFilter,
+  pub wrap: WrapMode,
+}
+
+//  Instantiations
+impl ImageTexture {
+  pub fn new(canvas: Canvas, mapping: UvMapping) -> Self {
+    Self {
+      canvas,
+      mapping,
+      filter: TextureFilter::Bilinear,
+      wrap: WrapMode::Wrap,
+    }
+  }
+
+  pub fn from_png(filename: &str, mapping: UvMapping) -> Self {
+    Self::new(Canvas::from_png(filename), mapping)
+  }
+
+  pub fn from_ppm(filename: &str, mapping: UvMapping) -> Self {
+    Self::new(Canvas::from_ppm(filename), mapping)
+  }
+}
+
+//  Methods
+impl ImageTexture {
+  pub fn color_at(&self, point: &Tuple) -> Color {
+    let (u, v) = self.mapping.map(point);
+    self.uv_color_at(u, v)
+  }
+
+  pub fn uv_color_at(&self, u: f64, v: f64) -> Color {
+    sample_canvas(&self.canvas, u, v, self.filter, self.wrap)
+  }
+}
+
+impl WrapMode {
+  pub fn address(&self, index: i64, size: usize) -> usize {
+    let size = size as i64;
+    let address = match self {
+      WrapMode::Wrap => index.rem_euclid(size),
+      WrapMode::Clamp => index.clamp(0, size - 1),
+      WrapMode::Mirror => {
+        let mirrored = index.rem_euclid(2 * size);
+        if mirrored < size {
+          mirrored
+        } else {
+          2 * size - 1 - mirrored
+        }
+      }
+    };
+    address as usize
+  }
+}
+
+//  Texel centers sit at half-integer positions, and v runs from the bottom of the
+//    canvas (v = 0) to the top (v = 1).
+pub fn sample_canvas(
+  canvas: &Canvas,
+  u: f64,
+  v: f64,
+  filter: TextureFilter,
+  wrap: WrapMode,
+) -> Color {
+  let x = u * canvas.width as f64;
+  let y = (1.0 - v) * canvas.height as f64;
+  match filter {
+    TextureFilter::Nearest => texel_at(canvas, x.floor() as i64, y.floor() as i64, wrap),
+    TextureFilter::Bilinear => {
+      let x = x - 0.5;
+      let y = y - 0.5;
+      let x0 = x.floor();
+      let y0 = y.floor();
+      let tx = x - x0;
+      let ty = y - y0;
+      let (x0, y0) = (x0 as i64, y0 as i64);
+      let top =
+        texel_at(canvas, x0, y0, wrap) * (1.0 - tx) + texel_at(canvas, x0 + 1, y0, wrap) * tx;
+      let bottom = texel_at(canvas, x0, y0 + 1, wrap) * (1.0 - tx)
+        + texel_at(canvas, x0 + 1, y0 + 1, wrap) * tx;
+      top * (1.0 - ty) + bottom * ty
+    }
+  }
+}
+
+fn texel_at(canvas: &Canvas, x: i64, y: i64, wrap: WrapMode) -> Color {
+  canvas.pixel_at(
+    wrap.address(x, canvas.width),
+    wrap.address(y, canvas.height),
+  )
+}
+
+#[cfg(test)]
+mod image_texture_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn checkers() -> Canvas {
+    let mut canvas = Canvas::new(2, 2);
+    canvas.write_pixel(0, 0, Color::white());
+    canvas.write_pixel(1, 1, Color::white());
+    canvas
+  }
+
+  mod addressing_tests {
+    use super::*;
+
+    #[test]
+    fn wrapping_an_index_repeats_the_texture() {
+      assert_eq!(WrapMode::Wrap.address(-1, 4), 3);
+      assert_eq!(WrapMode::Wrap.address(4, 4), 0);
+      assert_eq!(WrapMode::Wrap.address(9, 4), 1);
+    }
+
+    #[test]
+    fn clamping_an_index_repeats_the_edge_texel() {
+      assert_eq!(WrapMode::Clamp.address(-3, 4), 0);
+      assert_eq!(WrapMode::Clamp.address(2, 4), 2);
+      assert_eq!(WrapMode::Clamp.address(9, 4), 3);
+    }
+
+    #[test]
+    fn mirroring_an_index_reflects_the_texture() {
+      assert_eq!(WrapMode::Mirror.address(-1, 4), 0);
+      assert_eq!(WrapMode::Mirror.address(4, 4), 3);
+      assert_eq!(WrapMode::Mirror.address(6, 4), 1);
+      assert_eq!(WrapMode::Mirror.address(8, 4), 0);
+    }
+  }
+
+  mod sampling_tests {
+    use super::*;
+
+    #[test]
+    fn nearest_sampling_picks_the_texel_containing_the_uv() {
+      let mut texture = ImageTexture::new(checkers(), UvMapping::Planar);
+      texture.filter = TextureFilter::Nearest;
+
+      assert_feq!(texture.uv_color_at(0.25, 0.75), Color::white());
+      assert_feq!(texture.uv_color_at(0.75, 0.75), Color::black());
+      assert_feq!(texture.uv_color_at(0.25, 0.25), Color::black());
+      assert_feq!(texture.uv_color_at(0.75, 0.25), Color::white());
+    }
+
+    #[test]
+    fn bilinear_sampling_at_a_texel_center_returns_the_texel() {
+      let texture = ImageTexture::new(checkers(), UvMapping::Planar);
+
+      assert_feq!(texture.uv_color_at(0.25, 0.75), Color::white());
+      assert_feq!(texture.uv_color_at(0.75, 0.75), Color::black());
+    }
+
+    #[test]
+    fn bilinear_sampling_blends_neighbouring_texels() {
+      let texture = ImageTexture::new(checkers(), UvMapping::Planar);
+
+      let actual = texture.uv_color_at(0.5, 0.75);
+
+      assert_feq!(actual, Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn bilinear_sampling_respects_the_wrap_mode_at_the_edges() {
+      let mut canvas = Canvas::new(2, 1);
+      canvas.write_pixel(0, 0, Color::white());
+      let mut texture = ImageTexture::new(canvas, UvMapping::Planar);
+
+      let wrapped = texture.uv_color_at(0.0, 0.5);
+      texture.wrap = WrapMode::Clamp;
+      let clamped = texture.uv_color_at(0.0, 0.5);
+
+      assert_feq!(wrapped, Color::new(0.5, 0.5, 0.5));
+      assert_feq!(clamped, Color::white());
+    }
+
+    #[test]
+    fn texturing_a_point_uses_the_uv_mapping() {
+      let mut texture = ImageTexture::new(checkers(), UvMapping::Planar);
+      texture.filter = TextureFilter::Nearest;
+
+      assert_feq!(
+        texture.color_at(&Tuple::point(0.25, 0.0, 0.75)),
+        Color::white()
+      );
+      assert_feq!(
+        texture.color_at(&Tuple::point(1.75, 0.0, 0.75)),
+        Color::black()
+      );
+      assert_feq!(
+        texture.color_at(&Tuple::point(0.75, 0.0, 0.25)),
+        Color::white()
+      );
+    }
+  }
+}
@@ -0,0 +1,493 @@
+use super::image_texture::{sample_canvas, TextureFilter, WrapMode};
+use crate::drawing::canvas::Canvas;
+use crate::drawing::color::Color;
+use crate::math::ray::Ray;
+use crate::math::special::sinc;
+use crate::math::tuple::Tuple;
+
+const KAISER_RADIUS: f64 = 2.0;
+const KAISER_ALPHA: f64 = 4.0;
+
+//  The fraction of the way toward a ray's offset points at which the uv
+//    map is followed to measure a footprint.
+const FOOTPRINT_STEP: f64 = 1e-4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Downsampling {
+  Box,
+  Kaiser,
+}
+
+//  Texture-space derivatives of the uv coordinates with respect to a one pixel
+//    step across (x) and down (y) the image plane.
+#[derive(Clone, Copy, Debug)]
+pub struct UvDifferentials {
+  pub dudx: f64,
+  pub dvdx: f64,
+  pub dudy: f64,
+  pub dvdy: f64,
+}
+
+#[derive(Clone)]
+pub struct MipMap {
+  pub levels: Vec<Canvas>,
+  pub wrap: WrapMode,
+  pub max_anisotropy: usize,
+}
+
+//  Instantiations
+impl MipMap {
+  pub fn new(canvas: Canvas, downsampling: Downsampling, wrap: WrapMode) -> Self {
+    let mut levels = vec![canvas];
+    loop {
+      let previous = levels.last().unwrap();
+      if previous.width == 1 && previous.height == 1 {
+        break;
+      }
+      let next = downsample(previous, downsampling, wrap);
+      levels.push(next);
+    }
+    Self {
+      levels,
+      wrap,
+      max_anisotropy: 16,
+    }
+  }
+}
+
+impl UvDifferentials {
+  pub fn new(dudx: f64, dvdx: f64, dudy: f64, dvdy: f64) -> Self {
+    Self {
+      dudx,
+      dvdx,
+      dudy,
+      dvdy,
+    }
+  }
+
+  //  Measures the footprint of the ray's pixel on the surface at point by
+  //    mapping where the ray's differentials meet the tangent plane into
+  //    texture space. A seam makes the coordinates jump by a whole unit, so
+  //    the map is followed a short step toward each offset point, where the
+  //    difference is only wrapped if a seam lies right there, and the step
+  //    is scaled back up. A footprint is at most the whole texture. None
+  //    when the ray carries no differentials or they run parallel to the
+  //    surface.
+  pub fn from_ray<F>(ray: &Ray, point: &Tuple, normalv: &Tuple, uv: F) -> Option<Self>
+  where
+    F: Fn(&Tuple) -> (f64, f64),
+  {
+    let (x_point, y_point) = ray.differentials.as_ref()?.meet(point, normalv)?;
+    let (u, v) = uv(point);
+    let toward = |offset: Tuple| {
+      let (near_u, near_v) = uv(&(point.clone() + (offset - point.clone()) * FOOTPRINT_STEP));
+      let wrap = |d: f64| ((d - d.round()) / FOOTPRINT_STEP).clamp(-1.0, 1.0);
+      (wrap(near_u - u), wrap(near_v - v))
+    };
+    let (dudx, dvdx) = toward(x_point);
+    let (dudy, dvdy) = toward(y_point);
+    Some(Self::new(dudx, dvdx, dudy, dvdy))
+  }
+}
+
+//  Methods
+impl MipMap {
+  pub fn level_count(&self) -> usize {
+    self.levels.len()
+  }
+
+  pub fn sample_level(&self, u: f64, v: f64, lod: f64) -> Color {
+    let lod = lod.clamp(0.0, (self.level_count() - 1) as f64);
+    let lower = lod.floor();
+    let fine = sample_canvas(
+      &self.levels[lower as usize],
+      u,
+      v,
+      TextureFilter::Bilinear,
+      self.wrap,
+    );
+    let t = lod - lower;
+    if t == 0.0 {
+      return fine;
+    }
+    let coarse = sample_canvas(
+      &self.levels[lower as usize + 1],
+      u,
+      v,
+      TextureFilter::Bilinear,
+      self.wrap,
+    );
+    fine * (1.0 - t) + coarse * t
+  }
+
+  pub fn trilinear(&self, u: f64, v: f64, footprint: f64) -> Color {
+    let texels = footprint * self.texel_scale();
+    self.sample_level(u, v, texels.max(1.0).log2())
+  }
+
+  pub fn anisotropic(&self, u: f64, v: f64, differentials: &UvDifferentials) -> Color {
+    let base = &self.levels[0];
+    let (width, height) = (base.width as f64, base.height as f64);
+    let x_length = (differentials.dudx * width).hypot(differentials.dvdx * height);
+    let y_length = (differentials.dudy * width).hypot(differentials.dvdy * height);
+    let (major, minor, du, dv) = if x_length >= y_length {
+      (x_length, y_length, differentials.dudx, differentials.dvdx)
+    } else {
+      (y_length, x_length, differentials.dudy, differentials.dvdy)
+    };
+    if major == 0.0 {
+      return self.sample_level(u, v, 0.0);
+    }
+    let ratio = if minor == 0.0 {
+      f64::INFINITY
+    } else {
+      major / minor
+    };
+    let probes = (ratio.ceil() as usize).clamp(1, self.max_anisotropy.max(1));
+    let lod = (major / probes as f64).max(1.0).log2();
+    let mut color = Color::black();
+    for probe in 0..probes {
+      let offset = (probe as f64 + 0.5) / probes as f64 - 0.5;
+      color += self.sample_level(u + du * offset, v + dv * offset, lod);
+    }
+    color / probes as f64
+  }
+
+  fn texel_scale(&self) -> f64 {
+    self.levels[0].width.max(self.levels[0].height) as f64
+  }
+}
+
+fn downsample(source: &Canvas, downsampling: Downsampling, wrap: WrapMode) -> Canvas {
+  let width = (source.width / 2).max(1);
+  let height = (source.height / 2).max(1);
+  let horizontal = resample_axis(source, width, source.height, true, downsampling, wrap);
+  resample_axis(&horizontal, width, height, false, downsampling, wrap)
+}
+
+fn resample_axis(
+  source: &Canvas,
+  width: usize,
+  height: usize,
+  horizontal: bool,
+  downsampling: Downsampling,
+  wrap: WrapMode,
+) -> Canvas {
+  let (source_size, target_size) = if horizontal {
+    (source.width, width)
+  } else {
+    (source.height, height)
+  };
+  let scale = source_size as f64 / target_size as f64;
+  let radius = match downsampling {
+    Downsampling::Box => 0.5,
+    Downsampling::Kaiser => KAISER_RADIUS,
+  };
+  let mut target = Canvas::new(width, height);
+  for y in 0..height {
+    for x in 0..width {
+      let index = if horizontal { x } else { y };
+      let center = (index as f64 + 0.5) * scale;
+      let first = (center - radius * scale).floor() as i64;
+      let last = (center + radius * scale).ceil() as i64;
+      let mut color = Color::black();
+      let mut total_weight = 0.0;
+      for tap in first..=last {
+        let distance = (tap as f64 + 0.5 - center) / scale;
+        let weight = match downsampling {
+          Downsampling::Box => box_weight(distance),
+          Downsampling::Kaiser => kaiser_weight(distance),
+        };
+        if weight == 0.0 {
+          continue;
+        }
+        let address = wrap.address(tap, source_size);
+        let texel = if horizontal {
+          source.pixel_at(address, y)
+        } else {
+          source.pixel_at(x, address)
+        };
+        color += texel * weight;
+        total_weight += weight;
+      }
+      let color = color / total_weight;
+      target.write_pixel(
+        x,
+        y,
+        Color::new(
+          color.red.max(0.0),
+          color.green.max(0.0),
+          color.blue.max(0.0),
+        ),
+      );
+    }
+  }
+  target
+}
+
+fn box_weight(distance: f64) -> f64 {
+  if distance.abs() < 0.5 {
+    1.0
+  } else {
+    0.0
+  }
+}
+
+fn kaiser_weight(distance: f64) -> f64 {
+  let ratio = distance / KAISER_RADIUS;
+  if ratio.abs() >= 1.0 {
+    return 0.0;
+  }
+  let window = bessel_i0(KAISER_ALPHA * (1.0 - ratio * ratio).sqrt()) / bessel_i0(KAISER_ALPHA);
+  sinc(distance) * window
+}
+
+fn bessel_i0(x: f64) -> f64 {
+  let mut sum = 1.0;
+  let mut term = 1.0;
+  let quarter_square = x * x / 4.0;
+  let mut k = 1.0;
+  while term > sum * 1e-12 {
+    term *= quarter_square / (k * k);
+    sum += term;
+    k += 1.0;
+  }
+  sum
+}
+
+#[cfg(test)]
+mod mipmap_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn checkers(size: usize) -> Canvas {
+    let mut canvas = Canvas::new(size, size);
+    for y in 0..size {
+      for x in 0..size {
+        if (x + y) % 2 == 0 {
+          canvas.write_pixel(x, y, Color::white());
+        }
+      }
+    }
+    canvas
+  }
+
+  fn stripes(size: usize) -> Canvas {
+    let mut canvas = Canvas::new(size, size);
+    for y in 0..size {
+      for x in (0..size).step_by(2) {
+        canvas.write_pixel(x, y, Color::white());
+      }
+    }
+    canvas
+  }
+
+  mod instantiation_tests {
+    use super::*;
+
+    #[test]
+    fn building_a_pyramid_halves_each_level_down_to_one_texel() {
+      let mipmap = MipMap::new(Canvas::new(8, 4), Downsampling::Box, WrapMode::Wrap);
+
+      let sizes: Vec<(usize, usize)> = mipmap
+        .levels
+        .iter()
+        .map(|level| (level.width, level.height))
+        .collect();
+
+      assert_eq!(sizes, vec![(8, 4), (4, 2), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn box_downsampling_averages_each_two_by_two_block() {
+      let mipmap = MipMap::new(checkers(4), Downsampling::Box, WrapMode::Wrap);
+
+      for y in 0..2 {
+        for x in 0..2 {
+          assert_feq!(mipmap.levels[1].pixel_at(x, y), Color::new(0.5, 0.5, 0.5));
+        }
+      }
+    }
+
+    #[test]
+    fn kaiser_downsampling_preserves_a_constant_texture() {
+      let mut canvas = Canvas::new(8, 8);
+      for y in 0..8 {
+        for x in 0..8 {
+          canvas.write_pixel(x, y, Color::new(0.2, 0.4, 0.6));
+        }
+      }
+
+      let mipmap = MipMap::new(canvas, Downsampling::Kaiser, WrapMode::Clamp);
+
+      for level in mipmap.levels.iter() {
+        assert_feq!(level.pixel_at(0, 0), Color::new(0.2, 0.4, 0.6));
+      }
+    }
+
+    #[test]
+    fn the_top_of_the_pyramid_is_the_average_color() {
+      let mipmap = MipMap::new(checkers(8), Downsampling::Kaiser, WrapMode::Wrap);
+
+      let top = mipmap.levels.last().unwrap().pixel_at(0, 0);
+
+      assert_feq!(top, Color::new(0.5, 0.5, 0.5));
+    }
+  }
+
+  mod lookup_tests {
+    use super::*;
+
+    #[test]
+    fn a_tiny_footprint_samples_the_base_level() {
+      let mipmap = MipMap::new(checkers(4), Downsampling::Box, WrapMode::Wrap);
+
+      let actual = mipmap.trilinear(0.125, 0.875, 0.0);
+
+      assert_feq!(actual, Color::white());
+    }
+
+    #[test]
+    fn a_footprint_covering_the_texture_samples_the_average() {
+      let mipmap = MipMap::new(checkers(4), Downsampling::Box, WrapMode::Wrap);
+
+      let actual = mipmap.trilinear(0.125, 0.875, 1.0);
+
+      assert_feq!(actual, Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn trilinear_lookups_blend_between_levels() {
+      let mipmap = MipMap::new(checkers(4), Downsampling::Box, WrapMode::Wrap);
+
+      let actual = mipmap.sample_level(0.125, 0.875, 0.5);
+
+      assert_feq!(actual, Color::new(0.75, 0.75, 0.75));
+    }
+
+    #[test]
+    fn isotropic_differentials_match_a_trilinear_lookup() {
+      let mipmap = MipMap::new(checkers(8), Downsampling::Box, WrapMode::Wrap);
+      let differentials = UvDifferentials::new(0.25, 0.0, 0.0, 0.25);
+
+      let actual = mipmap.anisotropic(0.3, 0.6, &differentials);
+      let expected = mipmap.trilinear(0.3, 0.6, 0.25);
+
+      assert_feq!(actual, expected);
+    }
+
+    #[test]
+    fn anisotropic_lookups_stay_sharp_across_the_minor_axis() {
+      let mipmap = MipMap::new(stripes(16), Downsampling::Box, WrapMode::Wrap);
+      let differentials = UvDifferentials::new(1.0 / 16.0, 0.0, 0.0, 1.0);
+      let u = 0.5 / 16.0;
+
+      let anisotropic = mipmap.anisotropic(u, 0.5, &differentials);
+      let trilinear = mipmap.trilinear(u, 0.5, 1.0);
+
+      assert_feq!(anisotropic, Color::white());
+      assert_feq!(trilinear, Color::new(0.5, 0.5, 0.5));
+    }
+  }
+
+  mod differential_tests {
+    use super::*;
+    use crate::math::ray::RayDifferentials;
+    use crate::texture::uv_mapping::{cylindrical_map, planar_map};
+
+    //  Looking straight down at the floor from a height of four, with the
+    //    offset rays spreading by the given amounts per unit.
+    fn spreading(x: f64, z: f64, across: f64, down: f64) -> Ray {
+      let origin = Tuple::point(x, 4.0, z);
+      Ray::new(origin.clone(), Tuple::vector(0.0, -1.0, 0.0)).with_differentials(Some(
+        RayDifferentials {
+          x_origin: origin.clone(),
+          x_direction: Tuple::vector(across, -1.0, 0.0),
+          y_origin: origin,
+          y_direction: Tuple::vector(0.0, -1.0, down),
+        },
+      ))
+    }
+
+    fn looking_down(x: f64, z: f64) -> Ray {
+      spreading(x, z, 0.1, 0.05)
+    }
+
+    #[test]
+    fn the_footprint_grows_with_distance() {
+      let ray = looking_down(0.3, 0.3);
+      let point = Tuple::point(0.3, 0.0, 0.3);
+
+      let differentials =
+        UvDifferentials::from_ray(&ray, &point, &Tuple::vector(0.0, 1.0, 0.0), planar_map).unwrap();
+
+      assert_feq!(differentials.dudx, 0.4);
+      assert_feq!(differentials.dvdx, 0.0);
+      assert_feq!(differentials.dudy, 0.0);
+      assert_feq!(differentials.dvdy, 0.2);
+    }
+
+    #[test]
+    fn differences_are_taken_across_the_texture_seam() {
+      let ray = looking_down(0.9, 0.0);
+      let point = Tuple::point(0.9, 0.0, 0.0);
+
+      let differentials =
+        UvDifferentials::from_ray(&ray, &point, &Tuple::vector(0.0, 1.0, 0.0), planar_map).unwrap();
+
+      assert_feq!(differentials.dudx, 0.4);
+    }
+
+    #[test]
+    fn footprints_wider_than_half_the_texture_keep_their_size() {
+      let ray = spreading(0.3, 0.3, 0.2, 0.5);
+      let point = Tuple::point(0.3, 0.0, 0.3);
+
+      let differentials =
+        UvDifferentials::from_ray(&ray, &point, &Tuple::vector(0.0, 1.0, 0.0), planar_map).unwrap();
+
+      assert_feq!(differentials.dudx, 0.8);
+      assert_feq!(differentials.dvdy, 1.0);
+    }
+
+    #[test]
+    fn differences_wrap_across_the_seam_of_a_cylinder() {
+      let origin = Tuple::point(0.0, 0.5, -4.0);
+      let ray = Ray::new(origin.clone(), Tuple::vector(0.0, 0.0, 1.0)).with_differentials(Some(
+        RayDifferentials {
+          x_origin: origin.clone(),
+          x_direction: Tuple::vector(0.1, 0.0, 1.0),
+          y_origin: origin,
+          y_direction: Tuple::vector(-0.1, 0.0, 1.0),
+        },
+      ));
+      let point = Tuple::point(0.0, 0.5, -1.0);
+
+      let differentials = UvDifferentials::from_ray(
+        &ray,
+        &point,
+        &Tuple::vector(0.0, 0.0, -1.0),
+        cylindrical_map,
+      )
+      .unwrap();
+
+      assert!(differentials.dudx.abs() < 0.1);
+      assert_feq!(differentials.dudx, -differentials.dudy);
+    }
+
+    #[test]
+    fn a_ray_without_differentials_has_no_footprint() {
+      let ray = Ray::new(Tuple::point(0.0, 4.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+      let differentials = UvDifferentials::from_ray(
+        &ray,
+        &Tuple::origin(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+        planar_map,
+      );
+
+      assert!(differentials.is_none());
+    }
+  }
+}
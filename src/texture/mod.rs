@@ -0,0 +1,6 @@
+pub mod environment_map;
+pub mod image_texture;
+pub mod mipmap;
+pub mod noise;
+pub mod normal_map;
+pub mod uv_mapping;
@@ -0,0 +1,172 @@
+use crate::math::random::Pcg32;
+use crate::math::tuple::Tuple;
+
+//  Improved Perlin gradient noise over 3D points, with the permutation
+//    shuffled from a seed.
+#[derive(Clone, Debug)]
+pub struct Perlin {
+  permutation: Vec<usize>,
+}
+
+//  Instantiations
+impl Perlin {
+  pub fn new(seed: u64) -> Self {
+    let mut rng = Pcg32::new(seed, 0);
+    let mut permutation: Vec<usize> = (0..256).collect();
+    for i in (1..256).rev() {
+      let j = rng.next_u32() as usize % (i + 1);
+      permutation.swap(i, j);
+    }
+    permutation.extend_from_within(..);
+    Self { permutation }
+  }
+}
+
+//  Methods
+impl Perlin {
+  //  Roughly within [-1, 1], and zero at every integer lattice point.
+  pub fn noise(&self, point: &Tuple) -> f64 {
+    let cell = |value: f64| (value.floor() as i64).rem_euclid(256) as usize;
+    let (x, y, z) = (cell(point.x), cell(point.y), cell(point.z));
+    let (fx, fy, fz) = (
+      point.x - point.x.floor(),
+      point.y - point.y.floor(),
+      point.z - point.z.floor(),
+    );
+    let (u, v, w) = (fade(fx), fade(fy), fade(fz));
+    let p = &self.permutation;
+
+    let a = p[x] + y;
+    let (aa, ab) = (p[a] + z, p[a + 1] + z);
+    let b = p[x + 1] + y;
+    let (ba, bb) = (p[b] + z, p[b + 1] + z);
+
+    lerp(
+      w,
+      lerp(
+        v,
+        lerp(
+          u,
+          gradient(p[aa], fx, fy, fz),
+          gradient(p[ba], fx - 1.0, fy, fz),
+        ),
+        lerp(
+          u,
+          gradient(p[ab], fx, fy - 1.0, fz),
+          gradient(p[bb], fx - 1.0, fy - 1.0, fz),
+        ),
+      ),
+      lerp(
+        v,
+        lerp(
+          u,
+          gradient(p[aa + 1], fx, fy, fz - 1.0),
+          gradient(p[ba + 1], fx - 1.0, fy, fz - 1.0),
+        ),
+        lerp(
+          u,
+          gradient(p[ab + 1], fx, fy - 1.0, fz - 1.0),
+          gradient(p[bb + 1], fx - 1.0, fy - 1.0, fz - 1.0),
+        ),
+      ),
+    )
+  }
+
+  //  A sum of octaves of absolute noise, each at twice the frequency and
+  //    half the weight of the last.
+  pub fn turbulence(&self, point: &Tuple, octaves: usize) -> f64 {
+    let mut sum = 0.0;
+    let mut scale = 1.0;
+    for _ in 0..octaves {
+      let scaled = Tuple::point(point.x * scale, point.y * scale, point.z * scale);
+      sum += self.noise(&scaled).abs() / scale;
+      scale *= 2.0;
+    }
+    sum
+  }
+}
+
+fn fade(t: f64) -> f64 {
+  t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+  a + t * (b - a)
+}
+
+fn gradient(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+  let h = hash & 15;
+  let u = if h < 8 { x } else { y };
+  let v = if h < 4 {
+    y
+  } else if h == 12 || h == 14 {
+    x
+  } else {
+    z
+  };
+  (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[cfg(test)]
+mod noise_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn noise_vanishes_on_the_lattice() {
+    let perlin = Perlin::new(1);
+
+    for point in [
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::point(3.0, -2.0, 7.0),
+      Tuple::point(-1.0, 5.0, 300.0),
+    ] {
+      assert_feq!(perlin.noise(&point), 0.0);
+    }
+  }
+
+  #[test]
+  fn noise_is_bounded_and_varies_smoothly() {
+    let perlin = Perlin::new(2);
+    let mut rng = Pcg32::new(3, 0);
+
+    for _ in 0..1000 {
+      let point = Tuple::point(
+        rng.next_f64() * 20.0,
+        rng.next_f64() * 20.0,
+        rng.next_f64() * 20.0,
+      );
+      let nearby = Tuple::point(point.x + 1e-4, point.y, point.z);
+
+      let value = perlin.noise(&point);
+
+      assert!(value.abs() <= 1.05);
+      assert!((perlin.noise(&nearby) - value).abs() < 1e-3);
+    }
+  }
+
+  #[test]
+  fn the_seed_chooses_the_pattern() {
+    let point = Tuple::point(0.3, 1.7, 2.2);
+
+    assert_feq!(Perlin::new(5).noise(&point), Perlin::new(5).noise(&point));
+    assert!((Perlin::new(5).noise(&point) - Perlin::new(6).noise(&point)).abs() > 1e-9);
+  }
+
+  #[test]
+  fn turbulence_is_never_negative() {
+    let perlin = Perlin::new(4);
+    let mut rng = Pcg32::new(5, 0);
+
+    for _ in 0..100 {
+      let point = Tuple::point(
+        rng.next_f64() * 5.0,
+        rng.next_f64() * 5.0,
+        rng.next_f64() * 5.0,
+      );
+
+      assert!(perlin.turbulence(&point, 4) >= 0.0);
+    }
+  }
+}
@@ -0,0 +1,328 @@
+use super::image_texture::{sample_canvas, TextureFilter, WrapMode};
+use crate::drawing::canvas::Canvas;
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+const DIFFERENCE_STEP: f64 = 1e-4;
+
+//  An orthonormal frame with the tangent along increasing u and the
+//    bitangent along increasing v, as closely as the normal allows.
+#[derive(Clone, Debug)]
+pub struct TangentFrame {
+  pub tangent: Tuple,
+  pub bitangent: Tuple,
+  pub normal: Tuple,
+}
+
+//  Instantiations
+impl TangentFrame {
+  //  Orthonormalizes the surface derivatives against the normal, falling
+  //    back to arbitrary axes where they are degenerate.
+  pub fn from_derivatives(dpdu: Tuple, dpdv: Tuple, normal: Tuple) -> Self {
+    let normal = normal.normalize();
+    let tangent = dpdu.clone() - normal.clone() * normal.clone().dot(dpdu);
+    if tangent.clone().magnitude() < 1e-12 {
+      let (tangent, bitangent) = normal.clone().perpendicular_axes();
+      return Self {
+        tangent,
+        bitangent,
+        normal,
+      };
+    }
+    let tangent = tangent.normalize();
+    let mut bitangent = normal.clone().cross(tangent.clone());
+    if bitangent.clone().dot(dpdv) < 0.0 {
+      bitangent = -bitangent;
+    }
+    Self {
+      tangent,
+      bitangent,
+      normal,
+    }
+  }
+
+  //  The frame a triangle's texture coordinates define.
+  pub fn from_triangle(points: &[Tuple; 3], uvs: &[(f64, f64); 3], normal: Tuple) -> Self {
+    let e1 = points[1].clone() - points[0].clone();
+    let e2 = points[2].clone() - points[0].clone();
+    let (du1, dv1) = (uvs[1].0 - uvs[0].0, uvs[1].1 - uvs[0].1);
+    let (du2, dv2) = (uvs[2].0 - uvs[0].0, uvs[2].1 - uvs[0].1);
+    let determinant = du1 * dv2 - du2 * dv1;
+    if determinant.abs() < 1e-12 {
+      return Self::from_derivatives(
+        Tuple::vector(0.0, 0.0, 0.0),
+        Tuple::vector(0.0, 0.0, 0.0),
+        normal,
+      );
+    }
+    let dpdu = (e1.clone() * dv2 - e2.clone() * dv1) / determinant;
+    let dpdv = (e2 * du1 - e1 * du2) / determinant;
+    Self::from_derivatives(dpdu, dpdv, normal)
+  }
+
+  //  The analytic frame of the spherical mapping on a sphere.
+  pub fn sphere(center: &Tuple, point: &Tuple) -> Self {
+    let normal = (point.clone() - center.clone()).normalize();
+    let theta = normal.x.atan2(normal.z);
+    let phi = normal.y.clamp(-1.0, 1.0).acos();
+    let dpdu = Tuple::vector(-normal.z, 0.0, normal.x) * (2.0 * PI);
+    let dpdv = Tuple::vector(
+      -phi.cos() * theta.sin(),
+      phi.sin(),
+      -phi.cos() * theta.cos(),
+    ) * PI;
+    Self::from_derivatives(dpdu, dpdv, normal)
+  }
+
+  //  The frame of the planar mapping on a plane facing +y.
+  pub fn plane() -> Self {
+    Self::from_derivatives(
+      Tuple::vector(1.0, 0.0, 0.0),
+      Tuple::vector(0.0, 0.0, 1.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    )
+  }
+}
+
+//  Methods
+impl TangentFrame {
+  pub fn to_world(&self, local: &Tuple) -> Tuple {
+    self.tangent.clone() * local.x
+      + self.bitangent.clone() * local.y
+      + self.normal.clone() * local.z
+  }
+}
+
+//  A tangent-space normal map, with each channel mapping [0, 1] to [-1, 1].
+//    Strength scales how far the normals lean away from the surface.
+#[derive(Clone)]
+pub struct NormalMap {
+  pub canvas: Canvas,
+  pub strength: f64,
+  pub filter: TextureFilter,
+  pub wrap: WrapMode,
+}
+
+//  Instantiations
+impl NormalMap {
+  pub fn new(canvas: Canvas) -> Self {
+    Self {
+      canvas,
+      strength: 1.0,
+      filter: TextureFilter::Bilinear,
+      wrap: WrapMode::Wrap,
+    }
+  }
+
+  pub fn from_png(filename: &str) -> Self {
+    Self::new(Canvas::from_png(filename))
+  }
+}
+
+//  Methods
+impl NormalMap {
+  pub fn perturb(&self, frame: &TangentFrame, u: f64, v: f64) -> Tuple {
+    let color = sample_canvas(&self.canvas, u, v, self.filter, self.wrap);
+    let local = Tuple::vector(
+      (color.red * 2.0 - 1.0) * self.strength,
+      (color.green * 2.0 - 1.0) * self.strength,
+      (color.blue * 2.0 - 1.0).max(0.0),
+    );
+    if local.clone().magnitude() == 0.0 {
+      return frame.normal.clone();
+    }
+    frame.to_world(&local).normalize()
+  }
+}
+
+//  Tilts the normal by the slope of a height field over the surface's
+//    texture coordinates, with heights measured in units of the frame's
+//    tangent and bitangent per unit of u and v.
+pub fn bump_uv<F>(frame: &TangentFrame, u: f64, v: f64, height: F, scale: f64) -> Tuple
+where
+  F: Fn(f64, f64) -> f64,
+{
+  let h = DIFFERENCE_STEP;
+  let dhdu = (height(u + h, v) - height(u - h, v)) / (2.0 * h);
+  let dhdv = (height(u, v + h) - height(u, v - h)) / (2.0 * h);
+  (frame.normal.clone()
+    - frame.tangent.clone() * (scale * dhdu)
+    - frame.bitangent.clone() * (scale * dhdv))
+    .normalize()
+}
+
+//  Tilts the normal by the slope of a height field over space, such as
+//    Perlin noise. Only the slope along the surface counts.
+pub fn bump_point<F>(normal: &Tuple, point: &Tuple, height: F, scale: f64) -> Tuple
+where
+  F: Fn(&Tuple) -> f64,
+{
+  let h = DIFFERENCE_STEP;
+  let slope = |offset: Tuple| {
+    (height(&(point.clone() + offset.clone())) - height(&(point.clone() - offset))) / (2.0 * h)
+  };
+  let gradient = Tuple::vector(
+    slope(Tuple::vector(h, 0.0, 0.0)),
+    slope(Tuple::vector(0.0, h, 0.0)),
+    slope(Tuple::vector(0.0, 0.0, h)),
+  );
+  let normal = normal.clone().normalize();
+  let along_surface = gradient.clone() - normal.clone() * normal.clone().dot(gradient);
+  (normal - along_surface * scale).normalize()
+}
+
+#[cfg(test)]
+mod normal_map_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::drawing::color::Color;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+  use crate::texture::noise::Perlin;
+  use crate::texture::uv_mapping::spherical_map;
+  use std::f64::consts::FRAC_1_SQRT_2;
+
+  fn filled(color: Color) -> Canvas {
+    let mut canvas = Canvas::new(2, 2);
+    for y in 0..2 {
+      for x in 0..2 {
+        canvas.write_pixel(x, y, color.clone());
+      }
+    }
+    canvas
+  }
+
+  mod frame_tests {
+    use super::*;
+
+    #[test]
+    fn a_triangles_frame_follows_its_texture_coordinates() {
+      let points = [
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::point(0.0, 0.0, 2.0),
+        Tuple::point(2.0, 0.0, 0.0),
+      ];
+      let uvs = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+
+      let frame = TangentFrame::from_triangle(&points, &uvs, Tuple::vector(0.0, 1.0, 0.0));
+
+      assert_feq!(frame.tangent, Tuple::vector(0.0, 0.0, 1.0));
+      assert_feq!(frame.bitangent, Tuple::vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn degenerate_texture_coordinates_still_give_a_frame() {
+      let points = [
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::point(1.0, 0.0, 0.0),
+        Tuple::point(0.0, 0.0, 1.0),
+      ];
+
+      let frame =
+        TangentFrame::from_triangle(&points, &[(0.5, 0.5); 3], Tuple::vector(0.0, 1.0, 0.0));
+
+      assert_feq!(frame.tangent.clone().dot(frame.normal.clone()), 0.0);
+      assert_feq!(frame.bitangent.clone().magnitude(), 1.0);
+    }
+
+    #[test]
+    fn a_spheres_tangent_points_along_increasing_u() {
+      let center = Tuple::origin();
+      let point = Tuple::origin() + Tuple::vector(0.3, 0.4, -0.866).normalize();
+
+      let frame = TangentFrame::sphere(&center, &point);
+      let (u, v) = spherical_map(&point);
+      let (u_ahead, _) = spherical_map(&(point.clone() + frame.tangent.clone() * 0.01));
+      let (_, v_ahead) = spherical_map(&(point.clone() + frame.bitangent.clone() * 0.01));
+
+      assert_feq!(frame.tangent.clone().dot(frame.normal.clone()), 0.0);
+      assert!(u_ahead > u);
+      assert!(v_ahead > v);
+    }
+
+    #[test]
+    fn a_plane_frame_follows_the_planar_mapping() {
+      let frame = TangentFrame::plane();
+
+      assert_feq!(frame.tangent, Tuple::vector(1.0, 0.0, 0.0));
+      assert_feq!(frame.bitangent, Tuple::vector(0.0, 0.0, 1.0));
+      assert_feq!(frame.normal, Tuple::vector(0.0, 1.0, 0.0));
+    }
+  }
+
+  mod perturbation_tests {
+    use super::*;
+
+    #[test]
+    fn a_flat_normal_map_leaves_the_normal_alone() {
+      let map = NormalMap::new(filled(Color::new(0.5, 0.5, 1.0)));
+
+      let normal = map.perturb(&TangentFrame::plane(), 0.3, 0.6);
+
+      assert_feq!(normal, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn a_normal_map_leans_the_normal_toward_the_tangent() {
+      let map = NormalMap::new(filled(Color::new(1.0, 0.5, 1.0)));
+
+      let normal = map.perturb(&TangentFrame::plane(), 0.3, 0.6);
+
+      assert_feq!(normal, Tuple::vector(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0));
+    }
+
+    #[test]
+    fn strength_scales_the_lean() {
+      let mut map = NormalMap::new(filled(Color::new(1.0, 0.5, 1.0)));
+      map.strength = 0.0;
+
+      let normal = map.perturb(&TangentFrame::plane(), 0.3, 0.6);
+
+      assert_feq!(normal, Tuple::vector(0.0, 1.0, 0.0));
+    }
+  }
+
+  mod bump_tests {
+    use super::*;
+
+    #[test]
+    fn a_flat_height_field_leaves_the_normal_alone() {
+      let normal = bump_uv(&TangentFrame::plane(), 0.2, 0.7, |_, _| 3.0, 1.0);
+
+      assert_feq!(normal, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn a_rising_height_field_tilts_the_normal_away_from_the_slope() {
+      let normal = bump_uv(&TangentFrame::plane(), 0.2, 0.7, |u, _| u, 1.0);
+
+      assert_feq!(normal, Tuple::vector(-FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0));
+    }
+
+    #[test]
+    fn slopes_across_the_surface_do_not_tilt_the_normal() {
+      let normal = bump_point(
+        &Tuple::vector(0.0, 1.0, 0.0),
+        &Tuple::point(1.0, 0.0, 2.0),
+        |point| 5.0 * point.y,
+        1.0,
+      );
+
+      assert_feq!(normal, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn noise_bumps_keep_normals_on_the_surfaces_side() {
+      let perlin = Perlin::new(0);
+      let up = Tuple::vector(0.0, 1.0, 0.0);
+
+      for i in 0..20 {
+        let point = Tuple::point(i as f64 * 0.37, 0.0, i as f64 * 0.21);
+
+        let normal = bump_point(&up, &point, |p| perlin.noise(p), 0.3);
+
+        assert_feq!(normal.clone().magnitude(), 1.0);
+        assert!(normal.y > 0.5);
+      }
+    }
+  }
+}
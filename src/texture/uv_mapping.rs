@@ -0,0 +1,259 @@
+use crate::math::tuple::Tuple;
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UvMapping {
+  Spherical,
+  Planar,
+  Cylindrical,
+  Cube,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CubeFace {
+  Left,
+  Right,
+  Front,
+  Back,
+  Up,
+  Down,
+}
+
+//  Methods
+impl UvMapping {
+  pub fn map(&self, point: &Tuple) -> (f64, f64) {
+    match self {
+      UvMapping::Spherical => spherical_map(point),
+      UvMapping::Planar => planar_map(point),
+      UvMapping::Cylindrical => cylindrical_map(point),
+      UvMapping::Cube => cube_atlas_map(point),
+    }
+  }
+}
+
+impl CubeFace {
+  pub fn from_point(point: &Tuple) -> Self {
+    let coordinate = point.x.abs().max(point.y.abs()).max(point.z.abs());
+    if coordinate == point.x {
+      CubeFace::Right
+    } else if coordinate == -point.x {
+      CubeFace::Left
+    } else if coordinate == point.y {
+      CubeFace::Up
+    } else if coordinate == -point.y {
+      CubeFace::Down
+    } else if coordinate == point.z {
+      CubeFace::Front
+    } else {
+      CubeFace::Back
+    }
+  }
+
+  pub fn uv(&self, point: &Tuple) -> (f64, f64) {
+    let (u, v) = match self {
+      CubeFace::Front => (point.x + 1.0, point.y + 1.0),
+      CubeFace::Back => (1.0 - point.x, point.y + 1.0),
+      CubeFace::Left => (point.z + 1.0, point.y + 1.0),
+      CubeFace::Right => (1.0 - point.z, point.y + 1.0),
+      CubeFace::Up => (point.x + 1.0, 1.0 - point.z),
+      CubeFace::Down => (point.x + 1.0, point.z + 1.0),
+    };
+    (u.rem_euclid(2.0) / 2.0, v.rem_euclid(2.0) / 2.0)
+  }
+
+  //  Faces are laid out as a horizontal cross, four cells wide and three tall:
+  //           [up]
+  //    [left][front][right][back]
+  //          [down]
+  fn atlas_cell(&self) -> (f64, f64) {
+    match self {
+      CubeFace::Left => (0.0, 1.0),
+      CubeFace::Front => (1.0, 1.0),
+      CubeFace::Right => (2.0, 1.0),
+      CubeFace::Back => (3.0, 1.0),
+      CubeFace::Up => (1.0, 2.0),
+      CubeFace::Down => (1.0, 0.0),
+    }
+  }
+}
+
+pub fn spherical_map(point: &Tuple) -> (f64, f64) {
+  let theta = point.x.atan2(point.z);
+  let radius = Tuple::vector(point.x, point.y, point.z).magnitude();
+  let phi = (point.y / radius).acos();
+  let raw_u = theta / (2.0 * PI);
+  (1.0 - (raw_u + 0.5), 1.0 - phi / PI)
+}
+
+//  The inverse of spherical_map, giving the unit direction for a (u, v).
+pub fn spherical_direction(u: f64, v: f64) -> Tuple {
+  let theta = 2.0 * PI * (0.5 - u);
+  let phi = PI * (1.0 - v);
+  Tuple::vector(phi.sin() * theta.sin(), phi.cos(), phi.sin() * theta.cos())
+}
+
+pub fn planar_map(point: &Tuple) -> (f64, f64) {
+  (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+}
+
+pub fn cylindrical_map(point: &Tuple) -> (f64, f64) {
+  let theta = point.x.atan2(point.z);
+  let raw_u = theta / (2.0 * PI);
+  (1.0 - (raw_u + 0.5), point.y.rem_euclid(1.0))
+}
+
+pub fn cube_map(point: &Tuple) -> (CubeFace, f64, f64) {
+  let face = CubeFace::from_point(point);
+  let (u, v) = face.uv(point);
+  (face, u, v)
+}
+
+fn cube_atlas_map(point: &Tuple) -> (f64, f64) {
+  let (face, u, v) = cube_map(point);
+  let (column, row) = face.atlas_cell();
+  ((column + u) / 4.0, (row + v) / 3.0)
+}
+
+#[cfg(test)]
+mod uv_mapping_tests {
+  use super::*;
+  use crate::assert_feq;
+  use crate::macros::fuzzy_eq::FuzzyEq;
+
+  fn assert_uv(actual: (f64, f64), expected: (f64, f64)) {
+    assert_feq!(actual.0, expected.0);
+    assert_feq!(actual.1, expected.1);
+  }
+
+  mod spherical_tests {
+    use super::*;
+
+    #[test]
+    fn using_a_spherical_mapping_on_a_3d_point() {
+      let root_half = 2.0_f64.sqrt() / 2.0;
+      let cases = [
+        (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.5)),
+        (Tuple::point(1.0, 0.0, 0.0), (0.25, 0.5)),
+        (Tuple::point(0.0, 0.0, 1.0), (0.5, 0.5)),
+        (Tuple::point(-1.0, 0.0, 0.0), (0.75, 0.5)),
+        (Tuple::point(0.0, 1.0, 0.0), (0.5, 1.0)),
+        (Tuple::point(0.0, -1.0, 0.0), (0.5, 0.0)),
+        (Tuple::point(root_half, root_half, 0.0), (0.25, 0.75)),
+      ];
+
+      for (point, expected) in cases {
+        assert_uv(spherical_map(&point), expected);
+      }
+    }
+
+    #[test]
+    fn spherical_direction_inverts_the_spherical_mapping() {
+      for (u, v) in [(0.0, 0.5), (0.25, 0.75), (0.6, 0.1), (0.875, 0.3)] {
+        let direction = spherical_direction(u, v);
+
+        assert_feq!(direction.clone().magnitude(), 1.0);
+        assert_uv(spherical_map(&(Tuple::origin() + direction)), (u, v));
+      }
+    }
+  }
+
+  mod planar_tests {
+    use super::*;
+
+    #[test]
+    fn using_a_planar_mapping_on_a_3d_point() {
+      let cases = [
+        (Tuple::point(0.25, 0.0, 0.5), (0.25, 0.5)),
+        (Tuple::point(0.25, 0.0, -0.25), (0.25, 0.75)),
+        (Tuple::point(0.25, 0.5, -0.25), (0.25, 0.75)),
+        (Tuple::point(1.25, 0.0, 0.5), (0.25, 0.5)),
+        (Tuple::point(0.25, 0.0, -1.75), (0.25, 0.25)),
+        (Tuple::point(1.0, 0.0, -1.0), (0.0, 0.0)),
+        (Tuple::point(0.0, 0.0, 0.0), (0.0, 0.0)),
+      ];
+
+      for (point, expected) in cases {
+        assert_uv(planar_map(&point), expected);
+      }
+    }
+  }
+
+  mod cylindrical_tests {
+    use super::*;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn using_a_cylindrical_mapping_on_a_3d_point() {
+      let r = FRAC_1_SQRT_2;
+      let cases = [
+        (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.0)),
+        (Tuple::point(0.0, 0.5, -1.0), (0.0, 0.5)),
+        (Tuple::point(0.0, 1.0, -1.0), (0.0, 0.0)),
+        (Tuple::point(r, 0.5, -r), (0.125, 0.5)),
+        (Tuple::point(1.0, 0.5, 0.0), (0.25, 0.5)),
+        (Tuple::point(r, 0.5, r), (0.375, 0.5)),
+        (Tuple::point(0.0, -0.25, 1.0), (0.5, 0.75)),
+        (Tuple::point(-r, 0.5, r), (0.625, 0.5)),
+        (Tuple::point(-1.0, 1.25, 0.0), (0.75, 0.25)),
+        (Tuple::point(-r, 0.5, -r), (0.875, 0.5)),
+      ];
+
+      for (point, expected) in cases {
+        assert_uv(cylindrical_map(&point), expected);
+      }
+    }
+  }
+
+  mod cube_tests {
+    use super::*;
+
+    #[test]
+    fn identifying_the_face_of_a_cube_from_a_point() {
+      let cases = [
+        (Tuple::point(-1.0, 0.5, -0.25), CubeFace::Left),
+        (Tuple::point(1.1, -0.75, 0.8), CubeFace::Right),
+        (Tuple::point(0.1, 0.6, 0.9), CubeFace::Front),
+        (Tuple::point(-0.7, 0.0, -2.0), CubeFace::Back),
+        (Tuple::point(0.5, 1.0, 0.9), CubeFace::Up),
+        (Tuple::point(-0.2, -1.3, 1.1), CubeFace::Down),
+      ];
+
+      for (point, expected) in cases {
+        assert_eq!(CubeFace::from_point(&point), expected);
+      }
+    }
+
+    #[test]
+    fn uv_mapping_the_faces_of_a_cube() {
+      let cases = [
+        (CubeFace::Front, Tuple::point(-0.5, 0.5, 1.0), (0.25, 0.75)),
+        (CubeFace::Front, Tuple::point(0.5, -0.5, 1.0), (0.75, 0.25)),
+        (CubeFace::Back, Tuple::point(0.5, 0.5, -1.0), (0.25, 0.75)),
+        (CubeFace::Back, Tuple::point(-0.5, -0.5, -1.0), (0.75, 0.25)),
+        (CubeFace::Left, Tuple::point(-1.0, 0.5, -0.5), (0.25, 0.75)),
+        (CubeFace::Left, Tuple::point(-1.0, -0.5, 0.5), (0.75, 0.25)),
+        (CubeFace::Right, Tuple::point(1.0, 0.5, 0.5), (0.25, 0.75)),
+        (CubeFace::Right, Tuple::point(1.0, -0.5, -0.5), (0.75, 0.25)),
+        (CubeFace::Up, Tuple::point(-0.5, 1.0, -0.5), (0.25, 0.75)),
+        (CubeFace::Up, Tuple::point(0.5, 1.0, 0.5), (0.75, 0.25)),
+        (CubeFace::Down, Tuple::point(-0.5, -1.0, 0.5), (0.25, 0.75)),
+        (CubeFace::Down, Tuple::point(0.5, -1.0, -0.5), (0.75, 0.25)),
+      ];
+
+      for (face, point, expected) in cases {
+        assert_uv(face.uv(&point), expected);
+      }
+    }
+
+    #[test]
+    fn the_cube_mapping_places_each_face_in_its_atlas_cell() {
+      let front = UvMapping::Cube.map(&Tuple::point(0.0, 0.0, 1.0));
+      let up = UvMapping::Cube.map(&Tuple::point(0.0, 1.0, 0.0));
+      let back = UvMapping::Cube.map(&Tuple::point(0.0, 0.0, -1.0));
+
+      assert_uv(front, (1.5 / 4.0, 1.5 / 3.0));
+      assert_uv(up, (1.5 / 4.0, 2.5 / 3.0));
+      assert_uv(back, (3.5 / 4.0, 1.5 / 3.0));
+    }
+  }
+}